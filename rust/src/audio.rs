@@ -0,0 +1,133 @@
+use crate::error::Failed;
+use crate::signal::graph::{Graph, SignalRef};
+use crate::signal::program::{Input as PInput, Parameters, Program};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Play a signal to the system's default audio output device in real time,
+/// blocking the calling thread until playback finishes.
+///
+/// Pulls buffers from [`Program::render_into`] one at a time, clamping
+/// samples to the -1.0..=1.0 range. A note's gate releases after one
+/// second, regardless of `--tempo` and `--gate`. When `do_loop` is set the
+/// graph is recompiled and restarted from silence each time the note
+/// finishes, instead of stopping after a single pass. Recompiling happens on a
+/// dedicated background thread, not the real-time audio callback: `compile`
+/// walks and allocates the whole node graph, and on failure logs through a
+/// blocking `error!`, neither of which is safe to do with a hard deadline.
+pub fn play(
+    graph: Graph,
+    signal: SignalRef,
+    parameters: Parameters,
+    note: f32,
+    do_loop: bool,
+) -> Result<(), Failed> {
+    let host = cpal::default_host();
+    let device = match host.default_output_device() {
+        Some(device) => device,
+        None => {
+            error!("no audio output device available");
+            return Err(Failed);
+        }
+    };
+    let mut program = match graph.compile(signal, &parameters) {
+        Ok(program) => program,
+        Err(e) => {
+            error!("could not create program: {}", e);
+            return Err(Failed);
+        }
+    };
+    let config = cpal::StreamConfig {
+        channels: 1,
+        sample_rate: cpal::SampleRate(parameters.sample_rate as u32),
+        buffer_size: cpal::BufferSize::Fixed(parameters.buffer_size as u32),
+    };
+    let end = parameters.sample_rate as usize;
+    let mut pos: usize = 0;
+    let finished = Arc::new(AtomicBool::new(false));
+    let callback_finished = finished.clone();
+
+    // Background compiler thread: the callback below sends a request each
+    // time it needs a fresh `Program` and keeps rendering silence until the
+    // finished result comes back, instead of calling `graph.compile` itself.
+    let (request_tx, request_rx) = mpsc::channel::<()>();
+    let (program_tx, program_rx) = mpsc::channel::<Program>();
+    let mut awaiting_recompile = false;
+    if do_loop {
+        let compile_finished = finished.clone();
+        thread::spawn(move || {
+            for () in request_rx.iter() {
+                match graph.compile(signal, &parameters) {
+                    Ok(p) => {
+                        if program_tx.send(p).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("could not restart program: {}", e);
+                        compile_finished.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    let stream = device.build_output_stream(
+        &config,
+        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            if let Ok(p) = program_rx.try_recv() {
+                program = p;
+                pos = 0;
+                awaiting_recompile = false;
+            }
+            let gate = if pos < end && end - pos < parameters.buffer_size {
+                Some(end - pos)
+            } else {
+                None
+            };
+            let n = program.render_into(data, &PInput { gate, note });
+            if n > 0 {
+                pos += n;
+                for sample in data[..n].iter_mut() {
+                    *sample = sample.max(-1.0).min(1.0);
+                }
+            }
+            if n < data.len() {
+                for sample in data[n..].iter_mut() {
+                    *sample = 0.0;
+                }
+                if do_loop {
+                    if !awaiting_recompile {
+                        awaiting_recompile = true;
+                        let _ = request_tx.send(());
+                    }
+                } else {
+                    callback_finished.store(true, Ordering::Relaxed);
+                }
+            }
+        },
+        move |err| {
+            error!("audio stream error: {}", err);
+        },
+    );
+    let stream = match stream {
+        Ok(stream) => stream,
+        Err(e) => {
+            error!("could not open audio stream: {}", e);
+            return Err(Failed);
+        }
+    };
+    if let Err(e) = stream.play() {
+        error!("could not start audio stream: {}", e);
+        return Err(Failed);
+    }
+    while !finished.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
@@ -1,16 +1,23 @@
-use crate::consolelogger::ConsoleLogger;
-use crate::error::Failed;
+use crate::audio;
+use crate::consolelogger::{self, ConsoleLogger};
+use crate::error::{Applicability, Diagnostic, ErrorHandler, Failed, Severity, Suggestion};
+use crate::evaluate;
 use crate::evaluate::evaluate_program;
+use crate::jsonlogger::JsonLogger;
 use crate::note::Note;
+use crate::output;
 use crate::parseargs::{Arg, Args, UsageError};
-use crate::parser::{ParseResult, Parser};
+use crate::parser::Parser;
 use crate::shell::quote_os;
+use crate::signal::dot::to_dot;
 use crate::signal::graph::{Graph, SignalRef};
-use crate::signal::program::{Input as PInput, Parameters, Program};
+use crate::signal::preset;
+use crate::signal::program::{Input as PInput, Parameters};
 use crate::token::Tokenizer;
 use crate::wave;
 use std::env;
 use std::ffi::{OsStr, OsString};
+use std::cmp::min;
 use std::fs;
 use std::io::{stdout, Error as IOError, Read, Write};
 use std::path::PathBuf;
@@ -21,6 +28,13 @@ const MAX_SAMPLE_RATE: u32 = 192000;
 const DEFAULT_BUFFER_SIZE: usize = 1024;
 const MIN_BUFFER_SIZE: usize = 32;
 const MAX_BUFFER_SIZE: usize = 8192;
+/// Default oversampling factor; 1 disables oversampling.
+const DEFAULT_OVERSAMPLE: usize = 1;
+const MAX_OVERSAMPLE: usize = 64;
+/// Default tempo for `--notes` sequences, in beats per minute.
+const DEFAULT_TEMPO: f32 = 120.0;
+/// Default fraction of a step's duration that the gate stays on for.
+const DEFAULT_GATE_FRACTION: f32 = 0.5;
 
 #[derive(Debug, Clone)]
 pub enum Input {
@@ -34,10 +48,33 @@ pub struct File {
     pub output_wave: Option<OsString>,
 }
 
+/// Selectable rendering for diagnostics reported while running a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// ANSI-colored text with a source snippet, aimed at a terminal.
+    Console,
+    /// Newline-delimited JSON records, for editors, LSP front-ends, and CI
+    /// annotators.
+    Json,
+}
+
+impl std::str::FromStr for ErrorFormat {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Ok(match s {
+            "console" => ErrorFormat::Console,
+            "json" => ErrorFormat::Json,
+            _ => return Err(()),
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Command {
     pub files: Vec<File>,
+    pub repl: bool,
     pub play: bool,
+    pub disable_audio: bool,
     pub notes: Option<Vec<Note>>,
     pub tempo: Option<f32>,
     pub gate: Option<f32>,
@@ -46,8 +83,22 @@ pub struct Command {
     pub verbose: bool,
     pub dump_syntax: bool,
     pub dump_graph: bool,
+    pub dump_dot: bool,
+    pub dump_preset: bool,
     pub sample_rate: Option<u32>,
     pub buffer_size: Option<usize>,
+    pub oversample: Option<usize>,
+    pub format: Option<output::Format>,
+    pub endian: Option<output::Endian>,
+    pub stdout: bool,
+    /// Exit with a non-zero status if any diagnostic reaches this severity
+    /// or higher, even though it wasn't otherwise fatal (e.g. a warning).
+    pub max_severity: Option<Severity>,
+    /// Apply every [`Applicability::MachineApplicable`](crate::error::Applicability::MachineApplicable)
+    /// suggestion back into the input file after running it.
+    pub fix: bool,
+    /// How to render diagnostics; defaults to [`ErrorFormat::Console`].
+    pub error_format: Option<ErrorFormat>,
 }
 
 fn parse_notes(arg: &str) -> Option<Vec<Note>> {
@@ -58,6 +109,59 @@ fn parse_notes(arg: &str) -> Option<Vec<Note>> {
     Some(result)
 }
 
+/// Wraps whichever [`ErrorHandler`] `-error-format` selects, to
+/// additionally collect every [`Applicability::MachineApplicable`]
+/// suggestion emitted during a run, for `--fix` to apply once the run
+/// finishes.
+struct FixCollector<'a> {
+    inner: Box<dyn ErrorHandler + 'a>,
+    suggestions: Vec<Suggestion>,
+}
+
+impl<'a> FixCollector<'a> {
+    fn new(format: ErrorFormat, filename: &'a str, text: &'a [u8]) -> Self {
+        let inner: Box<dyn ErrorHandler + 'a> = match format {
+            ErrorFormat::Console => Box::new(ConsoleLogger::from_text(filename, text)),
+            ErrorFormat::Json => Box::new(JsonLogger::from_text(filename, text)),
+        };
+        FixCollector {
+            inner,
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+impl<'a> ErrorHandler for FixCollector<'a> {
+    fn handle(&mut self, diagnostic: &Diagnostic) {
+        for suggestion in diagnostic.suggestions.iter() {
+            if suggestion.applicability == Applicability::MachineApplicable {
+                self.suggestions.push(suggestion.clone());
+            }
+        }
+        self.inner.handle(diagnostic);
+    }
+}
+
+/// Apply `suggestions` to `text`, right-to-left by span so earlier
+/// replacements don't shift the byte offsets later ones were computed
+/// against. A suggestion whose span overlaps one already applied is
+/// dropped rather than risking a corrupt rewrite.
+fn apply_fixes(text: &[u8], mut suggestions: Vec<Suggestion>) -> Vec<u8> {
+    suggestions.sort_unstable_by_key(|s| std::cmp::Reverse(s.span.start.0));
+    let mut result = text.to_vec();
+    let mut applied_start = u32::max_value();
+    for suggestion in suggestions {
+        let start = suggestion.span.start.0 as usize;
+        let end = suggestion.span.end.0 as usize;
+        if suggestion.span.end.0 > applied_start {
+            continue;
+        }
+        result.splice(start..end, suggestion.replacement.into_bytes());
+        applied_start = suggestion.span.start.0;
+    }
+    result
+}
+
 fn unwrap_write<T>(filename: &str, result: Result<T, IOError>) -> Result<T, Failed> {
     match result {
         Ok(x) => Ok(x),
@@ -71,10 +175,12 @@ fn unwrap_write<T>(filename: &str, result: Result<T, IOError>) -> Result<T, Fail
 impl Command {
     pub fn from_args(args: env::ArgsOs) -> Result<Command, UsageError> {
         let mut inputs = Vec::new();
+        let mut repl = false;
         let mut script = None;
         let mut do_write_wave = false;
         let mut wave_file = None;
         let mut play = false;
+        let mut disable_audio = false;
         let mut notes = None;
         let mut tempo = None;
         let mut gate = None;
@@ -83,8 +189,17 @@ impl Command {
         let mut verbose = false;
         let mut dump_syntax = false;
         let mut dump_graph = false;
+        let mut dump_dot = false;
+        let mut dump_preset = false;
         let mut sample_rate = None;
         let mut buffer_size = None;
+        let mut oversample = None;
+        let mut format = None;
+        let mut endian = None;
+        let mut to_stdout = false;
+        let mut max_severity = None;
+        let mut fix = false;
+        let mut error_format = None;
         let mut args = Args::from_args(args);
         loop {
             args = match args.next()? {
@@ -107,6 +222,14 @@ impl Command {
                         play = true;
                         option.no_value()?.1
                     }
+                    "disable-audio" => {
+                        disable_audio = true;
+                        option.no_value()?.1
+                    }
+                    "repl" => {
+                        repl = true;
+                        option.no_value()?.1
+                    }
                     "notes" => {
                         let (_, value, rest) = option.parse_str(parse_notes)?;
                         notes = Some(value);
@@ -142,6 +265,14 @@ impl Command {
                         dump_graph = true;
                         option.no_value()?.1
                     }
+                    "dump-dot" => {
+                        dump_dot = true;
+                        option.no_value()?.1
+                    }
+                    "dump-preset" => {
+                        dump_preset = true;
+                        option.no_value()?.1
+                    }
                     "sample-rate" => {
                         let (_, value, rest) = option.parse_str(|s| s.parse::<u32>().ok())?;
                         sample_rate = Some(value);
@@ -152,6 +283,46 @@ impl Command {
                         buffer_size = Some(value);
                         rest
                     }
+                    "oversample" => {
+                        let (_, value, rest) = option.parse_str(|s| s.parse::<usize>().ok())?;
+                        oversample = Some(value);
+                        rest
+                    }
+                    "format" => {
+                        let (_, value, rest) =
+                            option.parse_str(|s| s.parse::<output::Format>().ok())?;
+                        format = Some(value);
+                        rest
+                    }
+                    "endian" => {
+                        let (_, value, rest) =
+                            option.parse_str(|s| s.parse::<output::Endian>().ok())?;
+                        endian = Some(value);
+                        rest
+                    }
+                    "stdout" => {
+                        to_stdout = true;
+                        option.no_value()?.1
+                    }
+                    "max-severity" => {
+                        let (_, value, rest) = option.parse_str(|s| s.parse::<Severity>().ok())?;
+                        max_severity = Some(value);
+                        rest
+                    }
+                    "deny-warnings" => {
+                        max_severity = Some(Severity::Warning);
+                        option.no_value()?.1
+                    }
+                    "fix" => {
+                        fix = true;
+                        option.no_value()?.1
+                    }
+                    "error-format" => {
+                        let (_, value, rest) =
+                            option.parse_str(|s| s.parse::<ErrorFormat>().ok())?;
+                        error_format = Some(value);
+                        rest
+                    }
                     "script" => {
                         let (_, value, rest) = option.value_str()?;
                         script = Some(value);
@@ -161,6 +332,18 @@ impl Command {
                 },
             };
         }
+        if repl && (script.is_some() || !inputs.is_empty()) {
+            return Err(UsageError::Custom {
+                text: "cannot specify both -repl and an input".to_string(),
+            });
+        }
+        if to_stdout && format.map_or(false, output::Format::needs_seek) {
+            return Err(UsageError::Custom {
+                text: "-format wav needs a seekable file, and cannot be used with -stdout; \
+                       use -format raw-f32 or -format raw-i16"
+                    .to_string(),
+            });
+        }
         let mut files = Vec::new();
         match script {
             Some(input) => {
@@ -177,7 +360,7 @@ impl Command {
                 files.push(File { input, output_wave });
             }
             None => {
-                if inputs.is_empty() {
+                if inputs.is_empty() && !repl {
                     return Err(UsageError::Custom {
                         text: format!("no inputs"),
                     });
@@ -224,7 +407,9 @@ impl Command {
         }
         Ok(Command {
             files,
+            repl,
             play,
+            disable_audio,
             notes,
             tempo,
             gate,
@@ -233,23 +418,53 @@ impl Command {
             verbose,
             dump_syntax,
             dump_graph,
+            dump_dot,
+            dump_preset,
             sample_rate,
             buffer_size,
+            oversample,
+            format,
+            endian,
+            stdout: to_stdout,
+            max_severity,
+            fix,
+            error_format,
         })
     }
 
     pub fn run(&self) -> Result<(), Failed> {
+        if self.repl {
+            let mut err_handler = ConsoleLogger::from_text("<repl>", &[]);
+            evaluate::run_repl(&mut err_handler);
+            return Ok(());
+        }
         for file in self.files.iter() {
             self.run_file(file)?;
         }
-        Ok(())
+        self.check_max_severity()
+    }
+
+    /// Fail the run if `--max-severity`/`--deny-warnings` is set and some
+    /// diagnostic reached it, even though nothing else made the run fail
+    /// outright (e.g. a warning about an unused top-level form).
+    fn check_max_severity(&self) -> Result<(), Failed> {
+        match (self.max_severity, consolelogger::max_severity_seen()) {
+            (Some(max), Some(seen)) if seen >= max => {
+                error!(
+                    "a {} diagnostic was reported, which -max-severity {} forbids",
+                    seen, max
+                );
+                Err(Failed)
+            }
+            _ => Ok(()),
+        }
     }
 
     fn run_file(&self, file: &File) -> Result<(), Failed> {
         let (filename, text) = self.read_input(file)?;
-        let mut err_handler = ConsoleLogger::from_text(filename.as_ref(), text.as_ref());
+        let format = self.error_format.unwrap_or(ErrorFormat::Console);
+        let mut err_handler = FixCollector::new(format, filename.as_ref(), text.as_ref());
         let exprs = {
-            let mut exprs = Vec::new();
             let mut toks = match Tokenizer::new(text.as_ref()) {
                 Ok(toks) => toks,
                 Err(e) => {
@@ -258,22 +473,15 @@ impl Command {
                 }
             };
             let mut parser = Parser::new();
-            loop {
-                match parser.parse(&mut err_handler, &mut toks) {
-                    ParseResult::None => break,
-                    ParseResult::Incomplete => {
-                        parser.finish(&mut err_handler);
-                        break;
-                    }
-                    ParseResult::Error => return Err(Failed),
-                    ParseResult::Value(expr) => {
-                        if self.dump_syntax {
-                            eprintln!("Syntax: {}", expr.print());
-                        }
-                        exprs.push(expr);
-                    }
+            let exprs = parser.parse_all(&mut err_handler, &mut toks);
+            if self.dump_syntax {
+                for expr in exprs.iter() {
+                    eprintln!("Syntax: {}", expr.print());
                 }
             }
+            if parser.error_count() > 0 {
+                return Err(Failed);
+            }
             exprs
         };
         let (graph, root) = evaluate_program(&mut err_handler, exprs.as_ref())?;
@@ -282,10 +490,29 @@ impl Command {
             graph.dump(&mut stdout);
             writeln!(&mut stdout, "root = {:?}", root).unwrap();
         }
+        if self.dump_dot {
+            let mut stdout = stdout();
+            to_dot(&graph, &mut stdout);
+        }
+        if self.dump_preset {
+            println!("{}", preset::encode_base64(&graph, root));
+        }
         match file.output_wave {
             Some(ref path) => self.write_wave(path, &graph, root)?,
             None => {}
         }
+        if self.stdout {
+            self.write_stdout(&graph, root)?;
+        }
+        if self.play {
+            self.play_audio(graph, root)?;
+        }
+        if self.fix && !err_handler.suggestions.is_empty() {
+            if let Input::File(ref path) = file.input {
+                let fixed = apply_fixes(text.as_ref(), err_handler.suggestions);
+                unwrap_write(filename.as_ref(), fs::write(path, fixed))?;
+            }
+        }
         Ok(())
     }
 
@@ -308,9 +535,13 @@ impl Command {
         }
     }
 
-    /// Write output wave file.
-    fn write_wave(&self, path: &OsStr, graph: &Graph, signal: SignalRef) -> Result<(), Failed> {
-        let filename = quote_os(path);
+    /// Resolve the effective sample rate, buffer size, and oversampling
+    /// factor from `self.sample_rate`/`self.buffer_size`/`self.oversample`,
+    /// clamping to the supported range (and rounding the buffer size to a
+    /// power of two) with a warning. Shared by [`write_wave`](Self::write_wave)
+    /// and [`play_audio`](Self::play_audio), which both compile the graph
+    /// into a [`Program`](crate::signal::program::Program) the same way.
+    fn resolve_audio_parameters(&self) -> Result<(u32, usize, usize), Failed> {
         let sample_rate = match self.sample_rate {
             Some(rate) => {
                 if rate < MIN_SAMPLE_RATE {
@@ -357,25 +588,132 @@ impl Command {
             }
             None => DEFAULT_BUFFER_SIZE,
         };
-        let note = self
-            .notes
+        let oversample = match self.oversample {
+            Some(n) => {
+                if n == 0 {
+                    warning!("oversampling factor 0 is invalid, using 1");
+                    1
+                } else if n > MAX_OVERSAMPLE {
+                    warning!(
+                        "oversampling factor {} is too high, using {}",
+                        n, MAX_OVERSAMPLE
+                    );
+                    MAX_OVERSAMPLE
+                } else {
+                    n
+                }
+            }
+            None => DEFAULT_OVERSAMPLE,
+        };
+        Ok((sample_rate, buffer_size, oversample))
+    }
+
+    /// The output format, defaulting to WAVE for a file and raw 32-bit
+    /// float for `--stdout` (WAVE needs a seekable stream to go back and
+    /// fill in its header, which a pipe can't give it; `from_args` rejects
+    /// `-format wav -stdout` up front).
+    fn resolve_format(&self, to_stdout: bool) -> output::Format {
+        self.format.unwrap_or(if to_stdout {
+            output::Format::Raw(output::RawSampleFormat::F32)
+        } else {
+            output::Format::Wav
+        })
+    }
+
+    /// Byte order for raw PCM output, defaulting to little-endian.
+    fn endian(&self) -> output::Endian {
+        self.endian.unwrap_or(output::Endian::Little)
+    }
+
+    /// The note to play, defaulting to middle C if none was given with
+    /// `--notes`.
+    fn note(&self) -> Note {
+        self.notes
             .as_ref()
             .and_then(|x| x.first().copied())
-            .unwrap_or(Note(60));
-        let program = Program::new(
-            &graph,
-            signal,
-            &Parameters {
-                sample_rate: sample_rate as f64,
-                buffer_size,
-            },
-        );
-        let mut program = match program {
-            Ok(p) => p,
-            Err(e) => {
-                error!("could not create program: {}", e);
-                return Err(Failed);
+            .unwrap_or(Note(60))
+    }
+
+    /// The sequence of notes to step through, defaulting to a single
+    /// middle C if `--notes` wasn't given.
+    fn notes(&self) -> Vec<Note> {
+        self.notes.clone().unwrap_or_else(|| vec![Note(60)])
+    }
+
+    /// Length of a `--tempo` step, and of the gate within it, in samples.
+    /// Each step lasts one beat (`60 / tempo` seconds); the gate stays on
+    /// for `--gate` (clamped to `0.0..=1.0`) of that step, then releases
+    /// for the remainder so the envelope can retrigger on the next step.
+    fn step_timing(&self, sample_rate: u32) -> (usize, usize) {
+        let tempo = self.tempo.unwrap_or(DEFAULT_TEMPO);
+        let gate_fraction = self.gate.unwrap_or(DEFAULT_GATE_FRACTION).max(0.0).min(1.0);
+        let step_len = (((60.0 / tempo) * sample_rate as f32) as usize).max(1);
+        let gate_len = ((step_len as f32) * gate_fraction) as usize;
+        (step_len, gate_len)
+    }
+
+    /// Render `self.notes()` at `self.tempo` into `sink`, looping the whole
+    /// sequence if `self.do_loop`. Compiles a fresh program for each note so
+    /// its envelope always starts from silence -- there's no mechanism yet
+    /// to retrigger one already-compiled program's envelope mid-flight.
+    /// `context` names the destination for error messages.
+    fn render_notes(
+        &self,
+        graph: &Graph,
+        signal: SignalRef,
+        parameters: &Parameters,
+        step_len: usize,
+        gate_len: usize,
+        sink: &mut dyn output::Sink,
+        context: &str,
+    ) -> Result<(), Failed> {
+        let notes = self.notes();
+        loop {
+            for &note in notes.iter() {
+                let mut program = match graph.compile(signal, parameters) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        error!("could not create program: {}", e);
+                        return Err(Failed);
+                    }
+                };
+                let mut pos: usize = 0;
+                while pos < step_len {
+                    let output = program.render(&PInput {
+                        gate: if pos < gate_len && gate_len - pos < parameters.buffer_size {
+                            Some(gate_len - pos)
+                        } else {
+                            None
+                        },
+                        note: note.0 as f32,
+                    });
+                    let output = match output {
+                        Some(x) => x,
+                        None => break,
+                    };
+                    let take = min(output.len(), step_len - pos);
+                    unwrap_write(context, sink.write(&output[..take]))?;
+                    pos += take;
+                }
             }
+            if !self.do_loop {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write rendered audio to a file, in `self.resolve_format` (WAVE by
+    /// default).
+    fn write_wave(&self, path: &OsStr, graph: &Graph, signal: SignalRef) -> Result<(), Failed> {
+        let filename = quote_os(path);
+        let (sample_rate, buffer_size, oversample) = self.resolve_audio_parameters()?;
+        let format = self.resolve_format(false);
+        let (step_len, gate_len) = self.step_timing(sample_rate);
+        let parameters = Parameters {
+            sample_rate: sample_rate as f64,
+            buffer_size,
+            oversample,
         };
         let mut file = match fs::File::create(&path) {
             Ok(file) => file,
@@ -384,32 +722,114 @@ impl Command {
                 return Err(Failed);
             }
         };
-        let mut writer = wave::Writer::from_stream(
-            &mut file,
-            &wave::Parameters {
-                channel_count: 1,
+        let mut sink: Box<dyn output::Sink> = match format {
+            output::Format::Wav => Box::new(output::WaveSink::new(
+                &mut file,
                 sample_rate,
-            },
-        );
-        let mut pos: usize = 0;
-        let end = sample_rate as usize;
-        loop {
-            let output = program.render(&PInput {
-                gate: if pos < end && end - pos < buffer_size {
+                &wave::Parameters {
+                    channel_count: 1,
+                    sample_rate,
+                    sample_format: wave::SampleFormat::Pcm16,
+                    container: wave::Container::Wave,
+                    dither_mode: wave::DitherMode::Rectangular,
+                    noise_shaping: false,
+                },
+            )),
+            output::Format::Raw(fmt) => {
+                Box::new(output::RawSink::new(&mut file, fmt, self.endian()))
+            }
+        };
+        self.render_notes(
+            graph,
+            signal,
+            &parameters,
+            step_len,
+            gate_len,
+            sink.as_mut(),
+            &filename,
+        )?;
+        unwrap_write(&filename, sink.finish())?;
+        unwrap_write(&filename, file.sync_all())
+    }
+
+    /// Write rendered audio as headerless raw PCM to standard output, for
+    /// piping into other tools. `-format wav` is rejected for this sink in
+    /// `from_args`, since WAVE needs a seekable stream to fill in its header.
+    fn write_stdout(&self, graph: &Graph, signal: SignalRef) -> Result<(), Failed> {
+        let (sample_rate, buffer_size, oversample) = self.resolve_audio_parameters()?;
+        let fmt = match self.resolve_format(true) {
+            output::Format::Raw(fmt) => fmt,
+            output::Format::Wav => unreachable!("-format wav -stdout is rejected in from_args"),
+        };
+        let (step_len, gate_len) = self.step_timing(sample_rate);
+        let parameters = Parameters {
+            sample_rate: sample_rate as f64,
+            buffer_size,
+            oversample,
+        };
+        let mut out = stdout();
+        let mut sink = output::RawSink::new(&mut out, fmt, self.endian());
+        self.render_notes(
+            graph,
+            signal,
+            &parameters,
+            step_len,
+            gate_len,
+            &mut sink,
+            "<stdout>",
+        )?;
+        unwrap_write("<stdout>", Box::new(sink).finish())
+    }
+
+    /// Play to the default audio output device, looping according to
+    /// `self.do_loop` until interrupted. If `--disable-audio` was given,
+    /// drive the same render loop without opening a device, as a dry run.
+    fn play_audio(&self, graph: Graph, signal: SignalRef) -> Result<(), Failed> {
+        let (sample_rate, buffer_size, oversample) = self.resolve_audio_parameters()?;
+        let note = self.note();
+        let parameters = Parameters {
+            sample_rate: sample_rate as f64,
+            buffer_size,
+            oversample,
+        };
+        if self.disable_audio {
+            let mut program = match graph.compile(signal, &parameters) {
+                Ok(p) => p,
+                Err(e) => {
+                    error!("could not create program: {}", e);
+                    return Err(Failed);
+                }
+            };
+            let mut pos: usize = 0;
+            let end = sample_rate as usize;
+            loop {
+                let gate = if pos < end && end - pos < buffer_size {
                     Some(end - pos)
                 } else {
                     None
-                },
-                note: note.0 as f32,
-            });
-            let output = match output {
-                Some(x) => x,
-                None => break,
-            };
-            pos += output.len();
-            unwrap_write(&filename, writer.write(output))?;
+                };
+                match program.render(&PInput {
+                    gate,
+                    note: note.0 as f32,
+                }) {
+                    Some(output) => pos += output.len(),
+                    None => {
+                        if !self.do_loop {
+                            break;
+                        }
+                        program = match graph.compile(signal, &parameters) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                error!("could not create program: {}", e);
+                                return Err(Failed);
+                            }
+                        };
+                        pos = 0;
+                    }
+                }
+            }
+            return Ok(());
         }
-        unwrap_write(&filename, writer.finish())?;
-        unwrap_write(&filename, file.sync_all())
+        audio::play(graph, signal, parameters, note.0 as f32, self.do_loop)
     }
 }
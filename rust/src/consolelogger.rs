@@ -1,11 +1,11 @@
 use crate::color::{Style, StyleFlag};
-use crate::error::{ErrorHandler, Severity};
-use crate::sourcepos::Span;
-use crate::sourceprint::write_source;
+use crate::error::{Diagnostic, ErrorHandler, Severity};
+use crate::sourceprint::{write_source, write_suggestion, Label};
 use crate::sourcetext::SourceText;
 use std::fmt::Arguments;
 use std::io;
 use std::io::{stderr, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
 
 const MESSAGE: Style<'static> = Style(&[StyleFlag::FgBrightWhite]);
 const RESET: Style<'static> = Style(&[StyleFlag::Reset]);
@@ -14,15 +14,39 @@ const RESET: Style<'static> = Style(&[StyleFlag::Reset]);
 fn severity_color(severity: Severity) -> Style<'static> {
     use Severity::*;
     Style(match severity {
+        Help => &[StyleFlag::FgGreen, StyleFlag::Bold],
+        Note => &[StyleFlag::FgCyan, StyleFlag::Bold],
         Warning => &[StyleFlag::FgYellow, StyleFlag::Bold],
         Error => &[StyleFlag::FgRed, StyleFlag::Bold],
     })
 }
 
+// Highest severity emitted so far through `_print_diagnostic`, a
+// `ConsoleLogger`, or a `JsonLogger`, stored as `severity as u8 + 1` (0 means "none yet") so
+// `--max-severity`/`--deny-warnings` can decide the process exit code
+// without threading a diagnostic count through every call site.
+static MAX_SEVERITY_SEEN: AtomicU8 = AtomicU8::new(0);
+
+pub(crate) fn record_severity(severity: Severity) {
+    MAX_SEVERITY_SEEN.fetch_max(severity as u8 + 1, Ordering::Relaxed);
+}
+
+/// The highest-severity diagnostic emitted so far, if any.
+pub fn max_severity_seen() -> Option<Severity> {
+    Some(match MAX_SEVERITY_SEEN.load(Ordering::Relaxed) {
+        0 => return None,
+        1 => Severity::Help,
+        2 => Severity::Note,
+        3 => Severity::Warning,
+        _ => Severity::Error,
+    })
+}
+
 // FIXME: Seems like we could combine these functions, but str is ?Sized.
 
 /// Write a diagnostic message to a stream.
 pub fn write_diagnostic(w: &mut impl Write, severity: Severity, msg: &str) -> io::Result<()> {
+    record_severity(severity);
     writeln!(
         w,
         "{}{}{}: {}{}",
@@ -36,6 +60,7 @@ pub fn write_diagnostic(w: &mut impl Write, severity: Severity, msg: &str) -> io
 
 /// Print a diagnostic message to stderr.
 pub fn _print_diagnostic(severity: Severity, args: Arguments) {
+    record_severity(severity);
     let stderr = stderr();
     let mut handle = stderr.lock();
     writeln!(
@@ -87,13 +112,26 @@ impl<'a> ConsoleLogger<'a> {
 }
 
 impl<'a> ErrorHandler for ConsoleLogger<'a> {
-    fn handle(&mut self, pos: Span, message: &str) {
+    fn handle(&mut self, diagnostic: &Diagnostic) {
         self.init();
         let source_text = self.text.as_ref().unwrap();
         let mut stderr = stderr();
-        write_diagnostic(&mut stderr, Severity::Error, message).unwrap();
-        if let Some(text_pos) = source_text.span(pos) {
-            write_source(&mut stderr, &source_text, &text_pos).unwrap();
+        write_diagnostic(&mut stderr, diagnostic.severity, diagnostic.message).unwrap();
+        if let Some(text_pos) = source_text.span(diagnostic.pos) {
+            let labels: Vec<Label> = diagnostic
+                .labels
+                .iter()
+                .filter_map(|label| {
+                    Some(Label {
+                        span: source_text.span(label.span)?,
+                        text: label.message,
+                    })
+                })
+                .collect();
+            write_source(&mut stderr, &source_text, &text_pos, &labels).unwrap();
+        }
+        for suggestion in diagnostic.suggestions.iter() {
+            write_suggestion(&mut stderr, &source_text, suggestion).unwrap();
         }
         writeln!(stderr).unwrap();
     }
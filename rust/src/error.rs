@@ -1,14 +1,19 @@
 use crate::sourcepos::Span;
 use std::fmt;
+use std::str::FromStr;
 
 // An object that handles errors during parsing or evaluation.
 pub trait ErrorHandler {
-    fn handle(&mut self, pos: Span, message: &str);
+    fn handle(&mut self, diagnostic: &Diagnostic);
 }
 
-/// Serevrity level for diagnostic messages.
+/// Severity level for diagnostic messages, from least to most severe so
+/// that callers can filter with e.g. `severity >= Severity::Warning`.
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
 pub enum Severity {
+    Help,
+    Note,
+    Warning,
     Error,
 }
 
@@ -16,7 +21,83 @@ impl fmt::Display for Severity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Severity::*;
         f.write_str(match *self {
+            Help => "help",
+            Note => "note",
+            Warning => "warning",
             Error => "error",
         })
     }
 }
+
+impl FromStr for Severity {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Ok(match s {
+            "help" => Severity::Help,
+            "note" => Severity::Note,
+            "warning" => Severity::Warning,
+            "error" => Severity::Error,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// How safe a [`Suggestion`] is to apply without a human looking at it,
+/// borrowed from the same idea most lint tools use to gate their `--fix`
+/// mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The replacement is definitely correct; automated tools like
+    /// `--fix` may apply it unattended.
+    MachineApplicable,
+    /// The replacement is a plausible guess -- e.g. the closest known name
+    /// to a typo -- and should be reviewed before applying.
+    MaybeIncorrect,
+}
+
+/// A suggested fix attached to a diagnostic: replace the source text at
+/// `span` with `replacement`. `applicability` says whether it is safe to
+/// apply unattended.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// A secondary span attached to a [`Diagnostic`], labeled with its own
+/// message -- e.g. "previously defined here" pointing back at an earlier
+/// declaration while the diagnostic's primary span underlines the
+/// conflicting use.
+#[derive(Debug, Clone, Copy)]
+pub struct SecondaryLabel<'a> {
+    pub span: Span,
+    pub message: &'a str,
+}
+
+/// The severity/message/span/labels/suggestions passed to a single
+/// [`ErrorHandler::handle`] call, bundled up so an [`ErrorHandler`] can
+/// render it however it likes -- human-readable source snippets, a
+/// machine-readable record, or anything else -- without every
+/// implementation juggling the same parameters separately.
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a> {
+    pub pos: Span,
+    pub severity: Severity,
+    pub message: &'a str,
+    pub labels: &'a [SecondaryLabel<'a>],
+    pub suggestions: &'a [Suggestion],
+}
+
+impl<'a> Diagnostic<'a> {
+    /// A bare diagnostic with no secondary labels or suggestions.
+    pub fn new(pos: Span, severity: Severity, message: &'a str) -> Self {
+        Diagnostic {
+            pos,
+            severity,
+            message,
+            labels: &[],
+            suggestions: &[],
+        }
+    }
+}
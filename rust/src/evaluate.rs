@@ -1,4 +1,4 @@
-use crate::error::ErrorHandler;
+use crate::error::{Diagnostic, ErrorHandler, Severity};
 use crate::sexpr::SExpr;
 use crate::signal::graph::{Graph, SignalRef};
 use crate::sourcepos::Span;
@@ -9,9 +9,12 @@ mod environment;
 
 mod builtins;
 mod envelope;
+mod repl;
 
 use environment::*;
 
+pub use repl::run as run_repl;
+
 /// Evaluate an audio synthesis program.
 pub fn evaluate_program(
     err_handler: &mut dyn ErrorHandler,
@@ -21,7 +24,7 @@ pub fn evaluate_program(
     // considered to be the output, and must produce a value.
     let (last, first) = match program.split_last() {
         None => {
-            err_handler.handle(Span::none(), "empty program");
+            err_handler.handle(&Diagnostic::new(Span::none(), Severity::Error, "empty program"));
             return None;
         }
         Some(x) => x,
@@ -32,6 +35,13 @@ pub fn evaluate_program(
             EvalResult(_, Ok(())) => (),
             EvalResult(label, Err(e)) => match e {
                 ValueError::Failed => (),
+                // A non-last top-level form that evaluates to a value (e.g.
+                // an expression whose result is never used) is suspicious
+                // but not actually broken, so it's a warning rather than a
+                // hard error -- the graph built so far is still usable.
+                ValueError::BadType { expect: Type(DataType::Void, _), .. } => {
+                    log_warning!(env, label.pos, "unused top-level form: {}", e)
+                }
                 _ => log_error!(env, label.pos, "invalid top-level statement: {}", e),
             },
         }
@@ -1,13 +1,17 @@
 use super::envelope::envelope;
 use super::environment::*;
-use crate::sexpr::SExpr;
+use crate::sexpr::{Content, SExpr};
+use crate::signal::envelope;
 use crate::signal::filter;
+use crate::signal::fm;
 use crate::signal::graph::{Node, SignalRef};
 use crate::signal::ops;
 use crate::sourcepos::{HasPos, Span};
 use crate::units::Units;
+use crate::wave;
 use std::collections::hash_map::{HashMap, RandomState};
 use std::convert::TryFrom;
+use std::fs;
 
 pub fn operators() -> HashMap<&'static str, Operator, RandomState> {
     let mut map = HashMap::new();
@@ -37,26 +41,53 @@ pub fn operators() -> HashMap<&'static str, Operator, RandomState> {
         Macro,
         "define" => define,
         "envelope" => envelope,
+        "let" => let_macro,
+        "->" => thread_macro,
+        "sample" => sample,
     );
     operators!(
         Function,
+        "=" => eq,
+        "<" => lt,
+        "<=" => le,
+        ">" => gt,
+        ">=" => ge,
+        "and" => and,
+        "or" => or,
+        "not" => not,
         "*" => multiply,
         "note" => note,
         "oscillator" => oscillator,
+        "fmOperator" => fm_operator,
+        "adsr" => adsr,
         "sawtooth" => sawtooth,
         "sine" => sine,
         "noise" => noise,
+        "gaussianNoise" => gaussian_noise,
+        "pinkNoise" => pink_noise,
+        "brownNoise" => brown_noise,
         "highPass" => high_pass,
         "lowPass2" => low_pass_2,
         "highPass2" => high_pass_2,
         "bandPass2" => band_pass_2,
         "lowPass4" => low_pass_4,
+        "notch" => notch,
+        "peak" => peak,
+        "allpass" => allpass,
+        "lowShelf" => low_shelf,
+        "highShelf" => high_shelf,
+        "filterLowPass" => filter_low_pass,
+        "filterHighPass" => filter_high_pass,
+        "filterBandPass" => filter_band_pass,
+        "filterNotch" => filter_notch,
         "saturate" => saturate,
         "rectify" => rectify,
         "frequency" => frequency,
         "mix" => mix,
         "phase-mod" => phase_mod,
         "overtone" => overtone,
+        "fm" => fm_algorithm,
+        "rateEnvelope" => rate_envelope,
     );
     map
 }
@@ -103,11 +134,159 @@ fn define<'a>(env: &mut Env<'a>, _pos: Span, args: &'a [SExpr]) -> OpResult {
     let name = name.unwrap(env);
     let value = value.evaluate(env).into_nonvoid().unwrap(env);
     let name = name?;
-    env.variables.insert(name, value);
+    env.variables.insert(Box::from(name), value);
     value?;
     Ok(Value::void())
 }
 
+/// `(let ((name expr) ...) body ...)`: evaluate each binding in turn,
+/// installing it as a variable before the next binding is evaluated (so
+/// later bindings can refer to earlier ones), evaluate the body forms with
+/// those bindings in scope, then restore whatever the names were bound to
+/// beforehand (if anything) and return the value of the last body form.
+fn let_macro<'a>(env: &mut Env<'a>, _pos: Span, args: &'a [SExpr]) -> OpResult {
+    let (bindings, body) = match args.split_first() {
+        Some(x) => x,
+        None => {
+            return Err(OpError::BadNArgs {
+                got: args.len(),
+                min: 2,
+                max: None,
+            });
+        }
+    };
+    if body.is_empty() {
+        return Err(OpError::BadNArgs {
+            got: args.len(),
+            min: 2,
+            max: None,
+        });
+    }
+    let bindings: &[SExpr] = match &bindings.content {
+        Content::List(items) => items,
+        _ => {
+            return error!(
+                env,
+                bindings.source_pos(),
+                "let bindings must be a list of (name expr) pairs"
+            );
+        }
+    };
+    let mut shadowed: Vec<(Box<str>, Option<Result<Value, Failed>>)> =
+        Vec::with_capacity(bindings.len());
+    let mut ok = true;
+    for binding in bindings.iter() {
+        let pos = binding.source_pos();
+        let (name, value) = match &binding.content {
+            Content::List(items) => match items.as_ref() {
+                [name, value] => (name, value),
+                _ => {
+                    log_error!(env, pos, "let binding must be (name expr)");
+                    ok = false;
+                    continue;
+                }
+            },
+            _ => {
+                log_error!(env, pos, "let binding must be (name expr)");
+                ok = false;
+                continue;
+            }
+        };
+        let name = match get_symbol(name) {
+            Ok(name) => name,
+            Err(e) => {
+                log_error!(env, name.source_pos(), "{}", e);
+                ok = false;
+                continue;
+            }
+        };
+        let value = env.evaluate(value).into_nonvoid().unwrap(env);
+        if value.is_err() {
+            ok = false;
+        }
+        shadowed.push((Box::from(name), env.bind_variable(Box::from(name), value)));
+    }
+    let result = if !ok {
+        Err(Failed)
+    } else {
+        let (last, init) = body.split_last().unwrap();
+        let mut init_ok = true;
+        for form in init.iter() {
+            if env.evaluate(form).into_void().unwrap(env).is_err() {
+                init_ok = false;
+            }
+        }
+        if init_ok {
+            env.evaluate(last).into_nonvoid().unwrap(env)
+        } else {
+            Err(Failed)
+        }
+    };
+    for (name, previous) in shadowed.into_iter().rev() {
+        env.unbind_variable(&name, previous);
+    }
+    match result {
+        Ok(value) => Ok(value),
+        Err(Failed) => Err(OpError::Failed),
+    }
+}
+
+/// `(-> x (f a) (g b))`: thread `x` through each following step as its first
+/// argument, so the chain reads left-to-right instead of nesting inside
+/// out, e.g. this rewrites to `(g (f x a) b)` before evaluating it. A bare
+/// symbol step `f` is treated as the call `(f x)`.
+///
+/// Each step is a fresh s-expression built by cloning the pieces it is
+/// assembled from (reusing their spans, so diagnostics still point at the
+/// original source), and leaked to satisfy the evaluator's borrowed
+/// s-expression lifetime.
+fn thread_macro<'a>(env: &mut Env<'a>, _pos: Span, args: &'a [SExpr]) -> OpResult {
+    let (first, steps) = match args.split_first() {
+        Some(x) => x,
+        None => {
+            return Err(OpError::BadNArgs {
+                got: args.len(),
+                min: 1,
+                max: None,
+            });
+        }
+    };
+    let mut acc: &'a SExpr = first;
+    for step in steps.iter() {
+        let pos = Span {
+            start: acc.source_pos().start,
+            end: step.source_pos().end,
+        };
+        let items: Vec<SExpr> = match &step.content {
+            Content::List(items) => {
+                if items.is_empty() {
+                    return error!(env, step.source_pos(), "-> step must have an operator");
+                }
+                let mut items = items.to_vec();
+                items.insert(1, acc.clone());
+                items
+            }
+            Content::Symbol(_) => vec![step.clone(), acc.clone()],
+            _ => {
+                return error!(
+                    env,
+                    step.source_pos(),
+                    "-> step must be a function call or a symbol"
+                );
+            }
+        };
+        let expr = SExpr {
+            pos,
+            content: Content::List(items.into_boxed_slice()),
+        };
+        acc = Box::leak(Box::new(expr));
+    }
+    env.evaluate(acc)
+        .into_nonvoid()
+        .unwrap(env)
+        .map_err(OpError::from)
+}
+
 // =================================================================================================
 // Functions
 // =================================================================================================
@@ -116,6 +295,91 @@ fn new_node(env: &mut Env, pos: Span, units: Units, node: impl Node + 'static) -
     Ok(Value(Data::Signal(env.new_node(pos, node)), units))
 }
 
+// =================================================================================================
+// Comparisons and booleans
+// =================================================================================================
+
+/// Compare two numeric values. The comparison is units-aware: the operands
+/// must have the same units (e.g. comparing decibels against a raw scalar
+/// is rejected), and `Int` is coerced to `Float` the same way
+/// [`Value::into_float`] does elsewhere.
+fn compare(env: &mut Env, pos: Span, args: &[EvalResult<Value>], op: fn(f64, f64) -> bool) -> OpResult {
+    parse_args!(args, lhs, rhs);
+    let lhs = lhs.into_any_float().unwrap(env);
+    let rhs = rhs.into_any_float().unwrap(env);
+    match (lhs, rhs) {
+        (Ok((lval, lunits)), Ok((rval, runits))) => {
+            if lunits != runits {
+                error!(env, pos, "cannot compare {} to {}", lunits, runits)
+            } else {
+                Ok(Value(Data::Bool(op(lval, rval)), Units::scalar()))
+            }
+        }
+        _ => Err(OpError::Failed),
+    }
+}
+
+fn eq(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    compare(env, pos, args, |a, b| a == b)
+}
+
+fn lt(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    compare(env, pos, args, |a, b| a < b)
+}
+
+fn le(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    compare(env, pos, args, |a, b| a <= b)
+}
+
+fn gt(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    compare(env, pos, args, |a, b| a > b)
+}
+
+fn ge(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    compare(env, pos, args, |a, b| a >= b)
+}
+
+/// Fold a non-empty list of boolean arguments with `combine`, starting from
+/// `identity`. All arguments are evaluated eagerly (functions here never
+/// short-circuit), so every bad argument is reported.
+fn logical(
+    env: &mut Env,
+    args: &[EvalResult<Value>],
+    identity: bool,
+    combine: fn(bool, bool) -> bool,
+) -> OpResult {
+    if args.is_empty() {
+        return Err(OpError::BadNArgs {
+            got: 0,
+            min: 1,
+            max: None,
+        });
+    }
+    let mut result: Result<bool, Failed> = Ok(identity);
+    for (n, arg) in args.iter().enumerate() {
+        let value = func_argn("arg", n + 1, arg).into_bool().unwrap(env);
+        result = match (result, value) {
+            (Ok(acc), Ok(b)) => Ok(combine(acc, b)),
+            _ => Err(Failed),
+        };
+    }
+    Ok(Value(Data::Bool(result.map_err(OpError::from)?), Units::scalar()))
+}
+
+fn and(env: &mut Env, _pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    logical(env, args, true, |a, b| a && b)
+}
+
+fn or(env: &mut Env, _pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    logical(env, args, false, |a, b| a || b)
+}
+
+fn not(env: &mut Env, _pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    parse_args!(args, value);
+    let value = value.into_bool().unwrap(env).map_err(OpError::from)?;
+    Ok(Value(Data::Bool(!value), Units::scalar()))
+}
+
 // =================================================================================================
 // Parameters
 // =================================================================================================
@@ -146,6 +410,26 @@ fn oscillator(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult
     )
 }
 
+/// A true-FM operator: `(fmOperator frequency modulation feedback)`. Unlike
+/// [`oscillator`] piped into [`sine`], the modulator is folded into the
+/// phase before the sine is taken, so several of these can be chained as
+/// carriers and modulators to build classic FM algorithms.
+fn fm_operator(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    parse_args!(args, frequency, modulation, feedback);
+    let frequency = frequency.into_signal(Units::hertz(1)).unwrap(env);
+    let modulation = modulation.into_signal(Units::volt(1)).unwrap(env);
+    let feedback = feedback.into_gain().unwrap(env);
+    new_node(
+        env,
+        pos,
+        Units::volt(1),
+        ops::PhaseModOscillator {
+            inputs: [frequency?, modulation?],
+            feedback: feedback?,
+        },
+    )
+}
+
 fn apply_function(
     env: &mut Env,
     pos: Span,
@@ -213,6 +497,94 @@ fn noise(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
     new_node(env, pos, Units::volt(1), ops::Noise)
 }
 
+fn color_noise(
+    env: &mut Env,
+    pos: Span,
+    args: &[EvalResult<Value>],
+    color: ops::NoiseColor,
+) -> OpResult {
+    parse_args!(args);
+    new_node(env, pos, Units::volt(1), ops::ColorNoise { color })
+}
+
+/// Gaussian-distributed white noise (see [`ops::NoiseColor::Gaussian`]),
+/// unlike [`noise`]'s uniform distribution.
+fn gaussian_noise(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    color_noise(env, pos, args, ops::NoiseColor::Gaussian)
+}
+
+fn pink_noise(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    color_noise(env, pos, args, ops::NoiseColor::Pink)
+}
+
+fn brown_noise(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    color_noise(env, pos, args, ops::NoiseColor::Brown)
+}
+
+/// A gate-driven ADSR amplitude contour: `(adsr attack decay sustain
+/// release)`, a 0..1 control signal meant to feed a [`mix`]/`*`. Thin
+/// wrapper around [`envelope::Envelope::adsr`], which does the actual
+/// segment sequencing and gate handling.
+fn adsr(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    parse_args!(args, attack, decay, sustain, release);
+    let attack = attack.into_float(Units::second(1)).unwrap(env);
+    let decay = decay.into_float(Units::second(1)).unwrap(env);
+    let sustain = sustain.into_float(Units::scalar()).unwrap(env);
+    let release = release.into_float(Units::second(1)).unwrap(env);
+    new_node(
+        env,
+        pos,
+        Units::scalar(),
+        envelope::Envelope::adsr(attack?, decay?, sustain?, release?),
+    )
+}
+
+// =================================================================================================
+// Samples
+// =================================================================================================
+
+/// `(sample path)`: load a RIFF/WAVE file and play it back once, top to
+/// bottom. `path` is a bare symbol rather than an evaluated argument, since
+/// the file has to be read before there's a signal to produce -- the same
+/// reason [`define`]'s name is a macro argument rather than a function one.
+/// Multi-channel files are downmixed to mono by averaging channels, since
+/// the rest of the signal graph is single-channel.
+fn sample<'a>(env: &mut Env<'a>, pos: Span, args: &'a [SExpr]) -> OpResult {
+    let path = match args {
+        [path] => path,
+        _ => {
+            return Err(OpError::BadNArgs {
+                got: args.len(),
+                min: 1,
+                max: Some(1),
+            });
+        }
+    };
+    let path = match get_symbol(path) {
+        Ok(path) => path,
+        Err(e) => return error!(env, path.source_pos(), "{}", e),
+    };
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => return error!(env, pos, "could not read {:?}: {}", path, e),
+    };
+    let reader = match wave::Reader::parse(&bytes) {
+        Ok(reader) => reader,
+        Err(e) => return error!(env, pos, "could not parse {:?} as a WAVE file: {}", path, e),
+    };
+    let channel_count = reader.parameters().channel_count.max(1) as usize;
+    let frames: Box<[f32]> = if channel_count <= 1 {
+        Box::from(reader.frames())
+    } else {
+        reader
+            .frames()
+            .chunks_exact(channel_count)
+            .map(|frame| frame.iter().sum::<f32>() / channel_count as f32)
+            .collect()
+    };
+    new_node(env, pos, Units::volt(1), ops::SamplePlayer { frames })
+}
+
 // =================================================================================================
 // Filters
 // =================================================================================================
@@ -251,6 +623,32 @@ fn state_variable(
             inputs: [input?, frequency?],
             mode,
             q: q?,
+            gain: 1.0,
+        },
+    )
+}
+
+fn state_variable_shelf(
+    env: &mut Env,
+    pos: Span,
+    args: &[EvalResult<Value>],
+    mode: filter::Mode,
+) -> OpResult {
+    parse_args!(args, input, frequency, q, gain);
+    let frequency = frequency.into_signal(Units::hertz(1)).unwrap(env);
+    let input = input.into_signal(Units::volt(1)).unwrap(env);
+    let q = q.into_float(Units::scalar()).unwrap(env);
+    let gain = gain.into_gain().unwrap(env);
+    // FIXME: check q >= 0.7
+    new_node(
+        env,
+        pos,
+        Units::volt(1),
+        filter::StateVariable {
+            inputs: [input?, frequency?],
+            mode,
+            q: q?,
+            gain: gain?,
         },
     )
 }
@@ -271,6 +669,66 @@ fn low_pass_4(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult
     state_variable(env, pos, args, filter::Mode::LowPass4)
 }
 
+fn notch(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    state_variable(env, pos, args, filter::Mode::Notch)
+}
+
+fn peak(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    state_variable(env, pos, args, filter::Mode::Peak)
+}
+
+fn allpass(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    state_variable(env, pos, args, filter::Mode::Allpass)
+}
+
+fn low_shelf(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    state_variable_shelf(env, pos, args, filter::Mode::LowShelf)
+}
+
+fn high_shelf(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    state_variable_shelf(env, pos, args, filter::Mode::HighShelf)
+}
+
+/// Unlike [`state_variable`], whose `q` is a fixed constant, these take
+/// resonance as a per-sample signal input too, so it can be swept by an
+/// envelope or LFO just like cutoff.
+fn filter_svf(
+    env: &mut Env,
+    pos: Span,
+    args: &[EvalResult<Value>],
+    mode: filter::ChamberlinMode,
+) -> OpResult {
+    parse_args!(args, input, cutoff, resonance);
+    let input = input.into_signal(Units::volt(1)).unwrap(env);
+    let cutoff = cutoff.into_signal(Units::hertz(1)).unwrap(env);
+    let resonance = resonance.into_signal(Units::scalar()).unwrap(env);
+    new_node(
+        env,
+        pos,
+        Units::volt(1),
+        filter::Filter {
+            inputs: [input?, cutoff?, resonance?],
+            mode,
+        },
+    )
+}
+
+fn filter_low_pass(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    filter_svf(env, pos, args, filter::ChamberlinMode::LowPass)
+}
+
+fn filter_high_pass(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    filter_svf(env, pos, args, filter::ChamberlinMode::HighPass)
+}
+
+fn filter_band_pass(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    filter_svf(env, pos, args, filter::ChamberlinMode::BandPass)
+}
+
+fn filter_notch(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    filter_svf(env, pos, args, filter::ChamberlinMode::Notch)
+}
+
 // =================================================================================================
 // Utilities
 // =================================================================================================
@@ -393,3 +851,107 @@ fn overtone(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
         },
     )
 }
+
+/// Multi-operator FM synthesis: `(fm algorithm frequency feedback ratio1
+/// level1 ratio2 level2 ratio3 level3 ratio4 level4)`. `algorithm` selects
+/// one of [`fm::Algorithm`]'s 8 fixed operator-routing topologies
+/// (wrapping modulo 8), `feedback` is the first operator's self-feedback
+/// amount (same convention as [`fm_operator`]'s), and each of the 4
+/// operators contributes a ratio off `frequency` and an output level.
+fn fm_algorithm(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    let expected = 3 + 2 * fm::OPERATOR_COUNT;
+    if args.len() != expected {
+        return error!(
+            env,
+            pos,
+            "got {} arguments, expected {} (algorithm, frequency, feedback, \
+             then ratio and level for each of {} operators)",
+            args.len(),
+            expected,
+            fm::OPERATOR_COUNT
+        );
+    }
+    let algorithm = func_arg("algorithm", &args[0])
+        .into_int()
+        .and_then(|i| u8::try_from(i.rem_euclid(fm::ALGORITHM_COUNT as i64)).map_err(|_| unimplemented!()))
+        .unwrap(env);
+    let frequency = func_arg("frequency", &args[1])
+        .into_signal(Units::hertz(1))
+        .unwrap(env);
+    let feedback = func_arg("feedback", &args[2]).into_gain().unwrap(env);
+    let mut operators = [fm::FmOperator {
+        ratio: 0.0,
+        level: 0.0,
+    }; fm::OPERATOR_COUNT];
+    let mut failed = false;
+    for (n, chunk) in args[3..].chunks_exact(2).enumerate() {
+        let ratio = func_argn("ratio", n + 1, &chunk[0])
+            .into_float(Units::scalar())
+            .unwrap(env);
+        let level = func_argn("level", n + 1, &chunk[1]).into_gain().unwrap(env);
+        match (ratio, level) {
+            (Ok(ratio), Ok(level)) => operators[n] = fm::FmOperator { ratio, level },
+            _ => failed = true,
+        }
+    }
+    match (algorithm, frequency, feedback, failed) {
+        (Ok(algorithm), Ok(frequency), Ok(feedback), false) => new_node(
+            env,
+            pos,
+            Units::volt(1),
+            fm::FmAlgorithm {
+                inputs: [frequency],
+                algorithm: fm::Algorithm::try_from(algorithm).unwrap(),
+                operators,
+                feedback,
+            },
+        ),
+        _ => Err(Failed),
+    }
+}
+
+/// Convert an evaluated integer to a rate index, reporting a [`ValueError`]
+/// instead of panicking if it doesn't fit in a `u8` -- [`envelope::rate_shift`]
+/// clamps the rate to [`envelope::RATE_MAX`] regardless, but the conversion
+/// from the user-supplied `i64` has to succeed first.
+fn into_rate(i: i64) -> Result<u8, ValueError> {
+    u8::try_from(i).map_err(|_| ValueError::BadRange {
+        got: i,
+        min: 0,
+        max: u8::max_value() as i64,
+    })
+}
+
+/// A gate-driven four-stage envelope generator, modeled on a chip's own
+/// envelope generator rather than [`adsr`]'s fixed-time segments: `(rateEnvelope
+/// attackRate decay1Rate sustainLevel decay2Rate releaseRate)`. Each `*Rate`
+/// is a `0..=31` rate index (see [`envelope::rate_shift`]) rather than a
+/// duration, so higher values update faster; [`envelope::RateEnvelope`] does
+/// the actual stage sequencing.
+fn rate_envelope(env: &mut Env, pos: Span, args: &[EvalResult<Value>]) -> OpResult {
+    parse_args!(
+        args,
+        attack_rate,
+        decay1_rate,
+        sustain_level,
+        decay2_rate,
+        release_rate
+    );
+    let attack_rate = attack_rate.into_int().and_then(into_rate).unwrap(env);
+    let decay1_rate = decay1_rate.into_int().and_then(into_rate).unwrap(env);
+    let sustain_level = sustain_level.into_float(Units::scalar()).unwrap(env);
+    let decay2_rate = decay2_rate.into_int().and_then(into_rate).unwrap(env);
+    let release_rate = release_rate.into_int().and_then(into_rate).unwrap(env);
+    new_node(
+        env,
+        pos,
+        Units::scalar(),
+        envelope::RateEnvelope {
+            attack_rate: attack_rate?,
+            decay1_rate: decay1_rate?,
+            sustain_level: sustain_level?,
+            decay2_rate: decay2_rate?,
+            release_rate: release_rate?,
+        },
+    )
+}
@@ -31,8 +31,11 @@ fn evaluate<'a>(env: &mut Env<'a>, expr: &'a SExpr) -> Result<EnvelopeSegment, F
                 "set" => set,
                 "lin" => lin,
                 "exp" => exp,
+                "target" => target,
                 "delay" => delay,
                 "gate" => gate,
+                "loop-start" => loop_start,
+                "loop" => loop_seg,
                 "stop" => stop,
                 _ => return error!(env, pos, "undefined envelope segment: {:?}", name),
             };
@@ -85,6 +88,13 @@ fn exp(env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
     Ok(EnvelopeSegment::Exp(time?, value?))
 }
 
+fn target(env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
+    parse_args!(args, tau, value);
+    let tau = tau.into_float(Units::second(1)).unwrap(env);
+    let value = value.into_float(Units::scalar()).unwrap(env);
+    Ok(EnvelopeSegment::Target(tau?, value?))
+}
+
 fn delay(env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
     parse_args!(args, time);
     let time = time.into_float(Units::second(1)).unwrap(env);
@@ -96,6 +106,16 @@ fn gate(_env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
     Ok(EnvelopeSegment::Gate)
 }
 
+fn loop_start(_env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
+    parse_args!(args);
+    Ok(EnvelopeSegment::LoopStart)
+}
+
+fn loop_seg(_env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
+    parse_args!(args);
+    Ok(EnvelopeSegment::Loop)
+}
+
 fn stop(_env: &mut Env, args: &[EvalResult<Value>]) -> EnvResult {
     parse_args!(args);
     Ok(EnvelopeSegment::Stop)
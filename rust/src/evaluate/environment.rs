@@ -1,4 +1,5 @@
-use crate::error::ErrorHandler;
+use crate::editdistance::edit_distance;
+use crate::error::{Applicability, Diagnostic, ErrorHandler, Severity, Suggestion};
 use crate::sexpr::{Content, SExpr, Type as EType};
 use crate::signal::graph::{Graph, Node, SignalRef};
 use crate::sourcepos::{HasPos, Span};
@@ -21,6 +22,17 @@ macro_rules! log_error {
     };
 }
 
+/// Log a warning, returning void. Unlike [`log_error!`], this does not mark
+/// the environment as failed, so a [`Graph`] is still produced.
+macro_rules! log_warning {
+    ($env:expr, $loc:expr, $msg:literal) => {
+        $env.warning($loc, $msg);
+    };
+    ($env:expr, $loc:expr, $($tts:expr),*) => {
+        $env.warning($loc, format!($($tts),*).as_ref());
+    };
+}
+
 /// Log an error and return an evaluation failure.
 macro_rules! error {
     ($env:expr, $loc:expr, $($tts:expr),*) => {{
@@ -36,6 +48,7 @@ pub enum ValueError {
     BadType { got: Type, expect: Type },
     BadEType { got: EType, expect: EType },
     BadGain { got: Type },
+    BadRange { got: i64, min: i64, max: i64 },
 }
 
 impl Display for ValueError {
@@ -46,6 +59,9 @@ impl Display for ValueError {
             BadType { got, expect } => write!(f, "type is {}, expected {}", got, expect),
             BadEType { got, expect } => write!(f, "type is {}, expected {}", got, expect),
             BadGain { got } => write!(f, "type is {}, expected gain (dB or scalar constant)", got),
+            BadRange { got, min, max } => {
+                write!(f, "value {} is out of range, expected {}..={}", got, min, max)
+            }
         }
     }
 }
@@ -97,6 +113,7 @@ impl From<Failed> for OpError {
 pub enum Data {
     Int(i64),
     Float(f64),
+    Bool(bool),
     Signal(SignalRef),
     Void,
 }
@@ -106,6 +123,7 @@ impl Data {
         match self {
             Data::Int(_) => DataType::Int,
             Data::Float(_) => DataType::Float,
+            Data::Bool(_) => DataType::Bool,
             Data::Signal(_) => DataType::Signal,
             Data::Void => DataType::Void,
         }
@@ -191,12 +209,55 @@ impl Value {
         }
     }
 
+    /// Get a numeric value along with its units, coercing `Int` to `Float`
+    /// the same way [`Value::into_float`] does. Unlike `into_float`, this
+    /// does not require the units to match any particular expectation; it
+    /// is up to the caller (e.g. a units-aware comparison) to check them.
+    fn into_any_float(self) -> Result<(f64, Units), ValueError> {
+        match self {
+            Value(Data::Float(num), units) => Ok((num, units)),
+            Value(Data::Int(num), units) => Ok((num as f64, units)),
+            val => Err(val.bad_type(Type(DataType::Float, None))),
+        }
+    }
+
+    fn into_bool(self) -> Result<bool, ValueError> {
+        match self {
+            Value(Data::Bool(b), units) if units.is_scalar() => Ok(b),
+            val => Err(val.bad_type(Type(DataType::Bool, Some(Units::scalar())))),
+        }
+    }
+
     fn into_signal(self, units: Units) -> Result<SignalRef, ValueError> {
         match self {
             Value(Data::Signal(sig), vunits) if vunits == units => Ok(sig),
             val => Err(val.bad_type(Type(DataType::Signal, Some(units)))),
         }
     }
+
+    /// Format the value the way an s-expression would print, for echoing
+    /// results back to a user (e.g. in a REPL).
+    pub fn print(&self) -> String {
+        match self {
+            Value(Data::Int(num), units) => {
+                if units.is_scalar() {
+                    format!("{}", num)
+                } else {
+                    format!("[{} {}]", units, num)
+                }
+            }
+            Value(Data::Float(num), units) => {
+                if units.is_scalar() {
+                    format!("{}", num)
+                } else {
+                    format!("[{} {}]", units, num)
+                }
+            }
+            Value(Data::Bool(b), _) => (if *b { "true" } else { "false" }).to_string(),
+            Value(Data::Signal(sig), _) => format!("#<signal {:?}>", sig),
+            Value(Data::Void, _) => "#<void>".to_string(),
+        }
+    }
 }
 
 /// Result of evaluating function or macro body.
@@ -209,6 +270,7 @@ pub enum DataType {
     Signal,
     Int,
     Float,
+    Bool,
     NonVoid,
 }
 
@@ -229,6 +291,7 @@ impl Display for Type {
             DataType::Signal => "signal",
             DataType::Int => "int",
             DataType::Float => "float",
+            DataType::Bool => "bool",
             DataType::NonVoid => "non-void",
         })?;
         match self.1 {
@@ -323,6 +386,14 @@ impl EvalResult<Value> {
         self.and_then(Value::into_any_signal)
     }
 
+    pub fn into_any_float(self) -> EvalResult<(f64, Units)> {
+        self.and_then(Value::into_any_float)
+    }
+
+    pub fn into_bool(self) -> EvalResult<bool> {
+        self.and_then(Value::into_bool)
+    }
+
     pub fn into_signal(self, units: Units) -> EvalResult<SignalRef> {
         self.and_then(|v| v.into_signal(units))
     }
@@ -352,7 +423,7 @@ pub enum Operator {
 pub struct Env<'a> {
     has_error: bool,
     err_handler: &'a mut dyn ErrorHandler,
-    pub variables: HashMap<&'a str, Result<Value, Failed>, RandomState>,
+    pub variables: HashMap<Box<str>, Result<Value, Failed>, RandomState>,
     operators: HashMap<&'a str, Operator, RandomState>,
     graph: Graph,
     #[allow(dead_code)]
@@ -375,6 +446,57 @@ impl<'a> Env<'a> {
         }
     }
 
+    /// Create a new environment that continues from previously-accumulated
+    /// state (variables and the signal graph), but evaluates expressions
+    /// backed by a new source buffer. This lets a REPL persist definitions
+    /// across separately-parsed lines of input.
+    pub fn resume(
+        err_handler: &'a mut dyn ErrorHandler,
+        operators: HashMap<&'a str, Operator, RandomState>,
+        variables: HashMap<Box<str>, Result<Value, Failed>, RandomState>,
+        graph: Graph,
+    ) -> Self {
+        Env {
+            has_error: false,
+            err_handler,
+            variables,
+            operators,
+            graph,
+            tail_length: None,
+        }
+    }
+
+    /// Split the environment into the state that should persist across
+    /// separately-parsed inputs: the variables and the signal graph.
+    pub fn into_parts(self) -> (HashMap<Box<str>, Result<Value, Failed>, RandomState>, Graph) {
+        (self.variables, self.graph)
+    }
+
+    /// Bind a variable, for example to introduce a lexically-scoped binding.
+    /// Returns whatever the name was previously bound to, if anything, so
+    /// the binding can later be undone with [`Env::unbind_variable`].
+    pub fn bind_variable(
+        &mut self,
+        name: Box<str>,
+        value: Result<Value, Failed>,
+    ) -> Option<Result<Value, Failed>> {
+        self.variables.insert(name, value)
+    }
+
+    /// Undo a [`Env::bind_variable`] call, restoring whatever the name was
+    /// previously bound to (or leaving it unbound, if it was not bound
+    /// before).
+    pub fn unbind_variable(&mut self, name: &str, previous: Option<Result<Value, Failed>>) {
+        match previous {
+            Some(value) => {
+                self.variables.insert(Box::from(name), value);
+            }
+            None => {
+                self.variables.remove(name);
+            }
+        }
+    }
+
     /// Evaluate an s-expression.
     pub fn evaluate(&mut self, expr: &'a SExpr) -> EvalResult<Value> {
         let label = ValueLabel {
@@ -410,7 +532,23 @@ impl<'a> Env<'a> {
                 let oppos = op.source_pos();
                 let op = match self.operators.get(name) {
                     Some(x) => *x,
-                    None => return error!(self, oppos, "undefined function or macro: {:?}", name),
+                    None => {
+                        let suggestions = match suggest_name(name, self.operators.keys().copied())
+                        {
+                            Some(replacement) => vec![Suggestion {
+                                span: oppos,
+                                replacement,
+                                applicability: Applicability::MaybeIncorrect,
+                            }],
+                            None => Vec::new(),
+                        };
+                        self.error_with_suggestions(
+                            oppos,
+                            &format!("undefined function or macro: {:?}", name),
+                            &suggestions,
+                        );
+                        return Err(Failed);
+                    }
                 };
                 let r = match op {
                     Operator::Function(f) => {
@@ -442,14 +580,39 @@ impl<'a> Env<'a> {
 
     /// Log an error message.
     pub fn error(&mut self, pos: Span, msg: &str) {
-        self.has_error = true;
-        self.err_handler.handle(pos, msg);
+        self.error_with_suggestions(pos, msg, &[]);
     }
 
-    /// Add a new audio processing node to the graph.
+    /// Log an error message, along with any [`Suggestion`]s for fixing it.
+    pub fn error_with_suggestions(&mut self, pos: Span, msg: &str, suggestions: &[Suggestion]) {
+        self.has_error = true;
+        self.err_handler.handle(&Diagnostic {
+            pos,
+            severity: Severity::Error,
+            message: msg,
+            labels: &[],
+            suggestions,
+        });
+    }
+
+    /// Log a warning message. Unlike [`Env::error`], this does not mark the
+    /// environment as failed, so [`Env::into_graph`] still returns a graph.
+    pub fn warning(&mut self, pos: Span, msg: &str) {
+        self.err_handler
+            .handle(&Diagnostic::new(pos, Severity::Warning, msg));
+    }
+
+    /// Add a new audio processing node to the graph. The interpreter always
+    /// builds inputs from [`SignalRef`]s this same graph already returned
+    /// and node input arrays no longer than 4, so [`Graph::add`] rejecting
+    /// the node would mean the interpreter itself produced a malformed
+    /// graph, not a user-facing error -- hence the `expect` rather than
+    /// threading a `Result` through every caller.
     pub fn new_node(&mut self, pos: Span, node: impl Node) -> SignalRef {
         let _ = pos;
-        self.graph.add(Box::new(node))
+        self.graph
+            .add(Box::new(node))
+            .expect("interpreter produced an invalid graph node")
     }
 
     /// Discard the environment and return the created graph.
@@ -462,6 +625,19 @@ impl<'a> Env<'a> {
     }
 }
 
+/// Find the candidate in `candidates` closest to `name` by edit distance, for
+/// suggesting a fix to a typo'd identifier. Candidates farther than a third
+/// of `name`'s length (rounded down, minimum 1) away are not close enough to
+/// be worth suggesting.
+fn suggest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (candidate, edit_distance(name, candidate)))
+        .filter(|&(_, distance)| distance <= max_distance)
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(candidate, _)| candidate.to_string())
+}
+
 /// Get the name of a symbol.
 pub fn get_symbol(expr: &SExpr) -> Result<&str, ValueError> {
     match &expr.content {
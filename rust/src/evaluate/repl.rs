@@ -0,0 +1,116 @@
+use super::builtins;
+use super::environment::{Env, EvalResult, Failed, Value, ValueError};
+use crate::error::{Diagnostic, ErrorHandler, Severity};
+use crate::parser::{ParseResult, Parser};
+use crate::signal::graph::Graph;
+use crate::token::{Tokenizer, Type as TokenType};
+use std::collections::hash_map::{HashMap, RandomState};
+use std::io::{self, BufRead, Write};
+
+/// Count how deeply nested the parens in `text` are, ignoring anything the
+/// tokenizer does not treat as a paren (comments, symbols, numbers). A
+/// negative result means the text has more closing parens than opening ones.
+fn paren_depth(text: &[u8]) -> i32 {
+    let mut depth = 0i32;
+    let mut toks = match Tokenizer::new(text) {
+        Ok(toks) => toks,
+        Err(_) => return 0,
+    };
+    loop {
+        match toks.next().ty {
+            TokenType::End => return depth,
+            TokenType::ParenOpen => depth += 1,
+            TokenType::ParenClose => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// Run an interactive read-eval-print loop, reading s-expressions from
+/// stdin and printing the result of evaluating each one.
+///
+/// Input is buffered across lines until parens are balanced, so a form can
+/// span multiple lines; a continuation prompt is shown while a form is
+/// incomplete. Variables defined at one prompt (via `define`) remain visible
+/// at later prompts, since the environment's variables and signal graph
+/// persist across entries even though each entry is parsed from its own,
+/// separate buffer.
+pub fn run(err_handler: &mut dyn ErrorHandler) {
+    let stdin = io::stdin();
+    let mut variables: HashMap<Box<str>, Result<Value, Failed>, RandomState> = HashMap::new();
+    let mut graph = Graph::new();
+    let mut input = String::new();
+    loop {
+        print_prompt(!input.is_empty());
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(_) => break,
+        }
+        input.push_str(&line);
+        if input.trim().is_empty() {
+            input.clear();
+            continue;
+        }
+        if paren_depth(input.as_bytes()) > 0 {
+            continue;
+        }
+        let text = std::mem::take(&mut input);
+        let forms = match parse_forms(&mut *err_handler, text.as_bytes()) {
+            Some(forms) => forms,
+            None => continue,
+        };
+        let mut env = Env::resume(&mut *err_handler, builtins::operators(), variables, graph);
+        for form in forms.iter() {
+            match env.evaluate(form) {
+                EvalResult(_, Ok(value)) => println!("{}", value.print()),
+                EvalResult(_, Err(ValueError::Failed)) => {}
+                EvalResult(label, Err(e)) => env.error(label.pos, format!("{}", e).as_ref()),
+            }
+        }
+        let (v, g) = env.into_parts();
+        variables = v;
+        graph = g;
+    }
+}
+
+/// Parse all complete s-expressions out of a single, paren-balanced buffer.
+/// Returns `None` if the buffer failed to parse; the error is already
+/// reported through `err_handler`.
+fn parse_forms(
+    err_handler: &mut dyn ErrorHandler,
+    text: &[u8],
+) -> Option<Vec<crate::sexpr::SExpr>> {
+    let mut toks = match Tokenizer::new(text) {
+        Ok(toks) => toks,
+        Err(e) => {
+            err_handler.handle(&Diagnostic::new(
+                crate::sourcepos::Span::none(),
+                Severity::Error,
+                e.to_string().as_ref(),
+            ));
+            return None;
+        }
+    };
+    let mut parser = Parser::new();
+    let mut forms = Vec::new();
+    loop {
+        match parser.parse(err_handler, &mut toks) {
+            ParseResult::None => return Some(forms),
+            ParseResult::Incomplete => {
+                parser.finish(err_handler);
+                return Some(forms);
+            }
+            ParseResult::Error => return None,
+            ParseResult::Value(expr) => forms.push(expr),
+        }
+    }
+}
+
+fn print_prompt(continuation: bool) {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write!(out, "{}", if continuation { "... " } else { "> " }).unwrap();
+    out.flush().unwrap();
+}
@@ -128,8 +128,15 @@ pub mod ops {
         Set(f64),
         Lin(f64, f64),
         Exp(f64, f64),
+        /// Approach a target value along a one-pole RC curve with the given
+        /// time constant, in seconds.
+        Target(f64, f64),
         Delay(f64),
         Gate,
+        /// Mark the position that a later `Loop` segment jumps back to.
+        LoopStart,
+        /// While the gate is held, jump back to the previous `LoopStart`.
+        Loop,
         Stop,
     }
 
@@ -0,0 +1,141 @@
+//! A second [`ErrorHandler`] alongside [`ConsoleLogger`](crate::consolelogger::ConsoleLogger),
+//! for tools -- editors, LSP front-ends, CI annotators -- that want
+//! structured diagnostics instead of ANSI-colored text aimed at a
+//! terminal. Each [`JsonLogger::handle`] call writes one newline-delimited
+//! JSON record to stdout.
+
+use crate::error::{Applicability, Diagnostic, ErrorHandler, Severity, Suggestion};
+use crate::sourcepos::Span;
+use crate::sourcetext::SourceText;
+use serde::Serialize;
+use std::io;
+use std::io::{stdout, Write};
+
+/// A resolved source position, serialized as both the raw byte offset and
+/// the 0-indexed line/column it decodes to.
+#[derive(Serialize)]
+struct JsonPos {
+    byte: u32,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    start: JsonPos,
+    end: JsonPos,
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion {
+    replacement: String,
+    machine_applicable: bool,
+}
+
+impl JsonSuggestion {
+    fn new(suggestion: &Suggestion) -> Self {
+        JsonSuggestion {
+            replacement: suggestion.replacement.clone(),
+            machine_applicable: suggestion.applicability == Applicability::MachineApplicable,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    message: &'a str,
+    span: Option<JsonSpan>,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'a str,
+    message: &'a str,
+    filename: &'a str,
+    span: Option<JsonSpan>,
+    labels: Vec<JsonLabel<'a>>,
+    suggestions: Vec<JsonSuggestion>,
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Help => "help",
+        Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Emits one diagnostic record per line, as newline-delimited JSON, to
+/// stdout. Mirrors [`ConsoleLogger`](crate::consolelogger::ConsoleLogger)'s
+/// lazy source-text init: the filename and text are only decoded into line
+/// starts once the first diagnostic actually arrives.
+pub struct JsonLogger<'a> {
+    text: Result<SourceText<'a>, (&'a str, &'a [u8])>,
+}
+
+impl<'a> JsonLogger<'a> {
+    pub fn from_text(filename: &'a str, text: &'a [u8]) -> Self {
+        JsonLogger {
+            text: Err((filename, text)),
+        }
+    }
+
+    fn init(&mut self) {
+        match self.text {
+            Ok(_) => (),
+            Err((filename, text)) => self.text = Ok(SourceText::new(filename, text)),
+        }
+    }
+
+    fn span_for(&self, source_text: &SourceText<'_>, pos: Span) -> Option<JsonSpan> {
+        let text_span = source_text.span(pos)?;
+        Some(JsonSpan {
+            start: JsonPos {
+                byte: pos.start.0,
+                line: text_span.start.line,
+                column: text_span.start.byte,
+            },
+            end: JsonPos {
+                byte: pos.end.0,
+                line: text_span.end.line,
+                column: text_span.end.byte,
+            },
+        })
+    }
+
+    fn write_record(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+        let source_text = self.text.as_ref().unwrap();
+        let record = JsonDiagnostic {
+            severity: severity_name(diagnostic.severity),
+            message: diagnostic.message,
+            filename: source_text.filename(),
+            span: self.span_for(source_text, diagnostic.pos),
+            labels: diagnostic
+                .labels
+                .iter()
+                .map(|label| JsonLabel {
+                    message: label.message,
+                    span: self.span_for(source_text, label.span),
+                })
+                .collect(),
+            suggestions: diagnostic
+                .suggestions
+                .iter()
+                .map(JsonSuggestion::new)
+                .collect(),
+        };
+        let stdout = stdout();
+        let mut handle = stdout.lock();
+        serde_json::to_writer(&mut handle, &record)?;
+        writeln!(handle)
+    }
+}
+
+impl<'a> ErrorHandler for JsonLogger<'a> {
+    fn handle(&mut self, diagnostic: &Diagnostic) {
+        self.init();
+        crate::consolelogger::record_severity(diagnostic.severity);
+        self.write_record(diagnostic).unwrap();
+    }
+}
@@ -1,23 +1,62 @@
+// The `no_std` feature builds only the `signal` DSP core (plus `rand`) on
+// top of `alloc`, so it can be cross-compiled to `wasm32-unknown-unknown`
+// and run a patch's bytecode program directly in a browser. The
+// s-expression parser and diagnostic formatting need `std::fmt`/`std::io`
+// and stay host-side, so they (and everything else built on top of them)
+// are excluded from that build.
+#![cfg_attr(feature = "no_std", no_std)]
+#![cfg_attr(feature = "no_std", no_main)]
+
+#[cfg(feature = "no_std")]
+extern crate alloc;
+
+#[cfg(not(feature = "no_std"))]
+mod audio;
+#[cfg(not(feature = "no_std"))]
 mod cmd_sfx;
+#[cfg(not(feature = "no_std"))]
 mod color;
+#[cfg(not(feature = "no_std"))]
 mod consolelogger;
+#[cfg(not(feature = "no_std"))]
+mod editdistance;
+#[cfg(not(feature = "no_std"))]
 mod error;
+#[cfg(not(feature = "no_std"))]
 mod evaluate;
+#[cfg(not(feature = "no_std"))]
+mod jsonlogger;
+#[cfg(not(feature = "no_std"))]
 mod note;
+#[cfg(not(feature = "no_std"))]
 mod number;
+#[cfg(not(feature = "no_std"))]
+mod output;
+#[cfg(not(feature = "no_std"))]
 mod parser;
+#[cfg(not(feature = "no_std"))]
+mod resample;
+#[cfg(not(feature = "no_std"))]
 mod sexpr;
 mod signal;
+#[cfg(not(feature = "no_std"))]
 mod sourcepos;
+#[cfg(not(feature = "no_std"))]
 mod sourceprint;
+#[cfg(not(feature = "no_std"))]
 mod sourcetext;
+#[cfg(not(feature = "no_std"))]
 mod token;
+#[cfg(not(feature = "no_std"))]
 mod utf8;
+#[cfg(not(feature = "no_std"))]
 mod wave;
 
+#[cfg(not(feature = "no_std"))]
 #[allow(dead_code)]
 mod parseargs;
 
+#[cfg(not(feature = "no_std"))]
 #[allow(dead_code)]
 mod units;
 
@@ -27,12 +66,18 @@ mod rand;
 #[cfg(test)]
 mod test;
 
+#[cfg(not(feature = "no_std"))]
 use consolelogger::write_diagnostic;
+#[cfg(not(feature = "no_std"))]
 use error::Severity;
+#[cfg(not(feature = "no_std"))]
 use std::env;
+#[cfg(not(feature = "no_std"))]
 use std::io::stderr;
+#[cfg(not(feature = "no_std"))]
 use std::process;
 
+#[cfg(not(feature = "no_std"))]
 fn main() {
     let mut stderr = stderr();
     let mut args = env::args_os();
@@ -53,3 +98,12 @@ fn main() {
         }
     }
 }
+
+// `wasm32-unknown-unknown` has no OS to report a panic to; abort instead of
+// unwinding so the `no_std` build doesn't need unwinding tables or a
+// `std`-provided panic runtime.
+#[cfg(feature = "no_std")]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
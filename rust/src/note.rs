@@ -17,6 +17,43 @@ impl Note {
     pub fn chromaticity(&self) -> i32 {
         self.0 as i32 % 12
     }
+
+    /// Convert to a frequency in Hz, by equal temperament relative to
+    /// `tuning`'s reference pitch.
+    pub fn frequency(&self, tuning: Tuning) -> f64 {
+        let semitones = self.0 as f64 - tuning.reference.0 as f64;
+        tuning.frequency * 2f64.powf(semitones / 12.0)
+    }
+
+    /// The MIDI note nearest `freq`, and how many cents sharp (positive) or
+    /// flat (negative) `freq` is from that note -- the inverse of
+    /// [`Note::frequency`].
+    pub fn nearest_with_cents(freq: f64, tuning: Tuning) -> (Note, f32) {
+        let semitones = tuning.reference.0 as f64 + 12.0 * (freq / tuning.frequency).log2();
+        let note = Note(semitones.round().clamp(0.0, u8::MAX as f64) as u8);
+        let cents = 1200.0 * (freq / note.frequency(tuning)).log2();
+        (note, cents as f32)
+    }
+}
+
+/// A tuning reference pitch, for converting between [`Note`]s and
+/// frequencies via [`Note::frequency`] and [`Note::nearest_with_cents`].
+/// The default is concert pitch, A4 (note 69) at 440 Hz; patches that want
+/// a different reference (432 Hz, a shifted concert A, ...) can build their
+/// own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tuning {
+    pub reference: Note,
+    pub frequency: f64,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            reference: Note(69),
+            frequency: 440.0,
+        }
+    }
 }
 
 impl fmt::Display for Note {
@@ -36,6 +73,8 @@ pub enum ParseNoteError {
     UnknownNote,
     InvalidAccidentals,
     OutOfRange,
+    /// A trailing `+N`/`-N` cents offset was outside of [-100, 100].
+    CentsOutOfRange,
 }
 
 impl FromStr for Note {
@@ -80,7 +119,16 @@ impl FromStr for Note {
             }
             _ => (),
         }
-        let octave = match rest.parse::<i32>() {
+        // A second `+`/`-` after the octave (the first, if any, is the
+        // octave's own sign) introduces a trailing cents deviation, e.g.
+        // "a4+25" is 25 cents sharp of A4.
+        let search_start = if rest.is_empty() { 0 } else { 1 };
+        let cents_idx = rest[search_start..].find(['+', '-']).map(|i| i + search_start);
+        let (octave_text, cents_text) = match cents_idx {
+            Some(i) => (&rest[..i], Some(&rest[i..])),
+            None => (rest, None),
+        };
+        let octave = match octave_text.parse::<i32>() {
             Ok(n) => n,
             Err(_) => return Err(ParseNoteError::CannotParse),
         };
@@ -88,6 +136,15 @@ impl FromStr for Note {
             return Err(ParseNoteError::OutOfRange);
         }
         value += (octave + 1) * 12;
+        let cents = match cents_text {
+            None => 0,
+            Some(text) => match text.parse::<i32>() {
+                Ok(n) if (-100..=100).contains(&n) => n,
+                Ok(_) => return Err(ParseNoteError::CentsOutOfRange),
+                Err(_) => return Err(ParseNoteError::CannotParse),
+            },
+        };
+        let value = (value as f64 + cents as f64 / 100.0).round() as i32;
         match u8::try_from(value) {
             Ok(n) => Ok(Note(n)),
             Err(_) => Err(ParseNoteError::OutOfRange),
@@ -97,7 +154,46 @@ impl FromStr for Note {
 
 #[cfg(test)]
 mod test {
-    use super::Note;
+    use super::{Note, ParseNoteError, Tuning};
+
+    #[test]
+    fn frequency() {
+        assert_eq!(Note(69).frequency(Tuning::default()), 440.0);
+        assert_eq!(Note(81).frequency(Tuning::default()), 880.0);
+        assert_eq!(Note(57).frequency(Tuning::default()), 220.0);
+
+        // A retuned reference shifts every note's frequency with it.
+        let tuning = Tuning { reference: Note(69), frequency: 432.0 };
+        assert_eq!(Note(69).frequency(tuning), 432.0);
+    }
+
+    #[test]
+    fn nearest_with_cents() {
+        let (note, cents) = Note::nearest_with_cents(440.0, Tuning::default());
+        assert_eq!(note, Note(69));
+        assert_eq!(cents, 0.0);
+
+        // Halfway (in cents) between A4 and A#4 rounds up, 50 cents flat.
+        let (note, cents) = Note::nearest_with_cents(440.0 * 2f64.powf(0.5 / 12.0), Tuning::default());
+        assert_eq!(note, Note(70));
+        assert!((cents - -50.0).abs() < 1e-3, "cents = {}", cents);
+    }
+
+    #[test]
+    fn parse_cents() {
+        assert_eq!("a4+25".parse::<Note>(), Ok(Note(69)));
+        assert_eq!("a4-100".parse::<Note>(), Ok(Note(68)));
+        assert_eq!("a4+100".parse::<Note>(), Ok(Note(70)));
+        assert_eq!("c-1-30".parse::<Note>(), Ok(Note(0)));
+        assert_eq!(
+            "a4+101".parse::<Note>(),
+            Err(ParseNoteError::CentsOutOfRange)
+        );
+        assert_eq!(
+            "a4-101".parse::<Note>(),
+            Err(ParseNoteError::CentsOutOfRange)
+        );
+    }
 
     #[test]
     fn octave() {
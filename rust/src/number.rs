@@ -1,5 +1,8 @@
 use crate::sourcepos::{Pos, Span};
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
 
 /// A type of error from parsing a number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,6 +13,7 @@ pub enum ParseError {
     UnexpectedChar(char),
     NoDigits,
     NoExponentValue,
+    MisplacedSeparator(char),
 }
 
 impl fmt::Display for ParseError {
@@ -24,10 +28,52 @@ impl fmt::Display for ParseError {
             UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
             NoDigits => write!(f, "number has no digits"),
             NoExponentValue => write!(f, "missing exponent value"),
+            MisplacedSeparator(c) => {
+                write!(f, "digit separator {:?} must be between two digits", c)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// An error converting a [`ParsedNumber`] into a fixed-size numeric type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertError {
+    /// The number has a fractional part, is negative where the target type
+    /// can't represent that, or its magnitude doesn't fit in the target type.
+    Overflow,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Overflow => write!(f, "number does not fit in target type"),
+        }
+    }
+}
+
+impl Error for ConvertError {}
+
+/// An error from [`ParsedNumber::parse_with_unit`], covering both the
+/// numeric body and its trailing unit suffix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseUnitError {
+    Number(ParseError),
+    Unit(crate::units::ParseError),
+}
+
+impl fmt::Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseUnitError::Number(e) => e.fmt(f),
+            ParseUnitError::Unit(e) => e.fmt(f),
         }
     }
 }
 
+impl Error for ParseUnitError {}
+
 /// The sign for a number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Sign {
@@ -54,6 +100,11 @@ pub struct ParsedNumber {
     pub radix: Radix,
     pub digits: Vec<u8>,
     pub exponent: Option<i32>,
+    /// The binary exponent of a hex or binary float literal (`0x1.8p3`),
+    /// separate from `exponent` since it scales the digits by a power of two
+    /// rather than a power of ten -- `None` for ordinary integers and
+    /// decimal numbers.
+    pub bin_exponent: Option<i32>,
 }
 
 fn is_digit(c: char) -> bool {
@@ -87,16 +138,42 @@ fn starts_with_hex_digit(s: &str) -> bool {
     }
 }
 
-/// Parse an exponent from a string.
+/// What follows a `_` digit-group separator, without consuming it.
+enum AfterSeparator {
+    /// Another digit -- a well-placed separator, consumed and ignored.
+    Digit,
+    /// Nothing at all -- not an error, the `_` is simply left as part of the
+    /// remainder, the same way a truncated token like `0o` leaves `o`.
+    End,
+    /// Some other character -- a doubled `__`, or a separator trailing right
+    /// before a terminator, the decimal point, or an exponent marker.
+    Other,
+}
+
+fn after_separator(chars: &std::str::Chars, is_digit: impl Fn(char) -> bool) -> AfterSeparator {
+    match chars.clone().next() {
+        Some(c) if is_digit(c) => AfterSeparator::Digit,
+        Some(_) => AfterSeparator::Other,
+        None => AfterSeparator::End,
+    }
+}
+
+/// Parse an exponent from a string, introduced by either of `markers` (`('e',
+/// 'E')` for a decimal exponent, `('p', 'P')` for the binary exponent of a
+/// hex/binary float literal).
 ///
 /// Return the exponent's value, clamped to the range of i32, and the remainder
 /// of the string after the exponent.
-fn parse_exponent(text: &str, pos: Span) -> Result<(Option<i32>, &str), (ParseError, Span)> {
+fn parse_exponent(
+    text: &str,
+    pos: Span,
+    markers: (char, char),
+) -> Result<(Option<i32>, &str), (ParseError, Span)> {
     let mut chars = text.chars();
     let mut value: u32 = 0;
     let mut has_value = false;
     let sign = match chars.next() {
-        Some(c) if c == 'e' || c == 'E' => match chars.next() {
+        Some(c) if c == markers.0 || c == markers.1 => match chars.next() {
             Some(c) => match c {
                 '+' => Sign::Positive,
                 '-' => Sign::Negative,
@@ -111,6 +188,7 @@ fn parse_exponent(text: &str, pos: Span) -> Result<(Option<i32>, &str), (ParseEr
         },
         _ => return Ok((None, text)),
     };
+    let mut last_was_digit = has_value;
     let rest = loop {
         let rest = chars.as_str();
         match chars.next() {
@@ -118,6 +196,23 @@ fn parse_exponent(text: &str, pos: Span) -> Result<(Option<i32>, &str), (ParseEr
                 value = value.saturating_mul(10);
                 value = value.saturating_add(c as u32 - '0' as u32);
                 has_value = true;
+                last_was_digit = true;
+            }
+            Some('_') if last_was_digit => match after_separator(&chars, is_digit) {
+                AfterSeparator::Digit => last_was_digit = false,
+                AfterSeparator::End => break rest,
+                AfterSeparator::Other => {
+                    return Err((
+                        ParseError::MisplacedSeparator('_'),
+                        pos.sub_span(text.len() - rest.len()..text.len() - chars.as_str().len()),
+                    ));
+                }
+            },
+            Some('_') => {
+                return Err((
+                    ParseError::MisplacedSeparator('_'),
+                    pos.sub_span(text.len() - rest.len()..text.len() - chars.as_str().len()),
+                ));
             }
             _ => break rest,
         }
@@ -155,6 +250,7 @@ impl ParsedNumber {
             radix: Radix::Decimal,
             digits: Vec::new(),
             exponent: None,
+            bin_exponent: None,
         };
     }
 
@@ -173,6 +269,7 @@ impl ParsedNumber {
         self.sign = sign;
         self.digits.clear();
         self.exponent = None;
+        self.bin_exponent = None;
         let mut chars = text.chars();
         if chars.next() == Some('0') {
             match chars.next() {
@@ -197,7 +294,50 @@ impl ParsedNumber {
         self.parse_dec(text, pos)
     }
 
-    /// Parse an integer, without sign, and return the remainder of the string.
+    /// Parse a number followed by an optional unit suffix -- e.g. `"12V"`,
+    /// `"1.2e3ms"`, `"-6dB"` -- the same two-step pipeline [`Parser`] runs
+    /// for a numeric token, bundled into one call for callers outside the
+    /// main parser. Any metric prefix carried by the suffix (`"k"`, `"m"`,
+    /// ...) is folded into this number's own `exponent`, the same way
+    /// [`Units::parse`]'s prefix scale always is.
+    ///
+    /// Returns the units and the span they were parsed from, unless the
+    /// suffix was empty. Unlike [`ParsedNumber::parse`], there is no
+    /// remainder left over: whatever follows the numeric body must parse
+    /// as a valid (possibly empty) unit expression, the same way
+    /// [`Units::parse`] itself always consumes its whole input.
+    ///
+    /// [`Parser`]: crate::parser::Parser
+    pub fn parse_with_unit(
+        &mut self,
+        text: &str,
+        pos: Span,
+    ) -> Result<Option<(crate::units::Units, Span)>, (ParseUnitError, Span)> {
+        let rest = self
+            .parse(text, pos)
+            .map_err(|(e, pos)| (ParseUnitError::Number(e), pos))?;
+        let idx = text.len() - rest.len();
+        let (unit_pos, units, exponent) = crate::units::Units::parse(rest, pos.sub_span(idx..))
+            .map_err(|(e, pos, _)| (ParseUnitError::Unit(e), pos))?;
+        if exponent != 0 {
+            self.exponent = Some(self.exponent.unwrap_or(0).saturating_add(exponent));
+        }
+        Ok(if units == Default::default() {
+            None
+        } else {
+            Some((units, unit_pos))
+        })
+    }
+
+    /// Parse an integer, without sign, and return the remainder of the
+    /// string.
+    ///
+    /// For [`Radix::Hexadecimal`] and [`Radix::Binary`], this also accepts a
+    /// C99/WGSL-style float literal: an optional `.`-delimited fractional
+    /// part followed by a mandatory `p`/`P` binary exponent, e.g. `0x1.8p3`.
+    /// Each fractional digit narrows the value by one radix digit's worth of
+    /// binary places (4 for hex, 1 for binary), which is folded into the
+    /// exponent on the way out as `bin_exponent`.
     fn parse_int<'a>(
         &mut self,
         radix: Radix,
@@ -205,13 +345,59 @@ impl ParsedNumber {
         pos: Span,
     ) -> Result<&'a str, (ParseError, Span)> {
         self.radix = radix;
+        self.bin_exponent = None;
+        let allow_float = matches!(radix, Radix::Hexadecimal | Radix::Binary);
         let mut chars = text.chars();
-        loop {
+        let mut saw_point = false;
+        let mut frac_digits: i32 = 0;
+        let mut last_was_digit = false;
+        let rest = loop {
             let rest = chars.as_str();
             match chars.next() {
+                Some(c) if allow_float && (c == 'p' || c == 'P') => {
+                    self.digits.reverse();
+                    let exp_pos = pos.sub_span(text.len() - rest.len()..);
+                    let (p_value, tail) = parse_exponent(rest, exp_pos, ('p', 'P'))?;
+                    let p_value = match p_value {
+                        Some(value) => value,
+                        None => return Err((ParseError::NoExponentValue, exp_pos)),
+                    };
+                    let bits_per_digit = if let Radix::Hexadecimal = radix { 4 } else { 1 };
+                    self.bin_exponent = Some(p_value.saturating_sub(frac_digits * bits_per_digit));
+                    return Ok(tail);
+                }
+                Some('_') if last_was_digit => {
+                    match after_separator(&chars, |c| parse_digit(c) < radix as u8) {
+                        AfterSeparator::Digit => last_was_digit = false,
+                        AfterSeparator::End => break rest,
+                        AfterSeparator::Other => {
+                            return Err((
+                                ParseError::MisplacedSeparator('_'),
+                                pos.sub_span(
+                                    text.len() - rest.len()..text.len() - chars.as_str().len(),
+                                ),
+                            ));
+                        }
+                    }
+                }
+                Some('_') => {
+                    return Err((
+                        ParseError::MisplacedSeparator('_'),
+                        pos.sub_span(text.len() - rest.len()..text.len() - chars.as_str().len()),
+                    ));
+                }
                 Some(c) => {
                     let d = parse_digit(c);
-                    if d >= radix as u8 {
+                    if d < radix as u8 {
+                        self.digits.push(d);
+                        last_was_digit = true;
+                        if saw_point {
+                            frac_digits += 1;
+                        }
+                    } else if c == '.' && allow_float && !saw_point {
+                        saw_point = true;
+                        last_was_digit = false;
+                    } else {
                         return Err((
                             if d < 10 {
                                 ParseError::InvalidDigit(radix, c)
@@ -225,14 +411,21 @@ impl ParsedNumber {
                             ),
                         ));
                     }
-                    self.digits.push(d);
-                }
-                _ => {
-                    self.digits.reverse();
-                    return Ok(rest);
                 }
+                _ => break rest,
             }
+        };
+        self.digits.reverse();
+        if saw_point {
+            // A fractional hex/binary literal without a `p` exponent is
+            // ambiguous with the digits simply ending early, so -- like a
+            // decimal literal's `e` with no digits after it -- require one.
+            return Err((
+                ParseError::NoExponentValue,
+                pos.sub_span(text.len() - rest.len()..),
+            ));
         }
+        Ok(rest)
     }
 
     /// Parse a decimal number, without sign, and return the remainder of the string.
@@ -245,7 +438,7 @@ impl ParsedNumber {
         }
         let pos = pos.sub_span(toklen - text.len()..);
         self.digits.reverse();
-        let (exponent, text) = parse_exponent(text, pos)?;
+        let (exponent, text) = parse_exponent(text, pos, ('e', 'E'))?;
         self.exponent = match frac_digits {
             Some(count) => Some({
                 let bias = if count > i32::max_value() as usize {
@@ -279,22 +472,74 @@ impl ParsedNumber {
         text: &'a str,
     ) -> Result<(Option<usize>, &'a str), (ParseError, Span)> {
         let mut chars = text.chars();
+        let mut last_was_digit = false;
         let point_pos = loop {
             let rest = chars.as_str();
             match chars.next() {
                 Some(c) => match c {
-                    '0'..='9' => self.digits.push((c as u32 - '0' as u32) as u8),
+                    '0'..='9' => {
+                        self.digits.push((c as u32 - '0' as u32) as u8);
+                        last_was_digit = true;
+                    }
+                    '_' if last_was_digit => match after_separator(&chars, is_digit) {
+                        AfterSeparator::Digit => last_was_digit = false,
+                        AfterSeparator::End => return Ok((None, rest)),
+                        AfterSeparator::Other => {
+                            return Err((
+                                ParseError::MisplacedSeparator('_'),
+                                Span {
+                                    start: Pos((toklen - rest.len()) as u32),
+                                    end: Pos((toklen - chars.as_str().len()) as u32),
+                                },
+                            ));
+                        }
+                    },
+                    '_' => {
+                        return Err((
+                            ParseError::MisplacedSeparator('_'),
+                            Span {
+                                start: Pos((toklen - rest.len()) as u32),
+                                end: Pos((toklen - chars.as_str().len()) as u32),
+                            },
+                        ));
+                    }
                     '.' => break self.digits.len(),
                     _ => return Ok((None, rest)),
                 },
                 _ => return Ok((None, rest)),
             }
         };
+        last_was_digit = false;
         let rest = loop {
             let rest = chars.as_str();
             match chars.next() {
                 Some(c) => match c {
-                    '0'..='9' => self.digits.push((c as u32 - '0' as u32) as u8),
+                    '0'..='9' => {
+                        self.digits.push((c as u32 - '0' as u32) as u8);
+                        last_was_digit = true;
+                    }
+                    '_' if last_was_digit => match after_separator(&chars, is_digit) {
+                        AfterSeparator::Digit => last_was_digit = false,
+                        AfterSeparator::End => break rest,
+                        AfterSeparator::Other => {
+                            return Err((
+                                ParseError::MisplacedSeparator('_'),
+                                Span {
+                                    start: Pos((toklen - rest.len()) as u32),
+                                    end: Pos((toklen - chars.as_str().len()) as u32),
+                                },
+                            ));
+                        }
+                    },
+                    '_' => {
+                        return Err((
+                            ParseError::MisplacedSeparator('_'),
+                            Span {
+                                start: Pos((toklen - rest.len()) as u32),
+                                end: Pos((toklen - chars.as_str().len()) as u32),
+                            },
+                        ));
+                    }
                     '.' => {
                         return Err((
                             ParseError::ExtraPoint,
@@ -332,8 +577,1012 @@ impl ParsedNumber {
             self.exponent = Some(exponent.saturating_add(n as i32));
         }
     }
+
+    /// Convert to the nearest `f64`, rounded to nearest with ties to even --
+    /// the same result `text.parse::<f64>()` would give for this number's
+    /// canonical decimal text (see [`ParsedNumber`]'s `ToString` impl). For
+    /// a non-decimal radix, the integer is rounded to the nearest `f64` and
+    /// then scaled by `bin_exponent`.
+    pub fn to_f64(&self) -> f64 {
+        let value = match self.radix {
+            Radix::Decimal => self.decimal_to_f64(),
+            _ => self.integer_to_f64(),
+        };
+        match self.sign {
+            Sign::Positive => value,
+            Sign::Negative => -value,
+        }
+    }
+
+    /// Convert to the nearest `f32`, the same way [`ParsedNumber::to_f64`]
+    /// does for `f64`.
+    pub fn to_f32(&self) -> f32 {
+        let value = match self.radix {
+            Radix::Decimal => self.decimal_to_f32(),
+            _ => self.integer_to_f32(),
+        };
+        match self.sign {
+            Sign::Positive => value,
+            Sign::Negative => -value,
+        }
+    }
+
+    /// Fold up to the 19 most significant decimal digits into a `u64`
+    /// mantissa `m`, along with the power of ten `q` it needs to be scaled
+    /// by -- `self`'s unsigned value is `m * 10^q`. Returns `None` if there
+    /// are more than 19 significant digits, which is more than a `u64` can
+    /// hold exactly.
+    fn decimal_mantissa(&self) -> Option<(u64, i32)> {
+        let significant = self.digits.iter().rposition(|&d| d != 0)? + 1;
+        if significant > 19 {
+            return None;
+        }
+        let mut m: u64 = 0;
+        for &d in self.digits[..significant].iter().rev() {
+            m = m * 10 + d as u64;
+        }
+        Some((m, self.exponent.unwrap_or(0)))
+    }
+
+    /// This number's digits and exponent, written out as plain decimal text
+    /// (no sign), for [`str::parse`] to fall back on when the fast path in
+    /// [`ParsedNumber::decimal_to_f64`]/[`ParsedNumber::decimal_to_f32`]
+    /// doesn't apply.
+    fn decimal_text(&self) -> String {
+        use std::fmt::Write;
+        let mut s = String::with_capacity(self.digits.len() + 8);
+        if self.digits.is_empty() {
+            s.push('0');
+        } else {
+            for &d in self.digits.iter().rev() {
+                s.push((b'0' + d) as char);
+            }
+        }
+        if let Some(exponent) = self.exponent {
+            write!(&mut s, "e{}", exponent).unwrap();
+        }
+        s
+    }
+
+    fn decimal_to_f64(&self) -> f64 {
+        if self.digits.iter().all(|&d| d == 0) {
+            return 0.0;
+        }
+        if let Some((m, q)) = self.decimal_mantissa() {
+            if let Some(value) = fast_decimal_f64(m, q) {
+                return value;
+            }
+            if let Some((mantissa, exp)) = eisel_lemire(m, q, 52, -1022, 1023) {
+                return f64::from_bits(((exp + 1023) as u64) << 52 | mantissa);
+            }
+        }
+        // More than 19 significant digits, or an exponent so large/small
+        // the result is subnormal or overflows, or (extremely rarely) the
+        // Eisel-Lemire fast path above landed exactly on a rounding tie it
+        // can't resolve on its own: defer to the standard library's own
+        // correctly-rounded parser rather than re-deriving its big-integer
+        // fallback from scratch.
+        self.decimal_text().parse().unwrap()
+    }
+
+    fn decimal_to_f32(&self) -> f32 {
+        if self.digits.iter().all(|&d| d == 0) {
+            return 0.0;
+        }
+        if let Some((m, q)) = self.decimal_mantissa() {
+            if let Some(value) = fast_decimal_f32(m, q) {
+                return value;
+            }
+            if let Some((mantissa, exp)) = eisel_lemire(m, q, 23, -126, 127) {
+                return f32::from_bits(((exp + 127) as u32) << 23 | mantissa as u32);
+            }
+        }
+        self.decimal_text().parse().unwrap()
+    }
+
+    /// Fold this non-decimal-radix number's digits into the exact integer
+    /// they spell out, as a `u128`, falling back to a (not correctly
+    /// rounded) running float product for the pathological case of an
+    /// integer literal too wide to fit even that.
+    fn integer_value(&self) -> Result<u128, f64> {
+        let radix = self.radix as u128;
+        let mut acc: u128 = 0;
+        for &d in self.digits.iter().rev() {
+            acc = match acc
+                .checked_mul(radix)
+                .and_then(|acc| acc.checked_add(d as u128))
+            {
+                Some(acc) => acc,
+                None => {
+                    let mut value = 0f64;
+                    for &d in self.digits.iter().rev() {
+                        value = value * (radix as f64) + d as f64;
+                    }
+                    return Err(value);
+                }
+            };
+        }
+        Ok(acc)
+    }
+
+    fn integer_to_f64(&self) -> f64 {
+        let value = match self.integer_value() {
+            Ok(value) => value as f64,
+            Err(value) => value,
+        };
+        // Scaling by a power of two is exact (it only shifts the binary
+        // exponent field), so this adds no rounding beyond whatever `value`
+        // already picked up above.
+        match self.bin_exponent {
+            Some(exp) => value * 2f64.powi(exp),
+            None => value,
+        }
+    }
+
+    fn integer_to_f32(&self) -> f32 {
+        let value = match self.integer_value() {
+            Ok(value) => value as f32,
+            Err(value) => value as f32,
+        };
+        match self.bin_exponent {
+            Some(exp) => value * 2f32.powi(exp),
+            None => value,
+        }
+    }
+
+    /// This number's exact magnitude as a `u128`, for the integer
+    /// conversions in `impl TryFrom<&ParsedNumber>`. Returns `None` if the
+    /// number has a fractional part -- a negative decimal `exponent`, or a
+    /// negative `bin_exponent` from a hex/binary float literal -- or if the
+    /// magnitude doesn't fit in a `u128`.
+    fn to_u128(&self) -> Option<u128> {
+        match self.radix {
+            Radix::Decimal => {
+                let exponent = self.exponent.unwrap_or(0);
+                if exponent < 0 {
+                    return None;
+                }
+                let mut value: u128 = 0;
+                for &d in self.digits.iter().rev() {
+                    value = value.checked_mul(10)?.checked_add(d as u128)?;
+                }
+                value.checked_mul(10u128.checked_pow(u32::try_from(exponent).ok()?)?)
+            }
+            _ => {
+                if matches!(self.bin_exponent, Some(exp) if exp < 0) {
+                    return None;
+                }
+                let value = self.integer_value().ok()?;
+                match self.bin_exponent {
+                    Some(exp) => value.checked_mul(2u128.checked_pow(u32::try_from(exp).ok()?)?),
+                    None => Some(value),
+                }
+            }
+        }
+    }
 }
 
+impl FromStr for ParsedNumber {
+    type Err = ParseError;
+
+    /// Parse the entire string as a number, with no leftover text allowed --
+    /// unlike [`ParsedNumber::parse`], which returns whatever follows the
+    /// number (a unit suffix, for instance) as a remainder instead of
+    /// treating it as an error.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut num = ParsedNumber::new();
+        let pos = Span { start: Pos(0), end: Pos(text.len() as u32) };
+        let rest = num.parse(text, pos).map_err(|(e, _)| e)?;
+        match rest.chars().next() {
+            Some(c) => Err(ParseError::UnexpectedChar(c)),
+            None => Ok(num),
+        }
+    }
+}
+
+impl TryFrom<&ParsedNumber> for f64 {
+    type Error = ConvertError;
+
+    /// Always succeeds -- every [`ParsedNumber`] has some representation as
+    /// an `f64`, even if it's infinity.
+    fn try_from(num: &ParsedNumber) -> Result<Self, Self::Error> {
+        Ok(num.to_f64())
+    }
+}
+
+impl TryFrom<&ParsedNumber> for i64 {
+    type Error = ConvertError;
+
+    fn try_from(num: &ParsedNumber) -> Result<Self, Self::Error> {
+        let magnitude = num.to_u128().ok_or(ConvertError::Overflow)?;
+        match num.sign {
+            Sign::Positive => i64::try_from(magnitude).map_err(|_| ConvertError::Overflow),
+            Sign::Negative => {
+                if magnitude == i64::MIN.unsigned_abs() as u128 {
+                    Ok(i64::MIN)
+                } else {
+                    i64::try_from(magnitude)
+                        .map(|v| -v)
+                        .map_err(|_| ConvertError::Overflow)
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<&ParsedNumber> for u32 {
+    type Error = ConvertError;
+
+    fn try_from(num: &ParsedNumber) -> Result<Self, Self::Error> {
+        let magnitude = num.to_u128().ok_or(ConvertError::Overflow)?;
+        if num.sign == Sign::Negative && magnitude != 0 {
+            return Err(ConvertError::Overflow);
+        }
+        u32::try_from(magnitude).map_err(|_| ConvertError::Overflow)
+    }
+}
+
+/// Exact powers of ten representable as `f64` -- `10^0` through `10^22` are
+/// the only ones that round-trip exactly, which is what the fast paths in
+/// [`fast_decimal_f64`]/[`fast_decimal_f32`] rely on.
+const POW10_F64: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Clinger's fast path: `m * 10^q` is correctly rounded by a single `f64`
+/// multiplication or division -- with no intermediate rounding error to
+/// compound -- whenever `m` and `10^|q|` are both exactly representable as
+/// `f64` and the exponent is small enough that the single operation can't
+/// straddle a rounding boundary, which holds for `m <= 2^53` and
+/// `-22 <= q <= 22`.
+fn fast_decimal_f64(m: u64, q: i32) -> Option<f64> {
+    if m > (1u64 << 53) || !(-22..=22).contains(&q) {
+        return None;
+    }
+    let m = m as f64;
+    Some(if q >= 0 {
+        m * POW10_F64[q as usize]
+    } else {
+        m / POW10_F64[(-q) as usize]
+    })
+}
+
+/// The `f32` analog of [`fast_decimal_f64`]: computed in `f64` (since `m`
+/// and `10^|q|` are still exactly representable there) and narrowed to
+/// `f32` in a single final rounding step, to avoid rounding twice. Correct
+/// for `m <= 2^24` and `-10 <= q <= 10`.
+fn fast_decimal_f32(m: u64, q: i32) -> Option<f32> {
+    if m > (1u64 << 24) || !(-10..=10).contains(&q) {
+        return None;
+    }
+    let m = m as f64;
+    let value = if q >= 0 {
+        m * POW10_F64[q as usize]
+    } else {
+        m / POW10_F64[(-q) as usize]
+    };
+    Some(value as f32)
+}
+
+/// Eisel-Lemire's algorithm: the next tier after [`fast_decimal_f64`]/
+/// [`fast_decimal_f32`], covering the much wider range of `m`/`q` those
+/// can't handle without falling all the way back to [`str::parse`].
+/// `m * 5^q` is computed as an exact 192-bit product against a 128-bit
+/// truncated-or-rounded approximation of `5^q` from `POW5_TABLE`, which is
+/// enough bits of slack that the rounding of the top `mantissa_bits + 1`
+/// bits is only ever in doubt when the truncated bits land exactly on a
+/// rounding tie *and* the table entry itself isn't exact -- that combination
+/// is rare enough to just report it as `None` and let the slow path settle
+/// it. Returns `(mantissa, exponent)` with `mantissa` occupying exactly
+/// `mantissa_bits + 1` bits (including the implicit leading one) such that
+/// `m * 10^q == mantissa * 2^exponent`, or `None` if `q` falls outside
+/// `POW5_TABLE`, the result would be subnormal or overflow, or the rounding
+/// above is ambiguous.
+fn eisel_lemire(m: u64, q: i32, mantissa_bits: u32, exp_min: i32, exp_max: i32) -> Option<(u64, i32)> {
+    if q < POW5_QMIN || q > POW5_QMAX {
+        return None;
+    }
+    let clz = m.leading_zeros();
+    let m_norm = m << clz;
+    let (hi5, lo5, e5, exact5) = POW5_TABLE[(q - POW5_QMIN) as usize];
+    let hi_prod = (m_norm as u128) * (hi5 as u128);
+    let lo_prod = (m_norm as u128) * (lo5 as u128);
+    let total = hi_prod + (lo_prod >> 64);
+    let lo_prod_lo = lo_prod as u64;
+
+    // `total` is the top 128 bits of the exact 192-bit product `m_norm *
+    // 5^q` (scaled); its true leading bit sits at 126 or 127 depending on
+    // whether the multiplication carried an extra bit.
+    let msb = (total >> 127) as i32;
+    let shift = 126 + msb - mantissa_bits as i32;
+    let halfway = 1u128 << (shift - 1);
+    let remainder = total & ((halfway << 1) - 1);
+    let mut mantissa = (total >> shift) as u64;
+
+    let round_bit = remainder & halfway != 0;
+    let sticky = (remainder & (halfway - 1)) != 0 || lo_prod_lo != 0;
+    if round_bit && !sticky && !exact5 {
+        return None;
+    }
+    if round_bit && (sticky || mantissa & 1 == 1) {
+        mantissa += 1;
+    }
+    let mut binary_exp = shift + q - clz as i32 + e5 as i32 - 64;
+    if mantissa >> (mantissa_bits + 1) != 0 {
+        mantissa >>= 1;
+        binary_exp += 1;
+    }
+    let float_exp = binary_exp + mantissa_bits as i32;
+    if float_exp < exp_min || float_exp > exp_max {
+        return None;
+    }
+    Some((mantissa & ((1u64 << mantissa_bits) - 1), float_exp))
+}
+
+const POW5_QMIN: i32 = -342;
+const POW5_QMAX: i32 = 308;
+
+/// `POW5_TABLE[q - POW5_QMIN]` is the normalized 128-bit significand
+/// (MSB set) of `5^q`, as `(hi, lo)`, together with the binary exponent
+/// `e5` such that `5^q == (hi as u128) << 64 | lo as u128) * 2^(e5 - 128)`
+/// and a flag for whether that 128-bit value is exact (vs. rounded to
+/// nearest-even -- only possible for `q` small enough that `5^q` or its
+/// reciprocal fits in 128 bits without truncation).
+static POW5_TABLE: [(u64, u64, i16, bool); 651] = [
+    (0xeef453d6923bd65a, 0x113faa2906a13b40, -794, false), // 5^-342
+    (0x9558b4661b6565f8, 0x4ac7ca59a424c508, -791, false), // 5^-341
+    (0xbaaee17fa23ebf76, 0x5d79bcf00d2df64a, -789, false), // 5^-340
+    (0xe95a99df8ace6f53, 0xf4d82c2c107973dc, -787, false), // 5^-339
+    (0x91d8a02bb6c10594, 0x79071b9b8a4be86a, -784, false), // 5^-338
+    (0xb64ec836a47146f9, 0x9748e2826cdee284, -782, false), // 5^-337
+    (0xe3e27a444d8d98b7, 0xfd1b1b2308169b25, -780, false), // 5^-336
+    (0x8e6d8c6ab0787f72, 0xfe30f0f5e50e20f7, -777, false), // 5^-335
+    (0xb208ef855c969f4f, 0xbdbd2d335e51a935, -775, false), // 5^-334
+    (0xde8b2b66b3bc4723, 0xad2c788035e61382, -773, false), // 5^-333
+    (0x8b16fb203055ac76, 0x4c3bcb5021afcc31, -770, false), // 5^-332
+    (0xaddcb9e83c6b1793, 0xdf4abe242a1bbf3e, -768, false), // 5^-331
+    (0xd953e8624b85dd78, 0xd71d6dad34a2af0d, -766, false), // 5^-330
+    (0x87d4713d6f33aa6b, 0x8672648c40e5ad68, -763, false), // 5^-329
+    (0xa9c98d8ccb009506, 0x680efdaf511f18c2, -761, false), // 5^-328
+    (0xd43bf0effdc0ba48, 0x0212bd1b2566def3, -759, false), // 5^-327
+    (0x84a57695fe98746d, 0x014bb630f7604b58, -756, false), // 5^-326
+    (0xa5ced43b7e3e9188, 0x419ea3bd35385e2e, -754, false), // 5^-325
+    (0xcf42894a5dce35ea, 0x52064cac828675b9, -752, false), // 5^-324
+    (0x818995ce7aa0e1b2, 0x7343efebd1940994, -749, false), // 5^-323
+    (0xa1ebfb4219491a1f, 0x1014ebe6c5f90bf9, -747, false), // 5^-322
+    (0xca66fa129f9b60a6, 0xd41a26e077774ef7, -745, false), // 5^-321
+    (0xfd00b897478238d0, 0x8920b098955522b5, -743, false), // 5^-320
+    (0x9e20735e8cb16382, 0x55b46e5f5d5535b1, -740, false), // 5^-319
+    (0xc5a890362fddbc62, 0xeb2189f734aa831d, -738, false), // 5^-318
+    (0xf712b443bbd52b7b, 0xa5e9ec7501d523e4, -736, false), // 5^-317
+    (0x9a6bb0aa55653b2d, 0x47b233c92125366f, -733, false), // 5^-316
+    (0xc1069cd4eabe89f8, 0x999ec0bb696e840a, -731, false), // 5^-315
+    (0xf148440a256e2c76, 0xc00670ea43ca250d, -729, false), // 5^-314
+    (0x96cd2a865764dbca, 0x380406926a5e5728, -726, false), // 5^-313
+    (0xbc807527ed3e12bc, 0xc605083704f5ecf2, -724, false), // 5^-312
+    (0xeba09271e88d976b, 0xf7864a44c633682f, -722, false), // 5^-311
+    (0x93445b8731587ea3, 0x7ab3ee6afbe0211d, -719, false), // 5^-310
+    (0xb8157268fdae9e4c, 0x5960ea05bad82965, -717, false), // 5^-309
+    (0xe61acf033d1a45df, 0x6fb92487298e33be, -715, false), // 5^-308
+    (0x8fd0c16206306bab, 0xa5d3b6d479f8e057, -712, false), // 5^-307
+    (0xb3c4f1ba87bc8696, 0x8f48a4899877186c, -710, false), // 5^-306
+    (0xe0b62e2929aba83c, 0x331acdabfe94de87, -708, false), // 5^-305
+    (0x8c71dcd9ba0b4925, 0x9ff0c08b7f1d0b15, -705, false), // 5^-304
+    (0xaf8e5410288e1b6f, 0x07ecf0ae5ee44dda, -703, false), // 5^-303
+    (0xdb71e91432b1a24a, 0xc9e82cd9f69d6150, -701, false), // 5^-302
+    (0x892731ac9faf056e, 0xbe311c083a225cd2, -698, false), // 5^-301
+    (0xab70fe17c79ac6ca, 0x6dbd630a48aaf407, -696, false), // 5^-300
+    (0xd64d3d9db981787d, 0x092cbbccdad5b108, -694, false), // 5^-299
+    (0x85f0468293f0eb4e, 0x25bbf56008c58ea5, -691, false), // 5^-298
+    (0xa76c582338ed2621, 0xaf2af2b80af6f24e, -689, false), // 5^-297
+    (0xd1476e2c07286faa, 0x1af5af660db4aee2, -687, false), // 5^-296
+    (0x82cca4db847945ca, 0x50d98d9fc890ed4d, -684, false), // 5^-295
+    (0xa37fce126597973c, 0xe50ff107bab528a1, -682, false), // 5^-294
+    (0xcc5fc196fefd7d0c, 0x1e53ed49a96272c9, -680, false), // 5^-293
+    (0xff77b1fcbebcdc4f, 0x25e8e89c13bb0f7b, -678, false), // 5^-292
+    (0x9faacf3df73609b1, 0x77b191618c54e9ad, -675, false), // 5^-291
+    (0xc795830d75038c1d, 0xd59df5b9ef6a2418, -673, false), // 5^-290
+    (0xf97ae3d0d2446f25, 0x4b0573286b44ad1e, -671, false), // 5^-289
+    (0x9becce62836ac577, 0x4ee367f9430aec33, -668, false), // 5^-288
+    (0xc2e801fb244576d5, 0x229c41f793cda73f, -666, false), // 5^-287
+    (0xf3a20279ed56d48a, 0x6b43527578c1110f, -664, false), // 5^-286
+    (0x9845418c345644d6, 0x830a13896b78aaaa, -661, false), // 5^-285
+    (0xbe5691ef416bd60c, 0x23cc986bc656d554, -659, false), // 5^-284
+    (0xedec366b11c6cb8f, 0x2cbfbe86b7ec8aa9, -657, false), // 5^-283
+    (0x94b3a202eb1c3f39, 0x7bf7d71432f3d6aa, -654, false), // 5^-282
+    (0xb9e08a83a5e34f07, 0xdaf5ccd93fb0cc54, -652, false), // 5^-281
+    (0xe858ad248f5c22c9, 0xd1b3400f8f9cff69, -650, false), // 5^-280
+    (0x91376c36d99995be, 0x23100809b9c21fa2, -647, false), // 5^-279
+    (0xb58547448ffffb2d, 0xabd40a0c2832a78a, -645, false), // 5^-278
+    (0xe2e69915b3fff9f9, 0x16c90c8f323f516d, -643, false), // 5^-277
+    (0x8dd01fad907ffc3b, 0xae3da7d97f6792e4, -640, false), // 5^-276
+    (0xb1442798f49ffb4a, 0x99cd11cfdf41779d, -638, false), // 5^-275
+    (0xdd95317f31c7fa1d, 0x40405643d711d584, -636, false), // 5^-274
+    (0x8a7d3eef7f1cfc52, 0x482835ea666b2572, -633, false), // 5^-273
+    (0xad1c8eab5ee43b66, 0xda3243650005eecf, -631, false), // 5^-272
+    (0xd863b256369d4a40, 0x90bed43e40076a83, -629, false), // 5^-271
+    (0x873e4f75e2224e68, 0x5a7744a6e804a292, -626, false), // 5^-270
+    (0xa90de3535aaae202, 0x711515d0a205cb36, -624, false), // 5^-269
+    (0xd3515c2831559a83, 0x0d5a5b44ca873e04, -622, false), // 5^-268
+    (0x8412d9991ed58091, 0xe858790afe9486c2, -619, false), // 5^-267
+    (0xa5178fff668ae0b6, 0x626e974dbe39a873, -617, false), // 5^-266
+    (0xce5d73ff402d98e3, 0xfb0a3d212dc81290, -615, false), // 5^-265
+    (0x80fa687f881c7f8e, 0x7ce66634bc9d0b9a, -612, false), // 5^-264
+    (0xa139029f6a239f72, 0x1c1fffc1ebc44e80, -610, false), // 5^-263
+    (0xc987434744ac874e, 0xa327ffb266b56220, -608, false), // 5^-262
+    (0xfbe9141915d7a922, 0x4bf1ff9f0062baa8, -606, false), // 5^-261
+    (0x9d71ac8fada6c9b5, 0x6f773fc3603db4a9, -603, false), // 5^-260
+    (0xc4ce17b399107c22, 0xcb550fb4384d21d4, -601, false), // 5^-259
+    (0xf6019da07f549b2b, 0x7e2a53a146606a48, -599, false), // 5^-258
+    (0x99c102844f94e0fb, 0x2eda7444cbfc426d, -596, false), // 5^-257
+    (0xc0314325637a1939, 0xfa911155fefb5309, -594, false), // 5^-256
+    (0xf03d93eebc589f88, 0x793555ab7eba27cb, -592, false), // 5^-255
+    (0x96267c7535b763b5, 0x4bc1558b2f3458df, -589, false), // 5^-254
+    (0xbbb01b9283253ca2, 0x9eb1aaedfb016f16, -587, false), // 5^-253
+    (0xea9c227723ee8bcb, 0x465e15a979c1cadc, -585, false), // 5^-252
+    (0x92a1958a7675175f, 0x0bfacd89ec191eca, -582, false), // 5^-251
+    (0xb749faed14125d36, 0xcef980ec671f667c, -580, false), // 5^-250
+    (0xe51c79a85916f484, 0x82b7e12780e7401b, -578, false), // 5^-249
+    (0x8f31cc0937ae58d2, 0xd1b2ecb8b0908811, -575, false), // 5^-248
+    (0xb2fe3f0b8599ef07, 0x861fa7e6dcb4aa15, -573, false), // 5^-247
+    (0xdfbdcece67006ac9, 0x67a791e093e1d49a, -571, false), // 5^-246
+    (0x8bd6a141006042bd, 0xe0c8bb2c5c6d24e0, -568, false), // 5^-245
+    (0xaecc49914078536d, 0x58fae9f773886e19, -566, false), // 5^-244
+    (0xda7f5bf590966848, 0xaf39a475506a899f, -564, false), // 5^-243
+    (0x888f99797a5e012d, 0x6d8406c952429603, -561, false), // 5^-242
+    (0xaab37fd7d8f58178, 0xc8e5087ba6d33b84, -559, false), // 5^-241
+    (0xd5605fcdcf32e1d6, 0xfb1e4a9a90880a65, -557, false), // 5^-240
+    (0x855c3be0a17fcd26, 0x5cf2eea09a55067f, -554, false), // 5^-239
+    (0xa6b34ad8c9dfc06f, 0xf42faa48c0ea481f, -552, false), // 5^-238
+    (0xd0601d8efc57b08b, 0xf13b94daf124da27, -550, false), // 5^-237
+    (0x823c12795db6ce57, 0x76c53d08d6b70858, -547, false), // 5^-236
+    (0xa2cb1717b52481ed, 0x54768c4b0c64ca6e, -545, false), // 5^-235
+    (0xcb7ddcdda26da268, 0xa9942f5dcf7dfd0a, -543, false), // 5^-234
+    (0xfe5d54150b090b02, 0xd3f93b35435d7c4c, -541, false), // 5^-233
+    (0x9efa548d26e5a6e1, 0xc47bc5014a1a6db0, -538, false), // 5^-232
+    (0xc6b8e9b0709f109a, 0x359ab6419ca1091b, -536, false), // 5^-231
+    (0xf867241c8cc6d4c0, 0xc30163d203c94b62, -534, false), // 5^-230
+    (0x9b407691d7fc44f8, 0x79e0de63425dcf1d, -531, false), // 5^-229
+    (0xc21094364dfb5636, 0x985915fc12f542e5, -529, false), // 5^-228
+    (0xf294b943e17a2bc4, 0x3e6f5b7b17b2939e, -527, false), // 5^-227
+    (0x979cf3ca6cec5b5a, 0xa705992ceecf9c43, -524, false), // 5^-226
+    (0xbd8430bd08277231, 0x50c6ff782a838353, -522, false), // 5^-225
+    (0xece53cec4a314ebd, 0xa4f8bf5635246428, -520, false), // 5^-224
+    (0x940f4613ae5ed136, 0x871b7795e136be99, -517, false), // 5^-223
+    (0xb913179899f68584, 0x28e2557b59846e3f, -515, false), // 5^-222
+    (0xe757dd7ec07426e5, 0x331aeada2fe589cf, -513, false), // 5^-221
+    (0x9096ea6f3848984f, 0x3ff0d2c85def7622, -510, false), // 5^-220
+    (0xb4bca50b065abe63, 0x0fed077a756b53aa, -508, false), // 5^-219
+    (0xe1ebce4dc7f16dfb, 0xd3e8495912c62894, -506, false), // 5^-218
+    (0x8d3360f09cf6e4bd, 0x64712dd7abbbd95d, -503, false), // 5^-217
+    (0xb080392cc4349dec, 0xbd8d794d96aacfb4, -501, false), // 5^-216
+    (0xdca04777f541c567, 0xecf0d7a0fc5583a1, -499, false), // 5^-215
+    (0x89e42caaf9491b60, 0xf41686c49db57245, -496, false), // 5^-214
+    (0xac5d37d5b79b6239, 0x311c2875c522ced6, -494, false), // 5^-213
+    (0xd77485cb25823ac7, 0x7d633293366b828b, -492, false), // 5^-212
+    (0x86a8d39ef77164bc, 0xae5dff9c02033197, -489, false), // 5^-211
+    (0xa8530886b54dbdeb, 0xd9f57f830283fdfd, -487, false), // 5^-210
+    (0xd267caa862a12d66, 0xd072df63c324fd7c, -485, false), // 5^-209
+    (0x8380dea93da4bc60, 0x4247cb9e59f71e6d, -482, false), // 5^-208
+    (0xa46116538d0deb78, 0x52d9be85f074e609, -480, false), // 5^-207
+    (0xcd795be870516656, 0x67902e276c921f8b, -478, false), // 5^-206
+    (0x806bd9714632dff6, 0x00ba1cd8a3db53b7, -475, false), // 5^-205
+    (0xa086cfcd97bf97f3, 0x80e8a40eccd228a5, -473, false), // 5^-204
+    (0xc8a883c0fdaf7df0, 0x6122cd128006b2ce, -471, false), // 5^-203
+    (0xfad2a4b13d1b5d6c, 0x796b805720085f81, -469, false), // 5^-202
+    (0x9cc3a6eec6311a63, 0xcbe3303674053bb1, -466, false), // 5^-201
+    (0xc3f490aa77bd60fc, 0xbedbfc4411068a9d, -464, false), // 5^-200
+    (0xf4f1b4d515acb93b, 0xee92fb5515482d44, -462, false), // 5^-199
+    (0x991711052d8bf3c5, 0x751bdd152d4d1c4b, -459, false), // 5^-198
+    (0xbf5cd54678eef0b6, 0xd262d45a78a0635d, -457, false), // 5^-197
+    (0xef340a98172aace4, 0x86fb897116c87c35, -455, false), // 5^-196
+    (0x9580869f0e7aac0e, 0xd45d35e6ae3d4da1, -452, false), // 5^-195
+    (0xbae0a846d2195712, 0x8974836059cca109, -450, false), // 5^-194
+    (0xe998d258869facd7, 0x2bd1a438703fc94b, -448, false), // 5^-193
+    (0x91ff83775423cc06, 0x7b6306a34627ddcf, -445, false), // 5^-192
+    (0xb67f6455292cbf08, 0x1a3bc84c17b1d543, -443, false), // 5^-191
+    (0xe41f3d6a7377eeca, 0x20caba5f1d9e4a94, -441, false), // 5^-190
+    (0x8e938662882af53e, 0x547eb47b7282ee9c, -438, false), // 5^-189
+    (0xb23867fb2a35b28d, 0xe99e619a4f23aa43, -436, false), // 5^-188
+    (0xdec681f9f4c31f31, 0x6405fa00e2ec94d4, -434, false), // 5^-187
+    (0x8b3c113c38f9f37e, 0xde83bc408dd3dd05, -431, false), // 5^-186
+    (0xae0b158b4738705e, 0x9624ab50b148d446, -429, false), // 5^-185
+    (0xd98ddaee19068c76, 0x3badd624dd9b0957, -427, false), // 5^-184
+    (0x87f8a8d4cfa417c9, 0xe54ca5d70a80e5d6, -424, false), // 5^-183
+    (0xa9f6d30a038d1dbc, 0x5e9fcf4ccd211f4c, -422, false), // 5^-182
+    (0xd47487cc8470652b, 0x7647c3200069671f, -420, false), // 5^-181
+    (0x84c8d4dfd2c63f3b, 0x29ecd9f40041e073, -417, false), // 5^-180
+    (0xa5fb0a17c777cf09, 0xf468107100525890, -415, false), // 5^-179
+    (0xcf79cc9db955c2cc, 0x7182148d4066eeb4, -413, false), // 5^-178
+    (0x81ac1fe293d599bf, 0xc6f14cd848405531, -410, false), // 5^-177
+    (0xa21727db38cb002f, 0xb8ada00e5a506a7d, -408, false), // 5^-176
+    (0xca9cf1d206fdc03b, 0xa6d90811f0e4851c, -406, false), // 5^-175
+    (0xfd442e4688bd304a, 0x908f4a166d1da663, -404, false), // 5^-174
+    (0x9e4a9cec15763e2e, 0x9a598e4e043287fe, -401, false), // 5^-173
+    (0xc5dd44271ad3cdba, 0x40eff1e1853f29fe, -399, false), // 5^-172
+    (0xf7549530e188c128, 0xd12bee59e68ef47d, -397, false), // 5^-171
+    (0x9a94dd3e8cf578b9, 0x82bb74f8301958ce, -394, false), // 5^-170
+    (0xc13a148e3032d6e7, 0xe36a52363c1faf02, -392, false), // 5^-169
+    (0xf18899b1bc3f8ca1, 0xdc44e6c3cb279ac2, -390, false), // 5^-168
+    (0x96f5600f15a7b7e5, 0x29ab103a5ef8c0b9, -387, false), // 5^-167
+    (0xbcb2b812db11a5de, 0x7415d448f6b6f0e8, -385, false), // 5^-166
+    (0xebdf661791d60f56, 0x111b495b3464ad21, -383, false), // 5^-165
+    (0x936b9fcebb25c995, 0xcab10dd900beec35, -380, false), // 5^-164
+    (0xb84687c269ef3bfb, 0x3d5d514f40eea742, -378, false), // 5^-163
+    (0xe65829b3046b0afa, 0x0cb4a5a3112a5113, -376, false), // 5^-162
+    (0x8ff71a0fe2c2e6dc, 0x47f0e785eaba72ac, -373, false), // 5^-161
+    (0xb3f4e093db73a093, 0x59ed216765690f57, -371, false), // 5^-160
+    (0xe0f218b8d25088b8, 0x306869c13ec3532c, -369, false), // 5^-159
+    (0x8c974f7383725573, 0x1e414218c73a13fc, -366, false), // 5^-158
+    (0xafbd2350644eeacf, 0xe5d1929ef90898fb, -364, false), // 5^-157
+    (0xdbac6c247d62a583, 0xdf45f746b74abf39, -362, false), // 5^-156
+    (0x894bc396ce5da772, 0x6b8bba8c328eb784, -359, false), // 5^-155
+    (0xab9eb47c81f5114f, 0x066ea92f3f326565, -357, false), // 5^-154
+    (0xd686619ba27255a2, 0xc80a537b0efefebe, -355, false), // 5^-153
+    (0x8613fd0145877585, 0xbd06742ce95f5f37, -352, false), // 5^-152
+    (0xa798fc4196e952e7, 0x2c48113823b73704, -350, false), // 5^-151
+    (0xd17f3b51fca3a7a0, 0xf75a15862ca504c5, -348, false), // 5^-150
+    (0x82ef85133de648c4, 0x9a984d73dbe722fb, -345, false), // 5^-149
+    (0xa3ab66580d5fdaf5, 0xc13e60d0d2e0ebba, -343, false), // 5^-148
+    (0xcc963fee10b7d1b3, 0x318df905079926a9, -341, false), // 5^-147
+    (0xffbbcfe994e5c61f, 0xfdf17746497f7053, -339, false), // 5^-146
+    (0x9fd561f1fd0f9bd3, 0xfeb6ea8bedefa634, -336, false), // 5^-145
+    (0xc7caba6e7c5382c8, 0xfe64a52ee96b8fc1, -334, false), // 5^-144
+    (0xf9bd690a1b68637b, 0x3dfdce7aa3c673b1, -332, false), // 5^-143
+    (0x9c1661a651213e2d, 0x06bea10ca65c084f, -329, false), // 5^-142
+    (0xc31bfa0fe5698db8, 0x486e494fcff30a62, -327, false), // 5^-141
+    (0xf3e2f893dec3f126, 0x5a89dba3c3efccfb, -325, false), // 5^-140
+    (0x986ddb5c6b3a76b7, 0xf89629465a75e01d, -322, false), // 5^-139
+    (0xbe89523386091465, 0xf6bbb397f1135824, -320, false), // 5^-138
+    (0xee2ba6c0678b597f, 0x746aa07ded582e2d, -318, false), // 5^-137
+    (0x94db483840b717ef, 0xa8c2a44eb4571cdc, -315, false), // 5^-136
+    (0xba121a4650e4ddeb, 0x92f34d62616ce413, -313, false), // 5^-135
+    (0xe896a0d7e51e1566, 0x77b020baf9c81d18, -311, false), // 5^-134
+    (0x915e2486ef32cd60, 0x0ace1474dc1d122f, -308, false), // 5^-133
+    (0xb5b5ada8aaff80b8, 0x0d819992132456bb, -306, false), // 5^-132
+    (0xe3231912d5bf60e6, 0x10e1fff697ed6c69, -304, false), // 5^-131
+    (0x8df5efabc5979c8f, 0xca8d3ffa1ef463c2, -301, false), // 5^-130
+    (0xb1736b96b6fd83b3, 0xbd308ff8a6b17cb2, -299, false), // 5^-129
+    (0xddd0467c64bce4a0, 0xac7cb3f6d05ddbdf, -297, false), // 5^-128
+    (0x8aa22c0dbef60ee4, 0x6bcdf07a423aa96b, -294, false), // 5^-127
+    (0xad4ab7112eb3929d, 0x86c16c98d2c953c6, -292, false), // 5^-126
+    (0xd89d64d57a607744, 0xe871c7bf077ba8b8, -290, false), // 5^-125
+    (0x87625f056c7c4a8b, 0x11471cd764ad4973, -287, false), // 5^-124
+    (0xa93af6c6c79b5d2d, 0xd598e40d3dd89bcf, -285, false), // 5^-123
+    (0xd389b47879823479, 0x4aff1d108d4ec2c3, -283, false), // 5^-122
+    (0x843610cb4bf160cb, 0xcedf722a585139ba, -280, false), // 5^-121
+    (0xa54394fe1eedb8fe, 0xc2974eb4ee658829, -278, false), // 5^-120
+    (0xce947a3da6a9273e, 0x733d226229feea33, -276, false), // 5^-119
+    (0x811ccc668829b887, 0x0806357d5a3f5260, -273, false), // 5^-118
+    (0xa163ff802a3426a8, 0xca07c2dcb0cf26f8, -271, false), // 5^-117
+    (0xc9bcff6034c13052, 0xfc89b393dd02f0b6, -269, false), // 5^-116
+    (0xfc2c3f3841f17c67, 0xbbac2078d443ace3, -267, false), // 5^-115
+    (0x9d9ba7832936edc0, 0xd54b944b84aa4c0e, -264, false), // 5^-114
+    (0xc5029163f384a931, 0x0a9e795e65d4df11, -262, false), // 5^-113
+    (0xf64335bcf065d37d, 0x4d4617b5ff4a16d6, -260, false), // 5^-112
+    (0x99ea0196163fa42e, 0x504bced1bf8e4e46, -257, false), // 5^-111
+    (0xc06481fb9bcf8d39, 0xe45ec2862f71e1d7, -255, false), // 5^-110
+    (0xf07da27a82c37088, 0x5d767327bb4e5a4d, -253, false), // 5^-109
+    (0x964e858c91ba2655, 0x3a6a07f8d510f870, -250, false), // 5^-108
+    (0xbbe226efb628afea, 0x890489f70a55368c, -248, false), // 5^-107
+    (0xeadab0aba3b2dbe5, 0x2b45ac74ccea842f, -246, false), // 5^-106
+    (0x92c8ae6b464fc96f, 0x3b0b8bc90012929d, -243, false), // 5^-105
+    (0xb77ada0617e3bbcb, 0x09ce6ebb40173745, -241, false), // 5^-104
+    (0xe55990879ddcaabd, 0xcc420a6a101d0516, -239, false), // 5^-103
+    (0x8f57fa54c2a9eab6, 0x9fa946824a12232e, -236, false), // 5^-102
+    (0xb32df8e9f3546564, 0x47939822dc96abf9, -234, false), // 5^-101
+    (0xdff9772470297ebd, 0x59787e2b93bc56f7, -232, false), // 5^-100
+    (0x8bfbea76c619ef36, 0x57eb4edb3c55b65b, -229, false), // 5^-99
+    (0xaefae51477a06b03, 0xede622920b6b23f1, -227, false), // 5^-98
+    (0xdab99e59958885c4, 0xe95fab368e45eced, -225, false), // 5^-97
+    (0x88b402f7fd75539b, 0x11dbcb0218ebb414, -222, false), // 5^-96
+    (0xaae103b5fcd2a881, 0xd652bdc29f26a11a, -220, false), // 5^-95
+    (0xd59944a37c0752a2, 0x4be76d3346f04960, -218, false), // 5^-94
+    (0x857fcae62d8493a5, 0x6f70a4400c562ddc, -215, false), // 5^-93
+    (0xa6dfbd9fb8e5b88e, 0xcb4ccd500f6bb953, -213, false), // 5^-92
+    (0xd097ad07a71f26b2, 0x7e2000a41346a7a8, -211, false), // 5^-91
+    (0x825ecc24c873782f, 0x8ed400668c0c28c9, -208, false), // 5^-90
+    (0xa2f67f2dfa90563b, 0x728900802f0f32fb, -206, false), // 5^-89
+    (0xcbb41ef979346bca, 0x4f2b40a03ad2ffba, -204, false), // 5^-88
+    (0xfea126b7d78186bc, 0xe2f610c84987bfa8, -202, false), // 5^-87
+    (0x9f24b832e6b0f436, 0x0dd9ca7d2df4d7c9, -199, false), // 5^-86
+    (0xc6ede63fa05d3143, 0x91503d1c79720dbb, -197, false), // 5^-85
+    (0xf8a95fcf88747d94, 0x75a44c6397ce912a, -195, false), // 5^-84
+    (0x9b69dbe1b548ce7c, 0xc986afbe3ee11aba, -192, false), // 5^-83
+    (0xc24452da229b021b, 0xfbe85badce996169, -190, false), // 5^-82
+    (0xf2d56790ab41c2a2, 0xfae27299423fb9c3, -188, false), // 5^-81
+    (0x97c560ba6b0919a5, 0xdccd879fc967d41a, -185, false), // 5^-80
+    (0xbdb6b8e905cb600f, 0x5400e987bbc1c921, -183, false), // 5^-79
+    (0xed246723473e3813, 0x290123e9aab23b69, -181, false), // 5^-78
+    (0x9436c0760c86e30b, 0xf9a0b6720aaf6521, -178, false), // 5^-77
+    (0xb94470938fa89bce, 0xf808e40e8d5b3e6a, -176, false), // 5^-76
+    (0xe7958cb87392c2c2, 0xb60b1d1230b20e04, -174, false), // 5^-75
+    (0x90bd77f3483bb9b9, 0xb1c6f22b5e6f48c3, -171, false), // 5^-74
+    (0xb4ecd5f01a4aa828, 0x1e38aeb6360b1af3, -169, false), // 5^-73
+    (0xe2280b6c20dd5232, 0x25c6da63c38de1b0, -167, false), // 5^-72
+    (0x8d590723948a535f, 0x579c487e5a38ad0e, -164, false), // 5^-71
+    (0xb0af48ec79ace837, 0x2d835a9df0c6d852, -162, false), // 5^-70
+    (0xdcdb1b2798182244, 0xf8e431456cf88e66, -160, false), // 5^-69
+    (0x8a08f0f8bf0f156b, 0x1b8e9ecb641b5900, -157, false), // 5^-68
+    (0xac8b2d36eed2dac5, 0xe272467e3d222f40, -155, false), // 5^-67
+    (0xd7adf884aa879177, 0x5b0ed81dcc6abb10, -153, false), // 5^-66
+    (0x86ccbb52ea94baea, 0x98e947129fc2b4ea, -150, false), // 5^-65
+    (0xa87fea27a539e9a5, 0x3f2398d747b36224, -148, false), // 5^-64
+    (0xd29fe4b18e88640e, 0x8eec7f0d19a03aad, -146, false), // 5^-63
+    (0x83a3eeeef9153e89, 0x1953cf68300424ac, -143, false), // 5^-62
+    (0xa48ceaaab75a8e2b, 0x5fa8c3423c052dd7, -141, false), // 5^-61
+    (0xcdb02555653131b6, 0x3792f412cb06794d, -139, false), // 5^-60
+    (0x808e17555f3ebf11, 0xe2bbd88bbee40bd0, -136, false), // 5^-59
+    (0xa0b19d2ab70e6ed6, 0x5b6aceaeae9d0ec4, -134, false), // 5^-58
+    (0xc8de047564d20a8b, 0xf245825a5a445275, -132, false), // 5^-57
+    (0xfb158592be068d2e, 0xeed6e2f0f0d56713, -130, false), // 5^-56
+    (0x9ced737bb6c4183d, 0x55464dd69685606c, -127, false), // 5^-55
+    (0xc428d05aa4751e4c, 0xaa97e14c3c26b887, -125, false), // 5^-54
+    (0xf53304714d9265df, 0xd53dd99f4b3066a8, -123, false), // 5^-53
+    (0x993fe2c6d07b7fab, 0xe546a8038efe4029, -120, false), // 5^-52
+    (0xbf8fdb78849a5f96, 0xde98520472bdd033, -118, false), // 5^-51
+    (0xef73d256a5c0f77c, 0x963e66858f6d4440, -116, false), // 5^-50
+    (0x95a8637627989aad, 0xdde7001379a44aa8, -113, false), // 5^-49
+    (0xbb127c53b17ec159, 0x5560c018580d5d52, -111, false), // 5^-48
+    (0xe9d71b689dde71af, 0xaab8f01e6e10b4a7, -109, false), // 5^-47
+    (0x9226712162ab070d, 0xcab3961304ca70e8, -106, false), // 5^-46
+    (0xb6b00d69bb55c8d1, 0x3d607b97c5fd0d22, -104, false), // 5^-45
+    (0xe45c10c42a2b3b05, 0x8cb89a7db77c506b, -102, false), // 5^-44
+    (0x8eb98a7a9a5b04e3, 0x77f3608e92adb243, -99, false), // 5^-43
+    (0xb267ed1940f1c61c, 0x55f038b237591ed3, -97, false), // 5^-42
+    (0xdf01e85f912e37a3, 0x6b6c46dec52f6688, -95, false), // 5^-41
+    (0x8b61313bbabce2c6, 0x2323ac4b3b3da015, -92, false), // 5^-40
+    (0xae397d8aa96c1b77, 0xabec975e0a0d081b, -90, false), // 5^-39
+    (0xd9c7dced53c72255, 0x96e7bd358c904a21, -88, false), // 5^-38
+    (0x881cea14545c7575, 0x7e50d64177da2e55, -85, false), // 5^-37
+    (0xaa242499697392d2, 0xdde50bd1d5d0b9ea, -83, false), // 5^-36
+    (0xd4ad2dbfc3d07787, 0x955e4ec64b44e864, -81, false), // 5^-35
+    (0x84ec3c97da624ab4, 0xbd5af13bef0b113f, -78, false), // 5^-34
+    (0xa6274bbdd0fadd61, 0xecb1ad8aeacdd58e, -76, false), // 5^-33
+    (0xcfb11ead453994ba, 0x67de18eda5814af2, -74, false), // 5^-32
+    (0x81ceb32c4b43fcf4, 0x80eacf948770ced7, -71, false), // 5^-31
+    (0xa2425ff75e14fc31, 0xa1258379a94d028d, -69, false), // 5^-30
+    (0xcad2f7f5359a3b3e, 0x096ee45813a04330, -67, false), // 5^-29
+    (0xfd87b5f28300ca0d, 0x8bca9d6e188853fc, -65, false), // 5^-28
+    (0x9e74d1b791e07e48, 0x775ea264cf55347e, -62, false), // 5^-27
+    (0xc612062576589dda, 0x95364afe032a819d, -60, false), // 5^-26
+    (0xf79687aed3eec551, 0x3a83ddbd83f52205, -58, false), // 5^-25
+    (0x9abe14cd44753b52, 0xc4926a9672793543, -55, false), // 5^-24
+    (0xc16d9a0095928a27, 0x75b7053c0f178294, -53, false), // 5^-23
+    (0xf1c90080baf72cb1, 0x5324c68b12dd6338, -51, false), // 5^-22
+    (0x971da05074da7bee, 0xd3f6fc16ebca5e03, -48, false), // 5^-21
+    (0xbce5086492111aea, 0x88f4bb1ca6bcf584, -46, false), // 5^-20
+    (0xec1e4a7db69561a5, 0x2b31e9e3d06c32e5, -44, false), // 5^-19
+    (0x9392ee8e921d5d07, 0x3aff322e62439fcf, -41, false), // 5^-18
+    (0xb877aa3236a4b449, 0x09befeb9fad487c3, -39, false), // 5^-17
+    (0xe69594bec44de15b, 0x4c2ebe687989a9b4, -37, false), // 5^-16
+    (0x901d7cf73ab0acd9, 0x0f9d37014bf60a10, -34, false), // 5^-15
+    (0xb424dc35095cd80f, 0x538484c19ef38c94, -32, false), // 5^-14
+    (0xe12e13424bb40e13, 0x2865a5f206b06fba, -30, false), // 5^-13
+    (0x8cbccc096f5088cb, 0xf93f87b7442e45d4, -27, false), // 5^-12
+    (0xafebff0bcb24aafe, 0xf78f69a51539d749, -25, false), // 5^-11
+    (0xdbe6fecebdedd5be, 0xb573440e5a884d1b, -23, false), // 5^-10
+    (0x89705f4136b4a597, 0x31680a88f8953031, -20, false), // 5^-9
+    (0xabcc77118461cefc, 0xfdc20d2b36ba7c3d, -18, false), // 5^-8
+    (0xd6bf94d5e57a42bc, 0x3d32907604691b4d, -16, false), // 5^-7
+    (0x8637bd05af6c69b5, 0xa63f9a49c2c1b110, -13, false), // 5^-6
+    (0xa7c5ac471b478423, 0x0fcf80dc33721d54, -11, false), // 5^-5
+    (0xd1b71758e219652b, 0xd3c36113404ea4a9, -9, false), // 5^-4
+    (0x83126e978d4fdf3b, 0x645a1cac083126e9, -6, false), // 5^-3
+    (0xa3d70a3d70a3d70a, 0x3d70a3d70a3d70a4, -4, false), // 5^-2
+    (0xcccccccccccccccc, 0xcccccccccccccccd, -2, false), // 5^-1
+    (0x8000000000000000, 0x0000000000000000, 1, true), // 5^0
+    (0xa000000000000000, 0x0000000000000000, 3, true), // 5^1
+    (0xc800000000000000, 0x0000000000000000, 5, true), // 5^2
+    (0xfa00000000000000, 0x0000000000000000, 7, true), // 5^3
+    (0x9c40000000000000, 0x0000000000000000, 10, true), // 5^4
+    (0xc350000000000000, 0x0000000000000000, 12, true), // 5^5
+    (0xf424000000000000, 0x0000000000000000, 14, true), // 5^6
+    (0x9896800000000000, 0x0000000000000000, 17, true), // 5^7
+    (0xbebc200000000000, 0x0000000000000000, 19, true), // 5^8
+    (0xee6b280000000000, 0x0000000000000000, 21, true), // 5^9
+    (0x9502f90000000000, 0x0000000000000000, 24, true), // 5^10
+    (0xba43b74000000000, 0x0000000000000000, 26, true), // 5^11
+    (0xe8d4a51000000000, 0x0000000000000000, 28, true), // 5^12
+    (0x9184e72a00000000, 0x0000000000000000, 31, true), // 5^13
+    (0xb5e620f480000000, 0x0000000000000000, 33, true), // 5^14
+    (0xe35fa931a0000000, 0x0000000000000000, 35, true), // 5^15
+    (0x8e1bc9bf04000000, 0x0000000000000000, 38, true), // 5^16
+    (0xb1a2bc2ec5000000, 0x0000000000000000, 40, true), // 5^17
+    (0xde0b6b3a76400000, 0x0000000000000000, 42, true), // 5^18
+    (0x8ac7230489e80000, 0x0000000000000000, 45, true), // 5^19
+    (0xad78ebc5ac620000, 0x0000000000000000, 47, true), // 5^20
+    (0xd8d726b7177a8000, 0x0000000000000000, 49, true), // 5^21
+    (0x878678326eac9000, 0x0000000000000000, 52, true), // 5^22
+    (0xa968163f0a57b400, 0x0000000000000000, 54, true), // 5^23
+    (0xd3c21bcecceda100, 0x0000000000000000, 56, true), // 5^24
+    (0x84595161401484a0, 0x0000000000000000, 59, true), // 5^25
+    (0xa56fa5b99019a5c8, 0x0000000000000000, 61, true), // 5^26
+    (0xcecb8f27f4200f3a, 0x0000000000000000, 63, true), // 5^27
+    (0x813f3978f8940984, 0x4000000000000000, 66, true), // 5^28
+    (0xa18f07d736b90be5, 0x5000000000000000, 68, true), // 5^29
+    (0xc9f2c9cd04674ede, 0xa400000000000000, 70, true), // 5^30
+    (0xfc6f7c4045812296, 0x4d00000000000000, 72, true), // 5^31
+    (0x9dc5ada82b70b59d, 0xf020000000000000, 75, true), // 5^32
+    (0xc5371912364ce305, 0x6c28000000000000, 77, true), // 5^33
+    (0xf684df56c3e01bc6, 0xc732000000000000, 79, true), // 5^34
+    (0x9a130b963a6c115c, 0x3c7f400000000000, 82, true), // 5^35
+    (0xc097ce7bc90715b3, 0x4b9f100000000000, 84, true), // 5^36
+    (0xf0bdc21abb48db20, 0x1e86d40000000000, 86, true), // 5^37
+    (0x96769950b50d88f4, 0x1314448000000000, 89, true), // 5^38
+    (0xbc143fa4e250eb31, 0x17d955a000000000, 91, true), // 5^39
+    (0xeb194f8e1ae525fd, 0x5dcfab0800000000, 93, true), // 5^40
+    (0x92efd1b8d0cf37be, 0x5aa1cae500000000, 96, true), // 5^41
+    (0xb7abc627050305ad, 0xf14a3d9e40000000, 98, true), // 5^42
+    (0xe596b7b0c643c719, 0x6d9ccd05d0000000, 100, true), // 5^43
+    (0x8f7e32ce7bea5c6f, 0xe4820023a2000000, 103, true), // 5^44
+    (0xb35dbf821ae4f38b, 0xdda2802c8a800000, 105, true), // 5^45
+    (0xe0352f62a19e306e, 0xd50b2037ad200000, 107, true), // 5^46
+    (0x8c213d9da502de45, 0x4526f422cc340000, 110, true), // 5^47
+    (0xaf298d050e4395d6, 0x9670b12b7f410000, 112, true), // 5^48
+    (0xdaf3f04651d47b4c, 0x3c0cdd765f114000, 114, true), // 5^49
+    (0x88d8762bf324cd0f, 0xa5880a69fb6ac800, 117, true), // 5^50
+    (0xab0e93b6efee0053, 0x8eea0d047a457a00, 119, true), // 5^51
+    (0xd5d238a4abe98068, 0x72a4904598d6d880, 121, true), // 5^52
+    (0x85a36366eb71f041, 0x47a6da2b7f864750, 124, true), // 5^53
+    (0xa70c3c40a64e6c51, 0x999090b65f67d924, 126, true), // 5^54
+    (0xd0cf4b50cfe20765, 0xfff4b4e3f741cf6d, 128, true), // 5^55
+    (0x82818f1281ed449f, 0xbff8f10e7a8921a4, 131, false), // 5^56
+    (0xa321f2d7226895c7, 0xaff72d52192b6a0d, 133, false), // 5^57
+    (0xcbea6f8ceb02bb39, 0x9bf4f8a69f764490, 135, false), // 5^58
+    (0xfee50b7025c36a08, 0x02f236d04753d5b5, 137, false), // 5^59
+    (0x9f4f2726179a2245, 0x01d762422c946591, 140, false), // 5^60
+    (0xc722f0ef9d80aad6, 0x424d3ad2b7b97ef5, 142, false), // 5^61
+    (0xf8ebad2b84e0d58b, 0xd2e0898765a7deb2, 144, false), // 5^62
+    (0x9b934c3b330c8577, 0x63cc55f49f88eb2f, 147, false), // 5^63
+    (0xc2781f49ffcfa6d5, 0x3cbf6b71c76b25fb, 149, false), // 5^64
+    (0xf316271c7fc3908a, 0x8bef464e3945ef7a, 151, false), // 5^65
+    (0x97edd871cfda3a56, 0x97758bf0e3cbb5ac, 154, false), // 5^66
+    (0xbde94e8e43d0c8ec, 0x3d52eeed1cbea317, 156, false), // 5^67
+    (0xed63a231d4c4fb27, 0x4ca7aaa863ee4bdd, 158, false), // 5^68
+    (0x945e455f24fb1cf8, 0x8fe8caa93e74ef6a, 161, false), // 5^69
+    (0xb975d6b6ee39e436, 0xb3e2fd538e122b45, 163, false), // 5^70
+    (0xe7d34c64a9c85d44, 0x60dbbca87196b616, 165, false), // 5^71
+    (0x90e40fbeea1d3a4a, 0xbc8955e946fe31ce, 168, false), // 5^72
+    (0xb51d13aea4a488dd, 0x6babab6398bdbe41, 170, false), // 5^73
+    (0xe264589a4dcdab14, 0xc696963c7eed2dd2, 172, false), // 5^74
+    (0x8d7eb76070a08aec, 0xfc1e1de5cf543ca3, 175, false), // 5^75
+    (0xb0de65388cc8ada8, 0x3b25a55f43294bcc, 177, false), // 5^76
+    (0xdd15fe86affad912, 0x49ef0eb713f39ebf, 179, false), // 5^77
+    (0x8a2dbf142dfcc7ab, 0x6e3569326c784337, 182, false), // 5^78
+    (0xacb92ed9397bf996, 0x49c2c37f07965405, 184, false), // 5^79
+    (0xd7e77a8f87daf7fb, 0xdc33745ec97be906, 186, false), // 5^80
+    (0x86f0ac99b4e8dafd, 0x69a028bb3ded71a4, 189, false), // 5^81
+    (0xa8acd7c0222311bc, 0xc40832ea0d68ce0d, 191, false), // 5^82
+    (0xd2d80db02aabd62b, 0xf50a3fa490c30190, 193, false), // 5^83
+    (0x83c7088e1aab65db, 0x792667c6da79e0fa, 196, false), // 5^84
+    (0xa4b8cab1a1563f52, 0x577001b891185939, 198, false), // 5^85
+    (0xcde6fd5e09abcf26, 0xed4c0226b55e6f87, 200, false), // 5^86
+    (0x80b05e5ac60b6178, 0x544f8158315b05b4, 203, false), // 5^87
+    (0xa0dc75f1778e39d6, 0x696361ae3db1c721, 205, false), // 5^88
+    (0xc913936dd571c84c, 0x03bc3a19cd1e38ea, 207, false), // 5^89
+    (0xfb5878494ace3a5f, 0x04ab48a04065c724, 209, false), // 5^90
+    (0x9d174b2dcec0e47b, 0x62eb0d64283f9c76, 212, false), // 5^91
+    (0xc45d1df942711d9a, 0x3ba5d0bd324f8394, 214, false), // 5^92
+    (0xf5746577930d6500, 0xca8f44ec7ee36479, 216, false), // 5^93
+    (0x9968bf6abbe85f20, 0x7e998b13cf4e1ecc, 219, false), // 5^94
+    (0xbfc2ef456ae276e8, 0x9e3fedd8c321a67f, 221, false), // 5^95
+    (0xefb3ab16c59b14a2, 0xc5cfe94ef3ea101e, 223, false), // 5^96
+    (0x95d04aee3b80ece5, 0xbba1f1d158724a13, 226, false), // 5^97
+    (0xbb445da9ca61281f, 0x2a8a6e45ae8edc98, 228, false), // 5^98
+    (0xea1575143cf97226, 0xf52d09d71a3293be, 230, false), // 5^99
+    (0x924d692ca61be758, 0x593c2626705f9c56, 233, false), // 5^100
+    (0xb6e0c377cfa2e12e, 0x6f8b2fb00c77836c, 235, false), // 5^101
+    (0xe498f455c38b997a, 0x0b6dfb9c0f956447, 237, false), // 5^102
+    (0x8edf98b59a373fec, 0x4724bd4189bd5eac, 240, false), // 5^103
+    (0xb2977ee300c50fe7, 0x58edec91ec2cb658, 242, false), // 5^104
+    (0xdf3d5e9bc0f653e1, 0x2f2967b66737e3ed, 244, false), // 5^105
+    (0x8b865b215899f46c, 0xbd79e0d20082ee74, 247, false), // 5^106
+    (0xae67f1e9aec07187, 0xecd8590680a3aa11, 249, false), // 5^107
+    (0xda01ee641a708de9, 0xe80e6f4820cc9496, 251, false), // 5^108
+    (0x884134fe908658b2, 0x3109058d147fdcde, 254, false), // 5^109
+    (0xaa51823e34a7eede, 0xbd4b46f0599fd415, 256, false), // 5^110
+    (0xd4e5e2cdc1d1ea96, 0x6c9e18ac7007c91a, 258, false), // 5^111
+    (0x850fadc09923329e, 0x03e2cf6bc604ddb0, 261, false), // 5^112
+    (0xa6539930bf6bff45, 0x84db8346b786151d, 263, false), // 5^113
+    (0xcfe87f7cef46ff16, 0xe612641865679a64, 265, false), // 5^114
+    (0x81f14fae158c5f6e, 0x4fcb7e8f3f60c07e, 268, false), // 5^115
+    (0xa26da3999aef7749, 0xe3be5e330f38f09e, 270, false), // 5^116
+    (0xcb090c8001ab551c, 0x5cadf5bfd3072cc5, 272, false), // 5^117
+    (0xfdcb4fa002162a63, 0x73d9732fc7c8f7f7, 274, false), // 5^118
+    (0x9e9f11c4014dda7e, 0x2867e7fddcdd9afa, 277, false), // 5^119
+    (0xc646d63501a1511d, 0xb281e1fd541501b9, 279, false), // 5^120
+    (0xf7d88bc24209a565, 0x1f225a7ca91a4227, 281, false), // 5^121
+    (0x9ae757596946075f, 0x3375788de9b06958, 284, false), // 5^122
+    (0xc1a12d2fc3978937, 0x0052d6b1641c83ae, 286, false), // 5^123
+    (0xf209787bb47d6b84, 0xc0678c5dbd23a49a, 288, false), // 5^124
+    (0x9745eb4d50ce6332, 0xf840b7ba963646e0, 291, false), // 5^125
+    (0xbd176620a501fbff, 0xb650e5a93bc3d898, 293, false), // 5^126
+    (0xec5d3fa8ce427aff, 0xa3e51f138ab4cebe, 295, false), // 5^127
+    (0x93ba47c980e98cdf, 0xc66f336c36b10137, 298, false), // 5^128
+    (0xb8a8d9bbe123f017, 0xb80b0047445d4185, 300, false), // 5^129
+    (0xe6d3102ad96cec1d, 0xa60dc059157491e6, 302, false), // 5^130
+    (0x9043ea1ac7e41392, 0x87c89837ad68db30, 305, false), // 5^131
+    (0xb454e4a179dd1877, 0x29babe4598c311fc, 307, false), // 5^132
+    (0xe16a1dc9d8545e94, 0xf4296dd6fef3d67b, 309, false), // 5^133
+    (0x8ce2529e2734bb1d, 0x1899e4a65f58660d, 312, false), // 5^134
+    (0xb01ae745b101e9e4, 0x5ec05dcff72e7f90, 314, false), // 5^135
+    (0xdc21a1171d42645d, 0x76707543f4fa1f74, 316, false), // 5^136
+    (0x899504ae72497eba, 0x6a06494a791c53a8, 319, false), // 5^137
+    (0xabfa45da0edbde69, 0x0487db9d17636892, 321, false), // 5^138
+    (0xd6f8d7509292d603, 0x45a9d2845d3c42b7, 323, false), // 5^139
+    (0x865b86925b9bc5c2, 0x0b8a2392ba45a9b2, 326, false), // 5^140
+    (0xa7f26836f282b732, 0x8e6cac7768d7141f, 328, false), // 5^141
+    (0xd1ef0244af2364ff, 0x3207d795430cd927, 330, false), // 5^142
+    (0x8335616aed761f1f, 0x7f44e6bd49e807b8, 333, false), // 5^143
+    (0xa402b9c5a8d3a6e7, 0x5f16206c9c6209a6, 335, false), // 5^144
+    (0xcd036837130890a1, 0x36dba887c37a8c10, 337, false), // 5^145
+    (0x802221226be55a64, 0xc2494954da2c978a, 340, false), // 5^146
+    (0xa02aa96b06deb0fd, 0xf2db9baa10b7bd6c, 342, false), // 5^147
+    (0xc83553c5c8965d3d, 0x6f92829494e5acc7, 344, false), // 5^148
+    (0xfa42a8b73abbf48c, 0xcb772339ba1f17f9, 346, false), // 5^149
+    (0x9c69a97284b578d7, 0xff2a760414536efc, 349, false), // 5^150
+    (0xc38413cf25e2d70d, 0xfef5138519684abb, 351, false), // 5^151
+    (0xf46518c2ef5b8cd1, 0x7eb258665fc25d69, 353, false), // 5^152
+    (0x98bf2f79d5993802, 0xef2f773ffbd97a62, 356, false), // 5^153
+    (0xbeeefb584aff8603, 0xaafb550ffacfd8fa, 358, false), // 5^154
+    (0xeeaaba2e5dbf6784, 0x95ba2a53f983cf39, 360, false), // 5^155
+    (0x952ab45cfa97a0b2, 0xdd945a747bf26184, 363, false), // 5^156
+    (0xba756174393d88df, 0x94f971119aeef9e4, 365, false), // 5^157
+    (0xe912b9d1478ceb17, 0x7a37cd5601aab85e, 367, false), // 5^158
+    (0x91abb422ccb812ee, 0xac62e055c10ab33b, 370, false), // 5^159
+    (0xb616a12b7fe617aa, 0x577b986b314d6009, 372, false), // 5^160
+    (0xe39c49765fdf9d94, 0xed5a7e85fda0b80b, 374, false), // 5^161
+    (0x8e41ade9fbebc27d, 0x14588f13be847307, 377, false), // 5^162
+    (0xb1d219647ae6b31c, 0x596eb2d8ae258fc9, 379, false), // 5^163
+    (0xde469fbd99a05fe3, 0x6fca5f8ed9aef3bb, 381, false), // 5^164
+    (0x8aec23d680043bee, 0x25de7bb9480d5855, 384, false), // 5^165
+    (0xada72ccc20054ae9, 0xaf561aa79a10ae6a, 386, false), // 5^166
+    (0xd910f7ff28069da4, 0x1b2ba1518094da05, 388, false), // 5^167
+    (0x87aa9aff79042286, 0x90fb44d2f05d0843, 391, false), // 5^168
+    (0xa99541bf57452b28, 0x353a1607ac744a54, 393, false), // 5^169
+    (0xd3fa922f2d1675f2, 0x42889b8997915ce9, 395, false), // 5^170
+    (0x847c9b5d7c2e09b7, 0x69956135febada11, 398, false), // 5^171
+    (0xa59bc234db398c25, 0x43fab9837e699096, 400, false), // 5^172
+    (0xcf02b2c21207ef2e, 0x94f967e45e03f4bb, 402, false), // 5^173
+    (0x8161afb94b44f57d, 0x1d1be0eebac278f5, 405, false), // 5^174
+    (0xa1ba1ba79e1632dc, 0x6462d92a69731732, 407, false), // 5^175
+    (0xca28a291859bbf93, 0x7d7b8f7503cfdcff, 409, false), // 5^176
+    (0xfcb2cb35e702af78, 0x5cda735244c3d43f, 411, false), // 5^177
+    (0x9defbf01b061adab, 0x3a0888136afa64a7, 414, false), // 5^178
+    (0xc56baec21c7a1916, 0x088aaa1845b8fdd1, 416, false), // 5^179
+    (0xf6c69a72a3989f5b, 0x8aad549e57273d45, 418, false), // 5^180
+    (0x9a3c2087a63f6399, 0x36ac54e2f678864b, 421, false), // 5^181
+    (0xc0cb28a98fcf3c7f, 0x84576a1bb416a7de, 423, false), // 5^182
+    (0xf0fdf2d3f3c30b9f, 0x656d44a2a11c51d5, 425, false), // 5^183
+    (0x969eb7c47859e743, 0x9f644ae5a4b1b325, 428, false), // 5^184
+    (0xbc4665b596706114, 0x873d5d9f0dde1fef, 430, false), // 5^185
+    (0xeb57ff22fc0c7959, 0xa90cb506d155a7ea, 432, false), // 5^186
+    (0x9316ff75dd87cbd8, 0x09a7f12442d588f3, 435, false), // 5^187
+    (0xb7dcbf5354e9bece, 0x0c11ed6d538aeb2f, 437, false), // 5^188
+    (0xe5d3ef282a242e81, 0x8f1668c8a86da5fb, 439, false), // 5^189
+    (0x8fa475791a569d10, 0xf96e017d694487bd, 442, false), // 5^190
+    (0xb38d92d760ec4455, 0x37c981dcc395a9ac, 444, false), // 5^191
+    (0xe070f78d3927556a, 0x85bbe253f47b1417, 446, false), // 5^192
+    (0x8c469ab843b89562, 0x93956d7478ccec8e, 449, false), // 5^193
+    (0xaf58416654a6babb, 0x387ac8d1970027b2, 451, false), // 5^194
+    (0xdb2e51bfe9d0696a, 0x06997b05fcc0319f, 453, false), // 5^195
+    (0x88fcf317f22241e2, 0x441fece3bdf81f03, 456, false), // 5^196
+    (0xab3c2fddeeaad25a, 0xd527e81cad7626c4, 458, false), // 5^197
+    (0xd60b3bd56a5586f1, 0x8a71e223d8d3b075, 460, false), // 5^198
+    (0x85c7056562757456, 0xf6872d5667844e49, 463, false), // 5^199
+    (0xa738c6bebb12d16c, 0xb428f8ac016561db, 465, false), // 5^200
+    (0xd106f86e69d785c7, 0xe13336d701beba52, 467, false), // 5^201
+    (0x82a45b450226b39c, 0xecc0024661173473, 470, false), // 5^202
+    (0xa34d721642b06084, 0x27f002d7f95d0190, 472, false), // 5^203
+    (0xcc20ce9bd35c78a5, 0x31ec038df7b441f4, 474, false), // 5^204
+    (0xff290242c83396ce, 0x7e67047175a15271, 476, false), // 5^205
+    (0x9f79a169bd203e41, 0x0f0062c6e984d387, 479, false), // 5^206
+    (0xc75809c42c684dd1, 0x52c07b78a3e60868, 481, false), // 5^207
+    (0xf92e0c3537826145, 0xa7709a56ccdf8a83, 483, false), // 5^208
+    (0x9bbcc7a142b17ccb, 0x88a66076400bb692, 486, false), // 5^209
+    (0xc2abf989935ddbfe, 0x6acff893d00ea436, 488, false), // 5^210
+    (0xf356f7ebf83552fe, 0x0583f6b8c4124d43, 490, false), // 5^211
+    (0x98165af37b2153de, 0xc3727a337a8b704a, 493, false), // 5^212
+    (0xbe1bf1b059e9a8d6, 0x744f18c0592e4c5d, 495, false), // 5^213
+    (0xeda2ee1c7064130c, 0x1162def06f79df74, 497, false), // 5^214
+    (0x9485d4d1c63e8be7, 0x8addcb5645ac2ba8, 500, false), // 5^215
+    (0xb9a74a0637ce2ee1, 0x6d953e2bd7173693, 502, false), // 5^216
+    (0xe8111c87c5c1ba99, 0xc8fa8db6ccdd0437, 504, false), // 5^217
+    (0x910ab1d4db9914a0, 0x1d9c9892400a22a2, 507, false), // 5^218
+    (0xb54d5e4a127f59c8, 0x2503beb6d00cab4b, 509, false), // 5^219
+    (0xe2a0b5dc971f303a, 0x2e44ae64840fd61e, 511, false), // 5^220
+    (0x8da471a9de737e24, 0x5ceaecfed289e5d3, 514, false), // 5^221
+    (0xb10d8e1456105dad, 0x7425a83e872c5f47, 516, false), // 5^222
+    (0xdd50f1996b947518, 0xd12f124e28f77719, 518, false), // 5^223
+    (0x8a5296ffe33cc92f, 0x82bd6b70d99aaa70, 521, false), // 5^224
+    (0xace73cbfdc0bfb7b, 0x636cc64d1001550c, 523, false), // 5^225
+    (0xd8210befd30efa5a, 0x3c47f7e05401aa4f, 525, false), // 5^226
+    (0x8714a775e3e95c78, 0x65acfaec34810a71, 528, false), // 5^227
+    (0xa8d9d1535ce3b396, 0x7f1839a741a14d0d, 530, false), // 5^228
+    (0xd31045a8341ca07c, 0x1ede48111209a051, 532, false), // 5^229
+    (0x83ea2b892091e44d, 0x934aed0aab460432, 535, false), // 5^230
+    (0xa4e4b66b68b65d60, 0xf81da84d5617853f, 537, false), // 5^231
+    (0xce1de40642e3f4b9, 0x36251260ab9d668f, 539, false), // 5^232
+    (0x80d2ae83e9ce78f3, 0xc1d72b7c6b426019, 542, false), // 5^233
+    (0xa1075a24e4421730, 0xb24cf65b8612f820, 544, false), // 5^234
+    (0xc94930ae1d529cfc, 0xdee033f26797b628, 546, false), // 5^235
+    (0xfb9b7cd9a4a7443c, 0x169840ef017da3b1, 548, false), // 5^236
+    (0x9d412e0806e88aa5, 0x8e1f289560ee864f, 551, false), // 5^237
+    (0xc491798a08a2ad4e, 0xf1a6f2bab92a27e3, 553, false), // 5^238
+    (0xf5b5d7ec8acb58a2, 0xae10af696774b1db, 555, false), // 5^239
+    (0x9991a6f3d6bf1765, 0xacca6da1e0a8ef29, 558, false), // 5^240
+    (0xbff610b0cc6edd3f, 0x17fd090a58d32af3, 560, false), // 5^241
+    (0xeff394dcff8a948e, 0xddfc4b4cef07f5b0, 562, false), // 5^242
+    (0x95f83d0a1fb69cd9, 0x4abdaf101564f98e, 565, false), // 5^243
+    (0xbb764c4ca7a4440f, 0x9d6d1ad41abe37f2, 567, false), // 5^244
+    (0xea53df5fd18d5513, 0x84c86189216dc5ee, 569, false), // 5^245
+    (0x92746b9be2f8552c, 0x32fd3cf5b4e49bb5, 572, false), // 5^246
+    (0xb7118682dbb66a77, 0x3fbc8c33221dc2a2, 574, false), // 5^247
+    (0xe4d5e82392a40515, 0x0fabaf3feaa5334a, 576, false), // 5^248
+    (0x8f05b1163ba6832d, 0x29cb4d87f2a7400e, 579, false), // 5^249
+    (0xb2c71d5bca9023f8, 0x743e20e9ef511012, 581, false), // 5^250
+    (0xdf78e4b2bd342cf6, 0x914da9246b255417, 583, false), // 5^251
+    (0x8bab8eefb6409c1a, 0x1ad089b6c2f7548e, 586, false), // 5^252
+    (0xae9672aba3d0c320, 0xa184ac2473b529b2, 588, false), // 5^253
+    (0xda3c0f568cc4f3e8, 0xc9e5d72d90a2741e, 590, false), // 5^254
+    (0x8865899617fb1871, 0x7e2fa67c7a658893, 593, false), // 5^255
+    (0xaa7eebfb9df9de8d, 0xddbb901b98feeab8, 595, false), // 5^256
+    (0xd51ea6fa85785631, 0x552a74227f3ea565, 597, false), // 5^257
+    (0x8533285c936b35de, 0xd53a88958f87275f, 600, false), // 5^258
+    (0xa67ff273b8460356, 0x8a892abaf368f137, 602, false), // 5^259
+    (0xd01fef10a657842c, 0x2d2b7569b0432d85, 604, false), // 5^260
+    (0x8213f56a67f6b29b, 0x9c3b29620e29fc73, 607, false), // 5^261
+    (0xa298f2c501f45f42, 0x8349f3ba91b47b90, 609, false), // 5^262
+    (0xcb3f2f7642717713, 0x241c70a936219a74, 611, false), // 5^263
+    (0xfe0efb53d30dd4d7, 0xed238cd383aa0111, 613, false), // 5^264
+    (0x9ec95d1463e8a506, 0xf4363804324a40ab, 616, false), // 5^265
+    (0xc67bb4597ce2ce48, 0xb143c6053edcd0d5, 618, false), // 5^266
+    (0xf81aa16fdc1b81da, 0xdd94b7868e94050a, 620, false), // 5^267
+    (0x9b10a4e5e9913128, 0xca7cf2b4191c8327, 623, false), // 5^268
+    (0xc1d4ce1f63f57d72, 0xfd1c2f611f63a3f0, 625, false), // 5^269
+    (0xf24a01a73cf2dccf, 0xbc633b39673c8cec, 627, false), // 5^270
+    (0x976e41088617ca01, 0xd5be0503e085d814, 630, false), // 5^271
+    (0xbd49d14aa79dbc82, 0x4b2d8644d8a74e19, 632, false), // 5^272
+    (0xec9c459d51852ba2, 0xddf8e7d60ed1219f, 634, false), // 5^273
+    (0x93e1ab8252f33b45, 0xcabb90e5c942b503, 637, false), // 5^274
+    (0xb8da1662e7b00a17, 0x3d6a751f3b936244, 639, false), // 5^275
+    (0xe7109bfba19c0c9d, 0x0cc512670a783ad5, 641, false), // 5^276
+    (0x906a617d450187e2, 0x27fb2b80668b24c5, 644, false), // 5^277
+    (0xb484f9dc9641e9da, 0xb1f9f660802dedf6, 646, false), // 5^278
+    (0xe1a63853bbd26451, 0x5e7873f8a0396974, 648, false), // 5^279
+    (0x8d07e33455637eb2, 0xdb0b487b6423e1e8, 651, false), // 5^280
+    (0xb049dc016abc5e5f, 0x91ce1a9a3d2cda63, 653, false), // 5^281
+    (0xdc5c5301c56b75f7, 0x7641a140cc7810fb, 655, false), // 5^282
+    (0x89b9b3e11b6329ba, 0xa9e904c87fcb0a9d, 658, false), // 5^283
+    (0xac2820d9623bf429, 0x546345fa9fbdcd44, 660, false), // 5^284
+    (0xd732290fbacaf133, 0xa97c177947ad4095, 662, false), // 5^285
+    (0x867f59a9d4bed6c0, 0x49ed8eabcccc485d, 665, false), // 5^286
+    (0xa81f301449ee8c70, 0x5c68f256bfff5a75, 667, false), // 5^287
+    (0xd226fc195c6a2f8c, 0x73832eec6fff3112, 669, false), // 5^288
+    (0x83585d8fd9c25db7, 0xc831fd53c5ff7eab, 672, false), // 5^289
+    (0xa42e74f3d032f525, 0xba3e7ca8b77f5e56, 674, false), // 5^290
+    (0xcd3a1230c43fb26f, 0x28ce1bd2e55f35eb, 676, false), // 5^291
+    (0x80444b5e7aa7cf85, 0x7980d163cf5b81b3, 679, false), // 5^292
+    (0xa0555e361951c366, 0xd7e105bcc3326220, 681, false), // 5^293
+    (0xc86ab5c39fa63440, 0x8dd9472bf3fefaa8, 683, false), // 5^294
+    (0xfa856334878fc150, 0xb14f98f6f0feb952, 685, false), // 5^295
+    (0x9c935e00d4b9d8d2, 0x6ed1bf9a569f33d3, 688, false), // 5^296
+    (0xc3b8358109e84f07, 0x0a862f80ec4700c8, 690, false), // 5^297
+    (0xf4a642e14c6262c8, 0xcd27bb612758c0fa, 692, false), // 5^298
+    (0x98e7e9cccfbd7dbd, 0x8038d51cb897789c, 695, false), // 5^299
+    (0xbf21e44003acdd2c, 0xe0470a63e6bd56c3, 697, false), // 5^300
+    (0xeeea5d5004981478, 0x1858ccfce06cac74, 699, false), // 5^301
+    (0x95527a5202df0ccb, 0x0f37801e0c43ebc9, 702, false), // 5^302
+    (0xbaa718e68396cffd, 0xd30560258f54e6bb, 704, false), // 5^303
+    (0xe950df20247c83fd, 0x47c6b82ef32a2069, 706, false), // 5^304
+    (0x91d28b7416cdd27e, 0x4cdc331d57fa5442, 709, false), // 5^305
+    (0xb6472e511c81471d, 0xe0133fe4adf8e952, 711, false), // 5^306
+    (0xe3d8f9e563a198e5, 0x58180fddd97723a7, 713, false), // 5^307
+    (0x8e679c2f5e44ff8f, 0x570f09eaa7ea7648, 716, false), // 5^308
+];
+
 impl ToString for ParsedNumber {
     fn to_string(&self) -> String {
         use std::fmt::Write;
@@ -351,6 +1600,9 @@ impl ToString for ParsedNumber {
             }
         } else {
             length += 2;
+            if self.bin_exponent.is_some() {
+                length += 13;
+            }
         }
         let mut s = String::with_capacity(length);
         if self.sign == Sign::Negative {
@@ -374,6 +1626,10 @@ impl ToString for ParsedNumber {
             Some(exp) => write!(&mut s, "e{:+}", exp).unwrap(),
             None => (),
         }
+        match self.bin_exponent {
+            Some(exp) => write!(&mut s, "p{:+}", exp).unwrap(),
+            None => (),
+        }
         s
     }
 }
@@ -452,6 +1708,33 @@ mod test {
             ("0b", "b", Positive, Decimal, &[0], None),
             ("0bbb", "bbb", Positive, Decimal, &[0], None),
             ("1.2e3ms", "ms", Positive, Decimal, &[2, 1], Some(2)),
+            (
+                "1_000_000",
+                "",
+                Positive,
+                Decimal,
+                &[0, 0, 0, 0, 0, 0, 1],
+                None,
+            ),
+            (
+                "3.141_592",
+                "",
+                Positive,
+                Decimal,
+                &[2, 9, 5, 1, 4, 1, 3],
+                Some(-6),
+            ),
+            ("1_000e1_0", "", Positive, Decimal, &[0, 0, 0, 1], Some(10)),
+            (
+                "0xDEAD_BEEF",
+                "",
+                Positive,
+                Hexadecimal,
+                &[15, 14, 14, 11, 13, 10, 14, 13],
+                None,
+            ),
+            ("0b1010_0101", "", Positive, Binary, &[1, 0, 1, 0, 0, 1, 0, 1], None),
+            ("1_", "_", Positive, Decimal, &[1], None),
         ];
         let mut num = ParsedNumber::new();
         for (n, &(input, output, sign, radix, digits, exponent)) in CASES.iter().enumerate() {
@@ -501,4 +1784,270 @@ mod test {
             panic!("failed");
         }
     }
+
+    #[test]
+    fn to_float_decimal() {
+        let mut success = true;
+        const CASES: &'static [&'static str] = &[
+            "0",
+            "-0",
+            "1",
+            "-1",
+            "3.14",
+            "0.1",
+            "2.5",
+            "123456789",
+            "1e300",
+            "1e-300",
+            "1.7976931348623157e308",
+            "2.2250738585072014e-308",
+            "5e-324",
+            "1.23456789012345678901234567890123",
+            "123456789012345678901234567890e10",
+            "9007199254740993",
+            "1e40",
+            "1e-40",
+            "123456789012345e30",
+            "123456789012345e-60",
+            "2.2250738585072009e-308",
+        ];
+        let mut num = ParsedNumber::new();
+        for (n, &input) in CASES.iter().enumerate() {
+            let pos = Span {
+                start: Pos(0),
+                end: Pos(input.len() as u32),
+            };
+            num.parse(input, pos).unwrap();
+            let want64: f64 = input.parse().unwrap();
+            let want32: f32 = input.parse().unwrap();
+            let got64 = num.to_f64();
+            let got32 = num.to_f32();
+            if got64.to_bits() != want64.to_bits() || got32.to_bits() != want32.to_bits() {
+                success = false;
+                eprintln!("Test case {} failed: input={:?}", n, input);
+                eprintln!("    to_f64: got {:?}, want {:?}", got64, want64);
+                eprintln!("    to_f32: got {:?}, want {:?}", got32, want32);
+            }
+        }
+        if !success {
+            eprintln!();
+            panic!("failed");
+        }
+    }
+
+    /// Sweeps a few thousand mantissa/exponent combinations chosen to land
+    /// squarely in [`eisel_lemire`]'s range -- well beyond what
+    /// [`fast_decimal_f64`]/[`fast_decimal_f32`] can handle on their own --
+    /// and checks each against the standard library's own parse, bit for
+    /// bit.
+    #[test]
+    fn to_float_decimal_wide_exponent() {
+        let mut success = true;
+        let mut state: u64 = 0x243f_6a88_85a3_08d3;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut num = ParsedNumber::new();
+        for _ in 0..4000 {
+            let digit_count = 1 + (next() % 19) as usize;
+            let mut text = String::with_capacity(digit_count + 8);
+            for i in 0..digit_count {
+                let d = if i == 0 { 1 + next() % 9 } else { next() % 10 };
+                text.push((b'0' + d as u8) as char);
+            }
+            let exp = (next() % 700) as i64 - 350;
+            text.push('e');
+            text.push_str(&exp.to_string());
+            let pos = Span {
+                start: Pos(0),
+                end: Pos(text.len() as u32),
+            };
+            num.parse(&text, pos).unwrap();
+            let want64: f64 = text.parse().unwrap();
+            let want32: f32 = text.parse().unwrap();
+            let got64 = num.to_f64();
+            let got32 = num.to_f32();
+            if got64.to_bits() != want64.to_bits() || got32.to_bits() != want32.to_bits() {
+                success = false;
+                eprintln!("Mismatch for {:?}", text);
+                eprintln!("    to_f64: got {:?}, want {:?}", got64, want64);
+                eprintln!("    to_f32: got {:?}, want {:?}", got32, want32);
+            }
+        }
+        if !success {
+            eprintln!();
+            panic!("failed");
+        }
+    }
+
+    #[test]
+    fn parse_hex_float() {
+        let mut success = true;
+        // (input, remainder, digits after the radix prefix, bin_exponent, to_f64)
+        type Case = (&'static str, &'static str, &'static [u8], Option<i32>, f64);
+        const CASES: &'static [Case] = &[
+            ("0x1.8p1", "", &[8, 1], Some(-3), 3.0),
+            ("0x1p3", "", &[1], Some(3), 8.0),
+            ("0x1p-3", "", &[1], Some(-3), 0.125),
+            ("0xap0", "", &[10], Some(0), 10.0),
+            ("0b1.1p1", "", &[1, 1], Some(0), 3.0),
+            ("0x1.8p1V", "V", &[8, 1], Some(-3), 3.0),
+        ];
+        let mut num = ParsedNumber::new();
+        for (n, &(input, output, digits, bin_exponent, want)) in CASES.iter().enumerate() {
+            let pos = Span { start: Pos(0), end: Pos(input.len() as u32) };
+            match num.parse(input, pos) {
+                Err((e, _)) => {
+                    success = false;
+                    eprintln!("Test case {} failed: input={:?}, error={:?}", n, input, e);
+                }
+                Ok(rest) => {
+                    let got = num.to_f64();
+                    if rest != output
+                        || num.digits != digits
+                        || num.bin_exponent != bin_exponent
+                        || got != want
+                    {
+                        success = false;
+                        eprintln!("Test case {} failed: input={:?}", n, input);
+                        eprintln!("    rest: {:?}, expected {:?}", rest, output);
+                        eprintln!("    digits: {:?}, expected {:?}", num.digits, digits);
+                        eprintln!(
+                            "    bin_exponent: {:?}, expected {:?}",
+                            num.bin_exponent, bin_exponent
+                        );
+                        eprintln!("    to_f64: {:?}, expected {:?}", got, want);
+                    }
+                }
+            }
+        }
+        if !success {
+            eprintln!();
+            panic!("failed");
+        }
+    }
+
+    #[test]
+    fn parse_hex_float_errors() {
+        let mut num = ParsedNumber::new();
+        for input in ["0x1.", "0x1.8", "0x1p", "0o1.5p1"] {
+            let pos = Span { start: Pos(0), end: Pos(input.len() as u32) };
+            if num.parse(input, pos).is_ok() {
+                panic!("expected {:?} to fail to parse", input);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_separator_errors() {
+        let mut num = ParsedNumber::new();
+        // Leading, doubled, and trailing-before-a-terminator `_` are all
+        // rejected, in every radix and in the fraction and exponent too. A
+        // `_` with nothing after it at all (like `1_`, tested in
+        // `parse_success`) is not one of these -- it is simply left in the
+        // remainder, the same as any other token that runs out of input.
+        for input in ["_1", "1__2", "1_.2", "1._2", "1.2_e3", "0x1__2"] {
+            let pos = Span { start: Pos(0), end: Pos(input.len() as u32) };
+            match num.parse(input, pos) {
+                Ok(rest) => panic!("expected {:?} to fail to parse, got rest {:?}", input, rest),
+                Err((e, _)) => assert_eq!(e, ParseError::MisplacedSeparator('_'), "input {:?}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn to_float_non_decimal() {
+        let mut success = true;
+        const CASES: &'static [(&'static str, f64)] = &[
+            ("0x1F", 31.0),
+            ("-0x10", -16.0),
+            ("0b101", 5.0),
+            ("0o17", 15.0),
+        ];
+        let mut num = ParsedNumber::new();
+        for (n, &(input, want)) in CASES.iter().enumerate() {
+            let pos = Span {
+                start: Pos(0),
+                end: Pos(input.len() as u32),
+            };
+            num.parse(input, pos).unwrap();
+            let got64 = num.to_f64();
+            let got32 = num.to_f32();
+            if got64 != want || got32 != want as f32 {
+                success = false;
+                eprintln!("Test case {} failed: input={:?}", n, input);
+                eprintln!("    to_f64: got {:?}, want {:?}", got64, want);
+                eprintln!("    to_f32: got {:?}, want {:?}", got32, want as f32);
+            }
+        }
+        if !success {
+            eprintln!();
+            panic!("failed");
+        }
+    }
+
+    #[test]
+    fn from_str_and_try_from() {
+        let num: ParsedNumber = "42".parse().unwrap();
+        assert_eq!(f64::try_from(&num), Ok(42.0));
+        assert_eq!(i64::try_from(&num), Ok(42));
+        assert_eq!(u32::try_from(&num), Ok(42));
+
+        assert_eq!(
+            "42V".parse::<ParsedNumber>().unwrap_err(),
+            ParseError::UnexpectedChar('V')
+        );
+
+        let neg: ParsedNumber = "-7".parse().unwrap();
+        assert_eq!(i64::try_from(&neg), Ok(-7));
+        assert_eq!(u32::try_from(&neg), Err(ConvertError::Overflow));
+
+        let frac: ParsedNumber = "1.5".parse().unwrap();
+        assert_eq!(i64::try_from(&frac), Err(ConvertError::Overflow));
+        assert_eq!(f64::try_from(&frac), Ok(1.5));
+
+        let too_big: ParsedNumber = "0x1_0000_0000".parse().unwrap();
+        assert_eq!(u32::try_from(&too_big), Err(ConvertError::Overflow));
+
+        let max: ParsedNumber = "0x7fffffffffffffff".parse().unwrap();
+        assert_eq!(i64::try_from(&max), Ok(i64::MAX));
+
+        let min: ParsedNumber = "-9223372036854775808".parse().unwrap();
+        assert_eq!(i64::try_from(&min), Ok(i64::MIN));
+
+        let overflow: ParsedNumber = "-9223372036854775809".parse().unwrap();
+        assert_eq!(i64::try_from(&overflow), Err(ConvertError::Overflow));
+    }
+
+    #[test]
+    fn parse_with_unit() {
+        use crate::units::Units;
+
+        let mut num = ParsedNumber::new();
+        let pos = |input: &str| Span { start: Pos(0), end: Pos(input.len() as u32) };
+
+        let input = "12V";
+        let unit = num.parse_with_unit(input, pos(input)).unwrap();
+        assert_eq!(unit, Some((Units::volt(1), Span { start: Pos(2), end: Pos(3) })));
+        assert_eq!(num.to_f64(), 12.0);
+
+        // A metric prefix on the suffix folds into the number's exponent.
+        let input = "1.2kHz";
+        num.parse_with_unit(input, pos(input)).unwrap();
+        assert_eq!(num.to_f64(), 1200.0);
+
+        // No suffix at all parses the same as a plain number, with no units.
+        let input = "42";
+        assert_eq!(num.parse_with_unit(input, pos(input)).unwrap(), None);
+
+        // An unrecognized suffix is an error, not leftover remainder.
+        let input = "12Q";
+        match num.parse_with_unit(input, pos(input)) {
+            Ok(unit) => panic!("expected {:?} to fail to parse, got {:?}", input, unit),
+            Err((e, _)) => assert_eq!(e, ParseUnitError::Unit(crate::units::ParseError::UnknownUnits)),
+        }
+    }
 }
@@ -0,0 +1,155 @@
+//! Output formats for rendered audio: the [`wave`] container, and raw
+//! interleaved PCM for piping into other tools (resamplers, denoisers,
+//! `sox`, ...). Both are written through the [`Sink`] trait so the render
+//! loop in [`crate::cmd_sfx`] doesn't need to know which one it's feeding.
+
+use crate::wave;
+use std::io::{Result as IOResult, Write};
+use std::str::FromStr;
+
+/// Sample encoding for [`Format::Raw`] output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawSampleFormat {
+    /// 32-bit IEEE float, no scaling.
+    F32,
+    /// 16-bit signed integer, scaled and clamped to the full range.
+    I16,
+}
+
+/// Byte order for [`Format::Raw`] output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl FromStr for Endian {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Ok(match s {
+            "little" => Endian::Little,
+            "big" => Endian::Big,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Selectable output container for rendered audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A WAVE file with a 16-bit PCM header. Requires a seekable stream,
+    /// since the header is rewritten once the sample count is known.
+    Wav,
+    /// Headerless interleaved PCM, in `format`/`endian`.
+    Raw(RawSampleFormat),
+}
+
+impl FromStr for Format {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Ok(match s {
+            "wav" => Format::Wav,
+            "raw-f32" => Format::Raw(RawSampleFormat::F32),
+            "raw-i16" => Format::Raw(RawSampleFormat::I16),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl Format {
+    /// Whether this format needs a seekable stream (only [`Format::Wav`],
+    /// to go back and fill in the frame count once rendering is done).
+    pub fn needs_seek(self) -> bool {
+        matches!(self, Format::Wav)
+    }
+}
+
+/// A destination for a stream of rendered audio samples. [`Sink::finish`]
+/// takes `self` by boxed value so a format that buffers a trailing header
+/// (like [`wave::Writer`]) can flush it on the way out.
+pub trait Sink {
+    /// Write floating-point samples, interleaved if multi-channel.
+    fn write(&mut self, data: &[f32]) -> IOResult<()>;
+    /// Finish writing, flushing anything buffered.
+    fn finish(self: Box<Self>) -> IOResult<()>;
+}
+
+/// Sink that writes a WAVE file through [`wave::Writer`].
+pub struct WaveSink<'a>(wave::Writer<'a>);
+
+impl<'a> WaveSink<'a> {
+    /// `in_rate` is the rate samples will arrive at through
+    /// [`Sink::write`]; see [`wave::Writer::from_stream`].
+    pub fn new(
+        stream: &'a mut dyn wave::SeekWrite,
+        in_rate: u32,
+        parameters: &wave::Parameters,
+    ) -> Self {
+        WaveSink(wave::Writer::from_stream(stream, in_rate, parameters))
+    }
+}
+
+impl<'a> Sink for WaveSink<'a> {
+    fn write(&mut self, data: &[f32]) -> IOResult<()> {
+        self.0.write(data)
+    }
+    fn finish(self: Box<Self>) -> IOResult<()> {
+        self.0.finish()
+    }
+}
+
+/// Sink that writes headerless interleaved PCM straight through, with no
+/// buffering to finish -- every format/endian combination is written as it
+/// arrives.
+pub struct RawSink<'a> {
+    stream: &'a mut dyn Write,
+    format: RawSampleFormat,
+    endian: Endian,
+}
+
+impl<'a> RawSink<'a> {
+    pub fn new(stream: &'a mut dyn Write, format: RawSampleFormat, endian: Endian) -> Self {
+        RawSink {
+            stream,
+            format,
+            endian,
+        }
+    }
+}
+
+impl<'a> Sink for RawSink<'a> {
+    fn write(&mut self, data: &[f32]) -> IOResult<()> {
+        match self.format {
+            RawSampleFormat::F32 => {
+                for &x in data {
+                    let bytes = match self.endian {
+                        Endian::Little => x.to_le_bytes(),
+                        Endian::Big => x.to_be_bytes(),
+                    };
+                    self.stream.write_all(&bytes)?;
+                }
+            }
+            RawSampleFormat::I16 => {
+                for &x in data {
+                    let x = (x * 32768.0).round();
+                    let x = if x > i16::max_value() as f32 {
+                        i16::max_value()
+                    } else if x < i16::min_value() as f32 {
+                        i16::min_value()
+                    } else {
+                        x as i16
+                    };
+                    let bytes = match self.endian {
+                        Endian::Little => x.to_le_bytes(),
+                        Endian::Big => x.to_be_bytes(),
+                    };
+                    self.stream.write_all(&bytes)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    fn finish(self: Box<Self>) -> IOResult<()> {
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
-use crate::error::ErrorHandler;
+use crate::error::{Applicability, Diagnostic, ErrorHandler, Severity, Suggestion};
 use crate::number::ParsedNumber;
 use crate::sexpr::{Content, SExpr};
 use crate::sourcepos::{HasPos, Span};
-use crate::token::{Token, Tokenizer, Type};
+use crate::token::{unescape_string, LexError, Token, Tokenizer, Type};
 use crate::units::Units;
 use std::fmt::Write;
 use std::str;
@@ -12,6 +12,7 @@ pub struct Parser {
     exprs: Vec<SExpr>,
     groups: Vec<(Span, usize)>,
     number: ParsedNumber,
+    error_count: usize,
 }
 
 /// Get the contents of a token as a string.
@@ -35,6 +36,39 @@ fn tok_boxstr(tok: &Token) -> Box<str> {
     }
 }
 
+/// Parse a quoted string token into its decoded value.
+///
+/// `tok.text` still has its delimiting quotes and undecoded escapes, the
+/// same way [`Tokenizer`] always leaves a [`Type::String`] token; this is
+/// the separate unescaping pass [`unescape_string`]'s own doc comment
+/// calls for.
+fn parse_string(err_handler: &mut dyn ErrorHandler, tok: &Token) -> Option<Content> {
+    let pos = tok.source_pos();
+    if tok.error == Some(LexError::UnterminatedString) {
+        err_handler.handle(&Diagnostic::new(pos, Severity::Error, "unterminated string literal"));
+        return None;
+    }
+    let raw = &tok.text[1..tok.text.len() - 1];
+    match unescape_string(raw) {
+        Ok(bytes) => match str::from_utf8(&bytes) {
+            Ok(s) => Some(Content::String(Box::from(s))),
+            Err(_) => {
+                err_handler.handle(&Diagnostic::new(
+                    pos,
+                    Severity::Error,
+                    "string literal escape produced invalid UTF-8",
+                ));
+                None
+            }
+        },
+        Err(e) => {
+            let esc_pos = pos.sub_span(1 + e.offset..);
+            err_handler.handle(&Diagnostic::new(esc_pos, Severity::Error, e.to_string().as_ref()));
+            None
+        }
+    }
+}
+
 /// A result from running the parser.
 pub enum ParseResult {
     None,         // Token stream ended without any expressions in it.
@@ -43,39 +77,61 @@ pub enum ParseResult {
     Value(SExpr), // Parsed complete expression.
 }
 
-// Send an error message
-fn handle_error_token(err_handler: &mut dyn ErrorHandler, pos: Span, text: &[u8]) {
-    let msg: String = match str::from_utf8(text) {
-        Ok(s) => match s.chars().next() {
-            Some(c) => {
-                if c <= '\x1f' || ('\u{7f}' <= c && c <= '\u{9f}') {
-                    format!("unexpected control character U+{:04X}", c as u32)
-                } else if c <= '\u{7f}' {
-                    if c == '\'' {
-                        "unexpected character <'>".to_owned()
-                    } else {
-                        format!("unexpected character '{}'", c)
-                    }
+// Get the first character of a `Type::Error` token's text, for the
+// [`LexError`] variants that are guaranteed to have decoded a real
+// character to classify in the first place.
+fn first_char(text: &[u8]) -> char {
+    match str::from_utf8(text).ok().and_then(|s| s.chars().next()) {
+        Some(c) => c,
+        // Tokenizer should not produce this.
+        None => panic!("error token missing valid leading character"),
+    }
+}
+
+// Send an error message for a `Type::Error` token, built from the
+// [`LexError`] the tokenizer already classified it as, instead of
+// re-decoding `tok.text` from scratch.
+fn handle_error_token(err_handler: &mut dyn ErrorHandler, pos: Span, tok: &Token) {
+    use LexError::*;
+    let error = match tok.error {
+        Some(error) => error,
+        // Tokenizer should not produce this.
+        None => panic!("error token missing its LexError"),
+    };
+    let msg: String = match error {
+        ControlCharacter => {
+            format!("unexpected control character U+{:04X}", first_char(tok.text) as u32)
+        }
+        UnexpectedCharacter => {
+            let c = first_char(tok.text);
+            if c <= '\u{7f}' {
+                if c == '\'' {
+                    "unexpected character <'>".to_owned()
                 } else {
-                    format!("unexpected Unicode character U+{:04X}", c as u32)
+                    format!("unexpected character '{}'", c)
                 }
+            } else {
+                format!("unexpected Unicode character U+{:04X}", c as u32)
             }
-            // Tokenizer should not produce this.
-            _ => panic!("empty error token"),
-        },
-        Err(_) => {
-            if text.is_empty() {
+        }
+        StrayByte | InvalidUtf8 => {
+            if tok.text.is_empty() {
                 // Tokenizer should not produce this.
                 panic!("empty error token");
             }
             let mut s = String::new();
-            for b in text.iter() {
+            for b in tok.text.iter() {
                 write!(&mut s, "0x{:02x}, ", b).unwrap();
             }
             format!("invalid UTF-8 text (byte sequence {})", &s[..s.len() - 2])
         }
+        // Tokenizer only sets these on Type::String/Type::Comment/
+        // Type::Number tokens, never on a Type::Error token.
+        UnterminatedString | UnterminatedComment | MalformedNumber => {
+            panic!("unexpected LexError on error token: {:?}", error)
+        }
     };
-    err_handler.handle(pos, msg.as_ref());
+    err_handler.handle(&Diagnostic::new(pos, Severity::Error, msg.as_ref()));
 }
 
 impl Parser {
@@ -84,9 +140,16 @@ impl Parser {
             exprs: Vec::new(),
             groups: Vec::new(),
             number: ParsedNumber::new(),
+            error_count: 0,
         };
     }
 
+    /// Get the number of errors reported so far, by [`Parser::parse`],
+    /// [`Parser::parse_all`], or [`Parser::finish`].
+    pub fn error_count(&self) -> usize {
+        self.error_count
+    }
+
     /// Parse the next s-expression from the token stream.
     ///
     /// If the stream ends without producing a complete s-expression, parse()
@@ -108,7 +171,8 @@ impl Parser {
                     }
                 }
                 Type::Error => {
-                    handle_error_token(err_handler, pos, tok.text);
+                    self.error_count += 1;
+                    handle_error_token(err_handler, pos, &tok);
                     return ParseResult::Error;
                 }
                 Type::Comment => {}
@@ -133,6 +197,20 @@ impl Parser {
                     }
                     self.exprs.push(expr);
                 }
+                Type::String => {
+                    let content = match parse_string(err_handler, &tok) {
+                        Some(x) => x,
+                        None => {
+                            self.error_count += 1;
+                            return ParseResult::Error;
+                        }
+                    };
+                    let expr = SExpr { pos, content };
+                    if self.groups.is_empty() {
+                        return ParseResult::Value(expr);
+                    }
+                    self.exprs.push(expr);
+                }
                 Type::ParenOpen => {
                     self.groups.push((pos, self.exprs.len()));
                 }
@@ -152,7 +230,8 @@ impl Parser {
                         self.exprs.push(expr);
                     }
                     _ => {
-                        err_handler.handle(pos, "extra ')'");
+                        self.error_count += 1;
+                        err_handler.handle(&Diagnostic::new(pos, Severity::Error, "extra ')'"));
                         return ParseResult::Error;
                     }
                 },
@@ -160,11 +239,57 @@ impl Parser {
         }
     }
 
+    /// Parse every s-expression out of the token stream, recovering from
+    /// errors instead of aborting at the first one.
+    ///
+    /// On an error, the tokens belonging to the broken form are discarded up
+    /// to the next point where the parser is back at the top level (the
+    /// group depth present when the error was encountered, fully closed
+    /// out), and parsing resumes from there. This reports every problem in
+    /// the input in a single pass, along with every well-formed top-level
+    /// expression, which suits editor and REPL-like workflows better than
+    /// stopping at the first mistake.
+    pub fn parse_all(
+        &mut self,
+        err_handler: &mut dyn ErrorHandler,
+        tokenizer: &mut Tokenizer,
+    ) -> Vec<SExpr> {
+        let mut result = Vec::new();
+        loop {
+            match self.parse(err_handler, tokenizer) {
+                ParseResult::None => return result,
+                ParseResult::Incomplete => {
+                    self.finish(err_handler);
+                    return result;
+                }
+                ParseResult::Error => self.recover(tokenizer),
+                ParseResult::Value(expr) => result.push(expr),
+            }
+        }
+    }
+
+    /// Discard tokens after a parse error until the groups that were open at
+    /// the time of the error have all been closed, leaving the parser back
+    /// at a consistent top-level boundary.
+    fn recover(&mut self, tokenizer: &mut Tokenizer) {
+        let mut depth = self.groups.len();
+        self.groups.clear();
+        self.exprs.clear();
+        while depth > 0 {
+            match tokenizer.next().ty {
+                Type::End => return,
+                Type::ParenOpen => depth += 1,
+                Type::ParenClose => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+
     /// Finish parsing a document, and report errors for any unclosed groups.
-    pub fn finish(&self, err_handler: &mut dyn ErrorHandler) {
+    pub fn finish(&mut self, err_handler: &mut dyn ErrorHandler) {
         for (pos, _) in self.groups.iter().rev() {
-            err_handler.handle(*pos, "missing ')'");
-            return;
+            self.error_count += 1;
+            err_handler.handle(&Diagnostic::new(*pos, Severity::Error, "missing ')'"));
         }
     }
 
@@ -175,15 +300,32 @@ impl Parser {
         let rest = match self.number.parse(text, tokpos) {
             Ok(rest) => rest,
             Err((e, pos)) => {
-                err_handler.handle(pos, e.to_string().as_ref());
+                self.error_count += 1;
+                err_handler.handle(&Diagnostic::new(pos, Severity::Error, e.to_string().as_ref()));
                 return None;
             }
         };
         let idx = text.len() - rest.len();
         let (_upos, units, exponent) = match Units::parse(rest, tokpos.sub_span(idx..)) {
             Ok(r) => r,
-            Err((e, pos)) => {
-                err_handler.handle(pos, e.to_string().as_ref());
+            Err((e, pos, suggestion)) => {
+                self.error_count += 1;
+                let suggestions: Vec<Suggestion> = suggestion
+                    .into_iter()
+                    .map(|replacement| Suggestion {
+                        span: pos,
+                        replacement,
+                        applicability: Applicability::MaybeIncorrect,
+                    })
+                    .collect();
+                let message = e.to_string();
+                err_handler.handle(&Diagnostic {
+                    pos,
+                    severity: Severity::Error,
+                    message: message.as_ref(),
+                    labels: &[],
+                    suggestions: &suggestions,
+                });
                 return None;
             }
         };
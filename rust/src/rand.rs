@@ -1,7 +1,10 @@
 // See: https://www.pcg-random.org/download.html
 
 /// State for a random number generator.
-#[derive(Clone)]
+///
+/// Pure integer/float arithmetic with no allocation, so this also compiles
+/// under the `no_std` feature.
+#[derive(Debug, Clone)]
 pub struct Rand {
     state: u64,
     inc: u64,
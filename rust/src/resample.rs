@@ -0,0 +1,122 @@
+//! Windowed-sinc sample-rate conversion, used by [`wave::Writer`](crate::wave::Writer)
+//! to let a synth graph render at its own internal rate and still be
+//! written out at an arbitrary file sample rate.
+//!
+//! [`Resampler`] is a streaming, causal interpolator: it keeps a fixed
+//! 16-tap ring buffer of the most recent input frames per channel and a
+//! fractional phase `pos`. Feeding it input frames and draining output
+//! frames can be interleaved freely, so it works a buffer at a time the
+//! same way [`wave::Writer::write`](crate::wave::Writer::write) is called.
+
+const TAPS: usize = 16;
+
+/// `sinc(x) = sin(pi x) / (pi x)`, defined as `1.0` at `x = 0` where the
+/// naive formula would divide zero by zero.
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-7 {
+        1.0
+    } else {
+        let px = std::f32::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over the `TAPS`-wide kernel support, indexed by
+/// position `0..TAPS`.
+fn blackman_window() -> [f32; TAPS] {
+    let mut window = [0.0f32; TAPS];
+    let n = (TAPS - 1) as f32;
+    for (i, w) in window.iter_mut().enumerate() {
+        let phase = std::f32::consts::PI * (i as f32) / n;
+        *w = 0.42 - 0.5 * (2.0 * phase).cos() + 0.08 * (4.0 * phase).cos();
+    }
+    window
+}
+
+/// Streaming windowed-sinc resampler for interleaved multi-channel audio.
+/// See the [module docs](self) for the overall approach.
+pub struct Resampler {
+    channel_count: usize,
+    /// How far one output sample period is, in input sample periods
+    /// (`in_rate / out_rate`).
+    ratio: f32,
+    /// How far the next output sample is past the most recently pushed
+    /// input frame, in input sample periods. Emit while this is `< 1.0`;
+    /// once it reaches `1.0`, the next input frame is due.
+    pos: f32,
+    window: [f32; TAPS],
+    /// One 16-tap ring buffer per channel, oldest sample first.
+    taps: Box<[[f32; TAPS]]>,
+}
+
+impl Resampler {
+    /// A resampler converting `in_rate` to `out_rate`, with `channel_count`
+    /// independent per-channel ring buffers, all primed with zeros.
+    pub fn new(channel_count: usize, in_rate: u32, out_rate: u32) -> Self {
+        Resampler {
+            channel_count,
+            ratio: in_rate as f32 / out_rate as f32,
+            pos: 1.0,
+            window: blackman_window(),
+            taps: vec![[0.0f32; TAPS]; channel_count].into_boxed_slice(),
+        }
+    }
+
+    /// Whether `in_rate == out_rate`, the identity case where resampling
+    /// would be a no-op other than blurring the signal through the filter
+    /// kernel for no reason.
+    pub fn is_identity(&self) -> bool {
+        self.ratio == 1.0
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        for (ring, &x) in self.taps.iter_mut().zip(frame) {
+            ring.copy_within(1.., 0);
+            ring[TAPS - 1] = x;
+        }
+    }
+
+    fn emit_frame(&self, out: &mut [f32]) {
+        for (ring, o) in self.taps.iter().zip(out.iter_mut()) {
+            let mut acc = 0.0f32;
+            for (j, &tap) in ring.iter().enumerate() {
+                let offset = self.pos + (TAPS - 1 - j) as f32;
+                acc += tap * self.window[j] * sinc(offset);
+            }
+            *o = acc;
+        }
+    }
+
+    /// Feed interleaved input frames (`input.len()` a multiple of
+    /// `channel_count`), calling `on_frame` with each interleaved output
+    /// frame as it becomes due. Any input left over once `pos` falls short
+    /// of a full frame stays buffered in `pos` for the next call.
+    pub fn feed(&mut self, input: &[f32], mut on_frame: impl FnMut(&[f32])) {
+        let mut frames = input.chunks_exact(self.channel_count);
+        let mut out = vec![0.0f32; self.channel_count];
+        loop {
+            if self.pos >= 1.0 {
+                match frames.next() {
+                    Some(frame) => {
+                        self.push_frame(frame);
+                        self.pos -= 1.0;
+                    }
+                    None => break,
+                }
+            } else {
+                self.emit_frame(&mut out);
+                on_frame(&out);
+                self.pos += self.ratio;
+            }
+        }
+    }
+
+    /// Drain the filter's tail by pushing `TAPS` trailing zero frames,
+    /// emitting any output frames that become due along the way. Called
+    /// once input has ended, e.g. from
+    /// [`Writer::finish`](crate::wave::Writer::finish).
+    pub fn flush(&mut self, on_frame: impl FnMut(&[f32])) {
+        let zeros = vec![0.0f32; TAPS * self.channel_count];
+        self.feed(&zeros, on_frame);
+    }
+}
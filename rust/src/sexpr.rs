@@ -8,6 +8,7 @@ pub enum Type {
     Symbol,
     Integer,
     Float,
+    String,
     List,
 }
 
@@ -18,17 +19,19 @@ impl Display for Type {
             Symbol => "symbol",
             Integer => "integer",
             Float => "float",
+            String => "string",
             List => "list",
         })
     }
 }
 
 /// The contents of an s-expression.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Content {
     Symbol(Box<str>),
     Integer(Units, i64),
     Float(Units, f64),
+    String(Box<str>),
     List(Box<[SExpr]>),
 }
 
@@ -39,13 +42,14 @@ impl Content {
             Content::Symbol(_) => Type::Symbol,
             Content::Integer(_, _) => Type::Integer,
             Content::Float(_, _) => Type::Float,
+            Content::String(_) => Type::String,
             Content::List(_) => Type::List,
         }
     }
 }
 
 /// An s-expression.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SExpr {
     pub pos: Span,
     pub content: Content,
@@ -88,6 +92,20 @@ impl SExpr {
                     write!(out, "[{} {}]", units, num).unwrap();
                 }
             }
+            String(s) => {
+                out.push('"');
+                for c in s.chars() {
+                    match c {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        '\n' => out.push_str("\\n"),
+                        '\t' => out.push_str("\\t"),
+                        '\r' => out.push_str("\\r"),
+                        _ => out.push(c),
+                    }
+                }
+                out.push('"');
+            }
             List(list) => {
                 out.push('(');
                 let mut iter = list.iter();
@@ -0,0 +1,951 @@
+use crate::rand::Rand;
+// NOTE: the `no_std` build needs `serde` pulled in with
+// `default-features = false` once this crate has a Cargo.toml; the derives
+// below don't otherwise require `std`.
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::envelope::{self, Segment};
+use super::filter::ChamberlinMode;
+use super::filter::Mode as FilterMode;
+use super::fm::{self, Algorithm, FmOperator, OPERATOR_COUNT};
+use super::graph::{Graph, SignalRef};
+use super::ops::{self, NoiseColor, PointFunction, PINK_GENERATORS};
+use super::program::{Input, Parameters};
+use std::cmp::min;
+use std::f32;
+use std::f64;
+
+/// Index of a previously-computed buffer, used as an instruction operand.
+pub type Slot = u32;
+
+/// Index into a [`Program`]'s constant pool, used as an instruction operand.
+pub type Const = u32;
+
+/// One instruction in a compiled [`Program`]. Each opcode names its signal
+/// inputs by [`Slot`] (the buffer computed by an earlier instruction) and its
+/// literal parameters by [`Const`] (an index into the program's constant
+/// pool), so every operand is a small integer, independent of the host's
+/// native float representation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Instr {
+    Osc {
+        frequency: Slot,
+    },
+    PhaseMod {
+        frequency: Slot,
+        modulation: Slot,
+        feedback: Const,
+    },
+    ApplyFunction {
+        input: Slot,
+        function: PointFunction,
+    },
+    Noise,
+    ColorNoise {
+        color: NoiseColor,
+    },
+    Multiply {
+        x: Slot,
+        y: Slot,
+    },
+    Mix {
+        base: Slot,
+        input: Slot,
+        gain: Const,
+    },
+    Frequency {
+        input: Slot,
+    },
+    Zero,
+    ScaleInt {
+        input: Slot,
+        scale: i32,
+    },
+    Note {
+        offset: i32,
+    },
+    Constant {
+        value: Const,
+    },
+    Svf {
+        input: Slot,
+        frequency: Slot,
+        mode: FilterMode,
+        invq: Const,
+        gain: Const,
+    },
+    HighPass {
+        input: Slot,
+        frequency: Const,
+    },
+    Filter {
+        input: Slot,
+        cutoff: Slot,
+        resonance: Slot,
+        mode: ChamberlinMode,
+    },
+    Envelope {
+        segments: Box<[Segment]>,
+    },
+    Sample {
+        frames: Box<[f32]>,
+    },
+    Fm {
+        frequency: Slot,
+        algorithm: Algorithm,
+        operators: [FmOperator; OPERATOR_COUNT],
+        feedback: Const,
+    },
+    RateEnvelope {
+        attack_rate: u8,
+        decay1_rate: u8,
+        sustain_level: Const,
+        decay2_rate: u8,
+        release_rate: u8,
+    },
+}
+
+/// A compiled, serializable audio program: a flat instruction list plus the
+/// constant pool its instructions index into. Unlike [`super::program::Program`],
+/// this holds no live [`Function`](super::program::Function) trait objects, so
+/// it can be saved, shipped, and round-tripped through serde.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Program {
+    pub instrs: Box<[Instr]>,
+    pub constants: Box<[f32]>,
+}
+
+/// Push a constant onto the pool and return its index.
+pub fn push_const(constants: &mut Vec<f32>, value: f32) -> Const {
+    constants.push(value);
+    (constants.len() - 1) as Const
+}
+
+/// Deserialize a [`Program`] from its compact binary encoding, for
+/// [`super::wasm::init_patch`] to load a patch compiled host-side. Uses
+/// `postcard`, a `no_std`-friendly serde codec, rather than `serde_json`.
+#[cfg(feature = "wasm")]
+pub fn deserialize(bytes: &[u8]) -> Result<Program, postcard::Error> {
+    postcard::from_bytes(bytes)
+}
+
+/// Compile a [`Graph`] into a [`Program`]. Since [`Graph::add`](super::graph::Graph::add)
+/// already requires every input to reference an earlier node, the graph's
+/// node order is already a topological sort: compiling is just a linear walk
+/// that re-encodes each node as an [`Instr`], renumbering its inputs as
+/// [`Slot`]s.
+pub fn compile(graph: &Graph) -> Program {
+    let mut constants = Vec::new();
+    let mut instrs = Vec::with_capacity(graph.nodes().len());
+    for node in graph.nodes().iter() {
+        let inputs: Vec<Slot> = node.inputs().iter().map(|&SignalRef(idx)| idx).collect();
+        instrs.push(node.encode(&inputs, &mut constants));
+    }
+    Program {
+        instrs: instrs.into_boxed_slice(),
+        constants: constants.into_boxed_slice(),
+    }
+}
+
+// =================================================================================================
+// Interpreter
+// =================================================================================================
+
+fn time_from(time: f32) -> usize {
+    if time >= 0.0 {
+        if time < usize::max_value() as f32 {
+            time.round() as usize
+        } else {
+            usize::max_value()
+        }
+    } else {
+        0
+    }
+}
+
+/// A single segment of an [`Instr::Envelope`], with durations already
+/// converted from seconds to samples.
+#[derive(Debug, Clone, Copy)]
+enum FSeg {
+    Set { value: f32 },
+    Linear { value: f32, time: usize },
+    Exponential { value: f32, time_constant: f32, threshold: f32 },
+    Delay { time: usize },
+    Gate,
+    Stop,
+    Loop { start: usize, count: Option<u32> },
+}
+
+/// The generator driving the current value of a running envelope.
+#[derive(Debug, Clone, Copy)]
+enum EnvPhase {
+    Constant(f32),
+    Linear {
+        value: f32,
+        delta: f32,
+        remaining: usize,
+        target: f32,
+    },
+    Exponential {
+        offset: f32,
+        target: f32,
+        decay: f32,
+    },
+}
+
+impl EnvPhase {
+    fn value(&self) -> f32 {
+        match self {
+            &EnvPhase::Constant(value) => value,
+            &EnvPhase::Linear { value, .. } => value,
+            &EnvPhase::Exponential { offset, target, .. } => target + offset,
+        }
+    }
+
+    fn render(&mut self, output: &mut [f32]) {
+        match self {
+            &mut EnvPhase::Constant(value) => {
+                for output in output.iter_mut() {
+                    *output = value;
+                }
+            }
+            &mut EnvPhase::Linear {
+                ref mut value,
+                delta,
+                ref mut remaining,
+                target,
+            } => {
+                let mut cur = *value;
+                let n = min(*remaining, output.len());
+                for output in output[..n].iter_mut() {
+                    cur += delta;
+                    *output = cur;
+                }
+                if n < *remaining {
+                    *value = cur;
+                    *remaining -= n;
+                } else {
+                    for output in output[n..].iter_mut() {
+                        *output = target;
+                    }
+                    *self = EnvPhase::Constant(target);
+                }
+            }
+            &mut EnvPhase::Exponential {
+                ref mut offset,
+                target,
+                decay,
+            } => {
+                let mut cur = *offset;
+                for output in output.iter_mut() {
+                    *output = target + cur;
+                    cur *= decay;
+                }
+                *offset = cur;
+            }
+        }
+    }
+}
+
+/// When the current segment finishes and the next one starts.
+#[derive(Debug, Clone, Copy)]
+enum EnvTime {
+    /// Segment is done now, start the next segment immediately.
+    Done,
+    /// Segment will run forever.
+    Forever,
+    /// Segment will run for a fixed amount of time.
+    Timed(usize),
+    /// Segment will run until the gate is triggered.
+    Gate,
+}
+
+/// Runtime state for an [`Instr::Envelope`]: a single cursor walking its
+/// segments. This is a minimal interpreter, unlike
+/// [`super::envelope::EnvelopeF`], which runs multiple overlapping sections
+/// in parallel so a later `gate` segment's generator can take over smoothly
+/// mid-curve; here, one segment finishes before the next begins.
+#[derive(Debug)]
+struct EnvMem {
+    segments: Box<[FSeg]>,
+    index: usize,
+    phase: EnvPhase,
+    time: EnvTime,
+    /// Start index of the [`FSeg::Loop`] currently being repeated, if any.
+    loop_active_start: Option<usize>,
+    /// Remaining loop-backs for the loop at `loop_active_start`, for a
+    /// finite ([`Some`]) [`FSeg::Loop::count`]; unused for an infinite one.
+    loop_remaining: u32,
+}
+
+impl EnvMem {
+    fn new(segments: &[Segment], sample_rate: f64) -> EnvMem {
+        let mut fsegs = Vec::with_capacity(segments.len());
+        let mut loop_start = 0;
+        for &seg in segments.iter() {
+            let fseg = match seg {
+                Segment::Set { value } => FSeg::Set {
+                    value: value as f32,
+                },
+                Segment::Linear { time, value } => FSeg::Linear {
+                    value: value as f32,
+                    time: time_from((time * sample_rate) as f32),
+                },
+                Segment::Exponential {
+                    time_constant,
+                    value,
+                } => FSeg::Exponential {
+                    value: value as f32,
+                    time_constant: (time_constant * sample_rate) as f32,
+                    threshold: 0.05,
+                },
+                Segment::Delay { time } => FSeg::Delay {
+                    time: time_from((time * sample_rate) as f32),
+                },
+                Segment::Gate => FSeg::Gate,
+                Segment::Stop => FSeg::Stop,
+                Segment::Loop { count } => FSeg::Loop {
+                    start: loop_start,
+                    count,
+                },
+            };
+            fsegs.push(fseg);
+            if let FSeg::Loop { .. } = fseg {
+                loop_start = fsegs.len();
+            }
+        }
+        EnvMem {
+            segments: fsegs.into_boxed_slice(),
+            index: 0,
+            phase: EnvPhase::Constant(0.0),
+            time: EnvTime::Done,
+            loop_active_start: None,
+            loop_remaining: 0,
+        }
+    }
+
+    fn advance(&mut self, offset: usize, end: &mut Option<usize>) {
+        let seg = match self.segments.get(self.index) {
+            None => {
+                self.time = EnvTime::Forever;
+                return;
+            }
+            Some(&seg) => seg,
+        };
+        self.index += 1;
+        self.time = match seg {
+            FSeg::Set { value } => {
+                self.phase = EnvPhase::Constant(value);
+                EnvTime::Done
+            }
+            FSeg::Linear { value, time } => {
+                let target = value;
+                let value = self.phase.value();
+                let delta = (target - value) / (time as f32);
+                self.phase = EnvPhase::Linear {
+                    value,
+                    delta,
+                    remaining: time,
+                    target,
+                };
+                EnvTime::Timed(time)
+            }
+            FSeg::Exponential {
+                value,
+                time_constant,
+                threshold,
+            } => {
+                let target = value;
+                let offset = self.phase.value() - target;
+                let decay = (-1.0 / time_constant).exp();
+                let time = time_from(time_constant * (offset.abs() / threshold).ln());
+                self.phase = EnvPhase::Exponential {
+                    offset,
+                    target,
+                    decay,
+                };
+                EnvTime::Timed(time)
+            }
+            FSeg::Delay { time } => EnvTime::Timed(time),
+            FSeg::Gate => EnvTime::Gate,
+            FSeg::Stop => {
+                *end = Some(match *end {
+                    None => offset,
+                    Some(prev) => min(prev, offset),
+                });
+                EnvTime::Done
+            }
+            FSeg::Loop { start, count } => {
+                if self.loop_active_start != Some(start) {
+                    self.loop_active_start = Some(start);
+                    self.loop_remaining = count.unwrap_or(0);
+                }
+                let should_loop = match count {
+                    None => true,
+                    Some(_) => {
+                        if self.loop_remaining > 0 {
+                            self.loop_remaining -= 1;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                };
+                if should_loop {
+                    self.index = start;
+                } else {
+                    self.loop_active_start = None;
+                }
+                EnvTime::Done
+            }
+        };
+    }
+
+    fn render(&mut self, output: &mut [f32], gate: Option<usize>, end: &mut Option<usize>) {
+        let mut output = output;
+        let mut pos = 0;
+        loop {
+            let done = match self.time {
+                EnvTime::Done => Some(0),
+                EnvTime::Forever => {
+                    self.phase.render(output);
+                    None
+                }
+                EnvTime::Timed(time) => {
+                    if time < output.len() {
+                        self.phase.render(&mut output[..time]);
+                        Some(time)
+                    } else {
+                        self.phase.render(output);
+                        self.time = EnvTime::Timed(time - output.len());
+                        None
+                    }
+                }
+                EnvTime::Gate => match gate {
+                    Some(time) if time < output.len() => {
+                        self.phase.render(&mut output[..time]);
+                        Some(time)
+                    }
+                    _ => {
+                        self.phase.render(output);
+                        None
+                    }
+                },
+            };
+            match done {
+                Some(n) => {
+                    output = &mut output[n..];
+                    pos += n;
+                }
+                None => return,
+            }
+            self.advance(pos, end);
+        }
+    }
+}
+
+/// Per-instruction runtime state: oscillator phase, filter history, noise
+/// generators, and envelope cursors. `Instr` variants with no memory of
+/// their own (arithmetic, constants) use `Mem::None`.
+#[derive(Debug)]
+enum Mem {
+    None,
+    Osc {
+        phase: f32,
+    },
+    PhaseMod {
+        phase: f32,
+        /// Last two output samples, averaged for the feedback term.
+        prev: [f32; 2],
+    },
+    Noise {
+        rand: Rand,
+    },
+    ColorNoise {
+        rand: Rand,
+        pink: [f32; PINK_GENERATORS],
+        pink_counter: u32,
+        brown: f32,
+        gaussian_cache: Option<f32>,
+    },
+    /// Shared by [`Instr::Svf`] and [`Instr::HighPass`], which both reduce to
+    /// one or two cascaded state-variable stages.
+    Svf {
+        stage: [[f32; 2]; 2],
+    },
+    Filter {
+        low: f32,
+        band: f32,
+    },
+    Envelope(EnvMem),
+    Sample {
+        pos: usize,
+    },
+    Fm {
+        phase: [f32; OPERATOR_COUNT],
+        prev: [f32; 2],
+    },
+    RateEnvelope {
+        phase: envelope::RatePhase,
+        level: f32,
+        counter: u32,
+    },
+}
+
+impl Mem {
+    fn new(instr: &Instr, parameters: &Parameters) -> Mem {
+        match instr {
+            Instr::Osc { .. } => Mem::Osc { phase: 0.0 },
+            Instr::PhaseMod { .. } => Mem::PhaseMod {
+                phase: 0.0,
+                prev: [0.0, 0.0],
+            },
+            Instr::Noise => Mem::Noise {
+                rand: Rand::with_default_seed(),
+            },
+            Instr::ColorNoise { .. } => Mem::ColorNoise {
+                rand: Rand::with_default_seed(),
+                pink: [0.0; PINK_GENERATORS],
+                pink_counter: 0,
+                brown: 0.0,
+                gaussian_cache: None,
+            },
+            Instr::Svf { .. } | Instr::HighPass { .. } => Mem::Svf {
+                stage: [[0.0, 0.0]; 2],
+            },
+            Instr::Filter { .. } => Mem::Filter {
+                low: 0.0,
+                band: 0.0,
+            },
+            Instr::Envelope { segments } => {
+                Mem::Envelope(EnvMem::new(segments, parameters.sample_rate))
+            }
+            Instr::Sample { .. } => Mem::Sample { pos: 0 },
+            Instr::Fm { .. } => Mem::Fm {
+                phase: [0.0; OPERATOR_COUNT],
+                prev: [0.0, 0.0],
+            },
+            Instr::RateEnvelope { .. } => Mem::RateEnvelope {
+                phase: envelope::RatePhase::Attack,
+                level: 0.0,
+                counter: 0,
+            },
+            _ => Mem::None,
+        }
+    }
+}
+
+/// Runtime state for executing a [`Program`]: scratch buffers and
+/// per-instruction memory, allocated once by [`Program::start`] and reused
+/// across calls to [`Program::render`].
+#[derive(Debug)]
+pub struct RunState {
+    buffer_size: usize,
+    buffer: Box<[f32]>,
+    scale: f32,
+    mem: Box<[Mem]>,
+}
+
+/// One step of the oversampled state-variable filter core, shared by every
+/// [`FilterMode`]. Returns the band, low, and high outputs, plus the updated
+/// state.
+fn svf_step(state: [f32; 2], x: f32, f: f32, invq: f32) -> (f32, f32, f32, [f32; 2]) {
+    let [a, b] = state;
+    let b = b + f * a;
+    let c = x - b - invq * a;
+    let a = a + f * c;
+    let b = b + f * a;
+    let c = x - b - invq * a;
+    let a = a + f * c;
+    (a, b, c, [a, b])
+}
+
+fn svf_output(mode: FilterMode, x: f32, a: f32, b: f32, c: f32, invq: f32, gain: f32) -> f32 {
+    match mode {
+        FilterMode::LowPass2 | FilterMode::LowPass4 => b,
+        FilterMode::HighPass2 => c,
+        FilterMode::BandPass2 => a,
+        FilterMode::Notch => b + c,
+        FilterMode::Peak => b - c,
+        FilterMode::Allpass => b + c - invq * a,
+        FilterMode::LowShelf => x + (gain - 1.0) * b,
+        FilterMode::HighShelf => x + (gain - 1.0) * c,
+    }
+}
+
+impl Program {
+    /// Create fresh runtime state for this program: scratch buffers and
+    /// per-instruction memory, sized for `parameters.buffer_size`.
+    pub fn start(&self, parameters: &Parameters) -> RunState {
+        let buffer_size = parameters.buffer_size;
+        let mut buffer = Vec::new();
+        buffer.resize(buffer_size.checked_mul(self.instrs.len()).unwrap(), 0.0f32);
+        let mem: Vec<Mem> = self
+            .instrs
+            .iter()
+            .map(|instr| Mem::new(instr, parameters))
+            .collect();
+        RunState {
+            buffer_size,
+            buffer: buffer.into_boxed_slice(),
+            scale: ((2.0 * f64::consts::PI) / parameters.sample_rate) as f32,
+            mem: mem.into_boxed_slice(),
+        }
+    }
+
+    /// Render one buffer of output, executing the instruction list against
+    /// `state`'s scratch buffers and per-instruction memory. Returns the
+    /// number of valid samples written to the front of `out`: the state's
+    /// buffer size, or fewer once an `Instr::Envelope` segment stops the
+    /// program. `out` must be at least that buffer size long.
+    pub fn render(&self, input: &Input, state: &mut RunState, out: &mut [f32]) -> usize {
+        let buffer_size = state.buffer_size;
+        let scale = state.scale;
+        let constants = &self.constants[..];
+        let mut end: Option<usize> = None;
+        let buffer = &mut state.buffer[..];
+        let mem = &mut state.mem[..];
+        let mut outputs: Vec<&[f32]> = Vec::new();
+        outputs.resize(self.instrs.len(), Default::default());
+        for ((n, (instr, chunk_mem)), output) in self
+            .instrs
+            .iter()
+            .zip(mem.iter_mut())
+            .enumerate()
+            .zip(buffer.chunks_mut(buffer_size))
+        {
+            match instr {
+                Instr::Osc { frequency } => {
+                    let freq = outputs[*frequency as usize];
+                    let phase = match chunk_mem {
+                        Mem::Osc { phase } => phase,
+                        _ => unreachable!(),
+                    };
+                    let osc_scale = scale / (2.0 * f32::consts::PI);
+                    let mut p = *phase;
+                    for (o, &f) in output.iter_mut().zip(freq.iter()) {
+                        *o = p;
+                        p += f * osc_scale;
+                        if p > 1.0 {
+                            p -= 1.0;
+                        }
+                    }
+                    *phase = p;
+                }
+                Instr::PhaseMod {
+                    frequency,
+                    modulation,
+                    feedback,
+                } => {
+                    let freq = outputs[*frequency as usize];
+                    let modbuf = outputs[*modulation as usize];
+                    let feedback = constants[*feedback as usize];
+                    let (phase, prev) = match chunk_mem {
+                        Mem::PhaseMod { phase, prev } => (phase, prev),
+                        _ => unreachable!(),
+                    };
+                    // Matches the fixed 48kHz phase scale used by the live
+                    // `PhaseModOscillatorF` path.
+                    let osc_scale = 1.0 / 48000.0;
+                    let mut p = *phase;
+                    let mut pr = *prev;
+                    for ((o, &f), &m) in output.iter_mut().zip(freq.iter()).zip(modbuf.iter()) {
+                        let fb = feedback * (pr[0] + pr[1]) * 0.5;
+                        *o = ((p + m + fb) * (2.0 * f32::consts::PI)).sin();
+                        pr = [pr[1], *o];
+                        p += f * osc_scale;
+                        if p > 1.0 {
+                            p -= 1.0;
+                        }
+                    }
+                    *phase = p;
+                    *prev = pr;
+                }
+                Instr::ApplyFunction { input, function } => {
+                    let inbuf = outputs[*input as usize];
+                    use PointFunction::*;
+                    match function {
+                        Sine => {
+                            for (o, &p) in output.iter_mut().zip(inbuf.iter()) {
+                                *o = (p * (2.0 * f32::consts::PI)).sin();
+                            }
+                        }
+                        Sawtooth => {
+                            for (o, &p) in output.iter_mut().zip(inbuf.iter()) {
+                                *o = p * 2.0 - 1.0;
+                            }
+                        }
+                        Saturate => {
+                            for (o, &x) in output.iter_mut().zip(inbuf.iter()) {
+                                *o = x.tanh();
+                            }
+                        }
+                        Rectify => {
+                            for (o, &x) in output.iter_mut().zip(inbuf.iter()) {
+                                *o = x.abs();
+                            }
+                        }
+                    }
+                }
+                Instr::Noise => {
+                    let rand = match chunk_mem {
+                        Mem::Noise { rand } => rand,
+                        _ => unreachable!(),
+                    };
+                    for o in output.iter_mut() {
+                        *o = rand.next_float() * 2.0 - 1.0;
+                    }
+                }
+                Instr::ColorNoise { color } => {
+                    let (rand, pink, pink_counter, brown, gaussian_cache) = match chunk_mem {
+                        Mem::ColorNoise {
+                            rand,
+                            pink,
+                            pink_counter,
+                            brown,
+                            gaussian_cache,
+                        } => (rand, pink, pink_counter, brown, gaussian_cache),
+                        _ => unreachable!(),
+                    };
+                    for o in output.iter_mut() {
+                        *o = ops::render_sample(
+                            *color,
+                            rand,
+                            pink,
+                            pink_counter,
+                            brown,
+                            gaussian_cache,
+                        );
+                    }
+                }
+                Instr::Multiply { x, y } => {
+                    let xbuf = outputs[*x as usize];
+                    let ybuf = outputs[*y as usize];
+                    for ((o, &xv), &yv) in output.iter_mut().zip(xbuf.iter()).zip(ybuf.iter()) {
+                        *o = xv * yv;
+                    }
+                }
+                Instr::Mix { base, input, gain } => {
+                    let basebuf = outputs[*base as usize];
+                    let inbuf = outputs[*input as usize];
+                    let gain = constants[*gain as usize];
+                    for ((o, &b), &i) in output.iter_mut().zip(basebuf.iter()).zip(inbuf.iter()) {
+                        *o = b + gain * i;
+                    }
+                }
+                Instr::Frequency { input } => {
+                    let inbuf = outputs[*input as usize];
+                    for (o, &x) in output.iter_mut().zip(inbuf.iter()) {
+                        *o = 630.0 * 32.0f32.powf(x);
+                    }
+                }
+                Instr::Zero => {
+                    for o in output.iter_mut() {
+                        *o = 0.0;
+                    }
+                }
+                Instr::ScaleInt { input, scale } => {
+                    let inbuf = outputs[*input as usize];
+                    let scale = *scale as f32;
+                    for (o, &x) in output.iter_mut().zip(inbuf.iter()) {
+                        *o = x * scale;
+                    }
+                }
+                Instr::Note { offset } => {
+                    let frequency =
+                        440.0 * 2.0f32.powf((input.note + (offset - 69) as f32) * (1.0 / 12.0));
+                    for o in output.iter_mut() {
+                        *o = frequency;
+                    }
+                }
+                Instr::Constant { value } => {
+                    let value = constants[*value as usize];
+                    for o in output.iter_mut() {
+                        *o = value;
+                    }
+                }
+                Instr::Svf {
+                    input,
+                    frequency,
+                    mode,
+                    invq,
+                    gain,
+                } => {
+                    let inbuf = outputs[*input as usize];
+                    let freqbuf = outputs[*frequency as usize];
+                    let invq = constants[*invq as usize];
+                    let gain = constants[*gain as usize];
+                    let stage = match chunk_mem {
+                        Mem::Svf { stage } => stage,
+                        _ => unreachable!(),
+                    };
+                    let mut s0 = stage[0];
+                    let mut s1 = stage[1];
+                    for ((o, &x), &freq) in
+                        output.iter_mut().zip(inbuf.iter()).zip(freqbuf.iter())
+                    {
+                        // FIXME: should be 10k
+                        let f = (scale * (freq * 0.5).min(20000.0)).sin();
+                        let (a0, b0, c0, ns0) = svf_step(s0, x, f, invq);
+                        s0 = ns0;
+                        *o = if let FilterMode::LowPass4 = mode {
+                            let (_a1, b1, _c1, ns1) = svf_step(s1, b0, f, invq);
+                            s1 = ns1;
+                            b1
+                        } else {
+                            svf_output(*mode, x, a0, b0, c0, invq, gain)
+                        };
+                    }
+                    stage[0] = s0;
+                    stage[1] = s1;
+                }
+                Instr::HighPass { input, frequency } => {
+                    let inbuf = outputs[*input as usize];
+                    let frequency = constants[*frequency as usize];
+                    // FIXME: should be 10k
+                    let f = (scale * (frequency * 0.5).min(20000.0)).sin();
+                    let invq = 2.0f32.sqrt();
+                    let stage = match chunk_mem {
+                        Mem::Svf { stage } => stage,
+                        _ => unreachable!(),
+                    };
+                    let mut s = stage[0];
+                    for (o, &x) in output.iter_mut().zip(inbuf.iter()) {
+                        let (_a, _b, c, ns) = svf_step(s, x, f, invq);
+                        s = ns;
+                        *o = c;
+                    }
+                    stage[0] = s;
+                }
+                Instr::Filter {
+                    input,
+                    cutoff,
+                    resonance,
+                    mode,
+                } => {
+                    let inbuf = outputs[*input as usize];
+                    let cutoffbuf = outputs[*cutoff as usize];
+                    let resonancebuf = outputs[*resonance as usize];
+                    let (low, band) = match chunk_mem {
+                        Mem::Filter { low, band } => (low, band),
+                        _ => unreachable!(),
+                    };
+                    let mut l = *low;
+                    let mut b = *band;
+                    for (((o, &x), &cutoff), &resonance) in output
+                        .iter_mut()
+                        .zip(inbuf.iter())
+                        .zip(cutoffbuf.iter())
+                        .zip(resonancebuf.iter())
+                    {
+                        // `scale` is `2*pi/sample_rate`; half of that is
+                        // `pi/sample_rate`, as Chamberlin's `f` coefficient
+                        // wants. Clamp to a quarter of the sample rate,
+                        // where this topology starts to self-oscillate
+                        // regardless of resonance.
+                        let angle = (scale * 0.5 * cutoff).min(f32::consts::FRAC_PI_4);
+                        let f = 2.0 * angle.sin();
+                        let invq = 1.0 / resonance;
+                        let high = x - l - invq * b;
+                        b += f * high;
+                        l += f * b;
+                        *o = match mode {
+                            ChamberlinMode::LowPass => l,
+                            ChamberlinMode::HighPass => high,
+                            ChamberlinMode::BandPass => b,
+                            ChamberlinMode::Notch => l + high,
+                        };
+                    }
+                    *low = l;
+                    *band = b;
+                }
+                Instr::Envelope { .. } => {
+                    let env = match chunk_mem {
+                        Mem::Envelope(env) => env,
+                        _ => unreachable!(),
+                    };
+                    env.render(output, input.gate, &mut end);
+                }
+                Instr::Sample { frames } => {
+                    let pos = match chunk_mem {
+                        Mem::Sample { pos } => pos,
+                        _ => unreachable!(),
+                    };
+                    for o in output.iter_mut() {
+                        *o = frames.get(*pos).copied().unwrap_or(0.0);
+                        *pos += 1;
+                    }
+                }
+                Instr::Fm {
+                    frequency,
+                    algorithm,
+                    operators,
+                    feedback,
+                } => {
+                    let freq = outputs[*frequency as usize];
+                    let feedback = constants[*feedback as usize];
+                    let osc_scale = scale / (2.0 * f32::consts::PI);
+                    let (phase, prev) = match chunk_mem {
+                        Mem::Fm { phase, prev } => (phase, prev),
+                        _ => unreachable!(),
+                    };
+                    for (o, &f) in output.iter_mut().zip(freq.iter()) {
+                        *o = fm::render_sample(
+                            *algorithm,
+                            operators,
+                            feedback,
+                            osc_scale,
+                            f,
+                            phase,
+                            prev,
+                        );
+                    }
+                }
+                Instr::RateEnvelope {
+                    attack_rate,
+                    decay1_rate,
+                    sustain_level,
+                    decay2_rate,
+                    release_rate,
+                } => {
+                    let sustain_level = constants[*sustain_level as usize];
+                    let (phase, level, counter) = match chunk_mem {
+                        Mem::RateEnvelope {
+                            phase,
+                            level,
+                            counter,
+                        } => (phase, level, counter),
+                        _ => unreachable!(),
+                    };
+                    for (i, o) in output.iter_mut().enumerate() {
+                        if input.gate == Some(i) && *phase != envelope::RatePhase::Release {
+                            *phase = envelope::RatePhase::Release;
+                            *counter = 0;
+                        }
+                        let shift = envelope::rate_shift(match *phase {
+                            envelope::RatePhase::Attack => *attack_rate,
+                            envelope::RatePhase::Decay1 => *decay1_rate,
+                            envelope::RatePhase::Decay2 => *decay2_rate,
+                            envelope::RatePhase::Release => *release_rate,
+                        });
+                        let (new_level, new_phase) =
+                            envelope::rate_step(*phase, *level, sustain_level, shift, counter);
+                        *level = new_level;
+                        *phase = new_phase;
+                        *o = new_level;
+                    }
+                }
+            }
+            outputs[n] = output;
+        }
+        let last = outputs[self.instrs.len() - 1];
+        let n = end.unwrap_or(buffer_size);
+        out[..n].copy_from_slice(&last[..n]);
+        n
+    }
+}
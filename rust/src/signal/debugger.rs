@@ -0,0 +1,126 @@
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+
+/// Per-node execution summary: the chunk's min, max, and RMS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkSummary {
+    pub min: f32,
+    pub max: f32,
+    pub rms: f32,
+}
+
+impl ChunkSummary {
+    pub(crate) fn of(chunk: &[f32]) -> Self {
+        let mut min = std::f32::INFINITY;
+        let mut max = std::f32::NEG_INFINITY;
+        let mut sum_sq = 0.0f32;
+        for &x in chunk.iter() {
+            min = min.min(x);
+            max = max.max(x);
+            sum_sq += x * x;
+        }
+        let rms = (sum_sq / (chunk.len().max(1) as f32)).sqrt();
+        ChunkSummary { min, max, rms }
+    }
+}
+
+/// A command from the debugger's user callback, borrowing the vocabulary of
+/// a machine-emulator debugger's breakpoint/trace/repeat command loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// Render this node, then stop again at the very next node.
+    Step,
+    /// Render this node and don't stop again until the next breakpoint.
+    Continue,
+    /// Print the live execution state, then ask again without advancing.
+    Print,
+}
+
+/// Node-level tracer/debugger for a [`Program`](super::program::Program).
+/// Attach one with
+/// [`Program::attach_debugger`](super::program::Program::attach_debugger)
+/// and drive rendering through
+/// [`Program::render_debug`](super::program::Program::render_debug) to have
+/// each node's `{:?}` and output [`ChunkSummary`] dumped as it runs, and to
+/// stop at breakpoints for interactive inspection. The ordinary
+/// [`Program::render`](super::program::Program::render) family still traces
+/// into an attached debugger but never stops at a breakpoint or invokes a
+/// callback, so the real-time path pays only a single `Option::is_none`
+/// check per node when no debugger is attached.
+///
+/// Breakpoints are kept in a `Vec` rather than a hash set: a debugging
+/// session has at most a handful of them, and a linear scan keeps this
+/// usable from `no_std` builds, which have no hasher to build one.
+#[derive(Debug, Default)]
+pub struct Debugger {
+    breakpoints: Vec<usize>,
+    trace_only: bool,
+    stepping: bool,
+    last_command: Option<DebugCommand>,
+    repeat_count: usize,
+}
+
+impl Debugger {
+    /// Create a debugger with no breakpoints, tracing every node.
+    pub fn new() -> Self {
+        Debugger {
+            breakpoints: Vec::new(),
+            trace_only: true,
+            stepping: false,
+            last_command: None,
+            repeat_count: 0,
+        }
+    }
+
+    /// Stop at `node`, not just trace it, from now on.
+    pub fn break_at(&mut self, node: usize) {
+        if !self.breakpoints.contains(&node) {
+            self.breakpoints.push(node);
+        }
+        self.trace_only = false;
+    }
+
+    /// Remove `node`'s breakpoint; returns whether one was set.
+    pub fn remove_breakpoint(&mut self, node: usize) -> bool {
+        match self.breakpoints.iter().position(|&b| b == node) {
+            Some(idx) => {
+                self.breakpoints.swap_remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// When set, every node is traced (but never stopped at) regardless of
+    /// breakpoints.
+    pub fn set_trace_only(&mut self, trace_only: bool) {
+        self.trace_only = trace_only;
+    }
+
+    /// The last command the user's callback returned, and how many times in
+    /// a row it's repeated -- mirrors an emulator debugger re-running the
+    /// last command on a blank Enter.
+    pub fn last_command(&self) -> Option<(DebugCommand, usize)> {
+        self.last_command.map(|command| (command, self.repeat_count))
+    }
+
+    pub(crate) fn should_trace(&self, node: usize) -> bool {
+        self.trace_only || self.stepping || self.breakpoints.contains(&node)
+    }
+
+    pub(crate) fn should_stop(&self, node: usize) -> bool {
+        !self.trace_only && (self.stepping || self.breakpoints.contains(&node))
+    }
+
+    pub(crate) fn record(&mut self, command: DebugCommand) {
+        if self.last_command == Some(command) {
+            self.repeat_count += 1;
+        } else {
+            self.repeat_count = 1;
+        }
+        self.last_command = Some(command);
+        self.stepping = command == DebugCommand::Step;
+    }
+}
@@ -0,0 +1,44 @@
+use super::graph::{Graph, SignalRef};
+use std::io::Write;
+
+/// Render `graph` as a Graphviz `digraph`.
+///
+/// Each [`Node`](super::graph::Node) becomes one graph node, labeled with its
+/// `{:?}` representation (operator name plus parameters such as filter
+/// [`Mode`](super::filter::Mode), `q`, fixed frequency, or an envelope's
+/// segment list), and each input becomes an edge from the producing node to
+/// its consumer. This makes it possible to inspect signal flow when a patch
+/// doesn't sound right, or to check that an s-expression was translated to
+/// the graph as expected.
+pub fn to_dot(graph: &Graph, out: &mut dyn Write) {
+    writeln!(out, "digraph synth {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=box];").unwrap();
+    for (n, node) in graph.nodes().iter().enumerate() {
+        writeln!(
+            out,
+            "    n{} [label={}];",
+            n,
+            quote(&format!("{}: {:?}", n, node))
+        )
+        .unwrap();
+        for &SignalRef(input) in node.inputs() {
+            writeln!(out, "    n{} -> n{};", input, n).unwrap();
+        }
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// Quote and escape a string for use as a Graphviz label.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
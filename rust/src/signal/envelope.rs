@@ -1,10 +1,22 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::bytecode::{push_const, Instr, Slot};
 use super::graph::{Node, NodeResult, SignalRef};
+use super::json;
+use super::preset::{self, NodeTag};
 use super::program::{Function, Parameters, State};
 use std::cmp::min;
+use std::error;
 use std::f32;
+use std::fmt::{Display, Formatter, Result as FResult};
 
 /// Segment of an envelope.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Segment {
     /// Move to target value instantly, then hold.
     Set { value: f64 },
@@ -19,14 +31,51 @@ pub enum Segment {
     Gate,
     /// Stop the synthesizer, ending audio output.
     Stop,
+    /// Jump back to the start of the current section (the segment right
+    /// after the previous [`Segment::Loop`], or the start of the section if
+    /// there is none), for a repeating LFO-style modulation shape. `None`
+    /// loops forever; `Some(n)` loops back up to `n` times, so the body runs
+    /// `n + 1` times in total. The body must contain at least one
+    /// [`Linear`](Segment::Linear), [`Exponential`](Segment::Exponential),
+    /// [`Delay`](Segment::Delay), or [`Gate`](Segment::Gate) segment, so a
+    /// loop can't spin the same instant forever.
+    Loop { count: Option<u32> },
 }
 
 /// Envelope generator.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Envelope {
     pub segments: Box<[Segment]>,
 }
 
+impl Envelope {
+    /// Build a classic ADSR amplitude envelope, driven by the note gate:
+    /// ramp toward 1.0 over `attack` seconds, fall toward `sustain` over
+    /// `decay` seconds, hold until the gate releases, then fall toward 0.0
+    /// over `release` seconds. Each stage is an [`Segment::Exponential`]
+    /// rather than a fixed-time ramp, so it approaches its target the usual
+    /// one-pole way instead of hitting it exactly.
+    pub fn adsr(attack: f64, decay: f64, sustain: f64, release: f64) -> Envelope {
+        Envelope {
+            segments: Box::new([
+                Segment::Exponential {
+                    time_constant: attack,
+                    value: 1.0,
+                },
+                Segment::Exponential {
+                    time_constant: decay,
+                    value: sustain,
+                },
+                Segment::Gate,
+                Segment::Exponential {
+                    time_constant: release,
+                    value: 0.0,
+                },
+            ]),
+        }
+    }
+}
+
 fn time_from(time: f32) -> usize {
     if time >= 0.0 {
         if time < usize::max_value() as f32 {
@@ -39,6 +88,36 @@ fn time_from(time: f32) -> usize {
     }
 }
 
+/// Converts segment durations from seconds to sample counts without
+/// accumulating rounding error: each call tracks the exact cumulative
+/// boundary in samples (as an `f64`) and returns only the *new* samples
+/// since the last call, so a long chain of short segments still sums to the
+/// correctly-rounded total duration instead of drifting by a sample or two.
+#[derive(Default)]
+struct SampleClock {
+    acc: f64,
+    prev_boundary: usize,
+}
+
+impl SampleClock {
+    /// Advance by `time` seconds at `sample_rate` and return how many
+    /// samples this segment should run for. Negative or NaN `time` is
+    /// clamped to zero, same as [`time_from`]; the boundary saturates at
+    /// `usize::MAX`.
+    fn advance(&mut self, time: f64, sample_rate: f64) -> usize {
+        let time = if time >= 0.0 { time } else { 0.0 };
+        self.acc += time * sample_rate;
+        let boundary = if self.acc < usize::max_value() as f64 {
+            self.acc.round() as usize
+        } else {
+            usize::max_value()
+        };
+        let samples = boundary - self.prev_boundary;
+        self.prev_boundary = boundary;
+        samples
+    }
+}
+
 impl Node for Envelope {
     fn inputs(&self) -> &[SignalRef] {
         &[]
@@ -46,6 +125,8 @@ impl Node for Envelope {
     fn instantiate(&self, parameters: &Parameters) -> NodeResult {
         let mut states = Vec::<Section>::new();
         let mut segments = Vec::<FSegment>::new();
+        let mut clock = SampleClock::default();
+        let mut loop_start: usize = 0;
         fn add_state(states: &mut Vec<Section>, segments: Vec<FSegment>) {
             let mut segments = segments;
             segments.shrink_to_fit();
@@ -58,6 +139,8 @@ impl Node for Envelope {
                 time: Time::Done,
                 index: 0,
                 segments: Box::from(segments),
+                loop_active_start: None,
+                loop_remaining: 0,
             });
         }
         for &seg in self.segments.iter() {
@@ -67,7 +150,7 @@ impl Node for Envelope {
                     segments.push(FSegment::Set { value });
                 }
                 Segment::Linear { time, value } => {
-                    let time = time_from((time * parameters.sample_rate) as f32);
+                    let time = clock.advance(time, parameters.sample_rate);
                     let value = value as f32;
                     segments.push(FSegment::Linear { time, value });
                 }
@@ -84,23 +167,55 @@ impl Node for Envelope {
                     });
                 }
                 Segment::Delay { time } => {
-                    let time = time_from((time * parameters.sample_rate) as f32);
+                    let time = clock.advance(time, parameters.sample_rate);
                     segments.push(FSegment::Delay { time });
                 }
                 Segment::Gate => {
                     add_state(&mut states, segments);
                     segments = Vec::new();
                     segments.push(FSegment::Gate);
+                    clock = SampleClock::default();
+                    loop_start = segments.len();
                 }
                 Segment::Stop => {
                     segments.push(FSegment::Stop);
                 }
+                Segment::Loop { count } => {
+                    let body = &segments[loop_start..];
+                    if body.is_empty() || !body.iter().any(is_timed_segment) {
+                        return Err(Box::new(EmptyLoopBody));
+                    }
+                    segments.push(FSegment::Loop {
+                        start: loop_start,
+                        count,
+                    });
+                    loop_start = segments.len();
+                }
             }
         }
         add_state(&mut states, segments);
         states.shrink_to_fit();
         Ok(Box::new(EnvelopeF(Box::from(states))))
     }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Envelope {
+            segments: self.segments.clone(),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Envelope
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u32(out, self.segments.len() as u32);
+        for segment in self.segments.iter() {
+            preset::write_segment(out, segment);
+        }
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Envelope(Envelope {
+            segments: self.segments.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -122,8 +237,41 @@ enum FSegment {
     },
     Gate,
     Stop,
+    Loop {
+        start: usize,
+        count: Option<u32>,
+    },
 }
 
+/// True for an [`FSegment`] that takes a non-zero amount of time (or waits on
+/// the gate), so a loop whose body contains one can't spin on the same
+/// instant forever.
+fn is_timed_segment(seg: &FSegment) -> bool {
+    matches!(
+        seg,
+        FSegment::Linear { .. }
+            | FSegment::Exponential { .. }
+            | FSegment::Delay { .. }
+            | FSegment::Gate
+    )
+}
+
+/// A [`Segment::Loop`] whose body contains no timed or gated segment, so it
+/// would repeat the same instant forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EmptyLoopBody;
+
+impl Display for EmptyLoopBody {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        write!(
+            f,
+            "envelope loop body must contain at least one timed or gated segment"
+        )
+    }
+}
+
+impl error::Error for EmptyLoopBody {}
+
 /// A generator for an infinite sequence of envelope values.
 #[derive(Debug, Clone, Copy)]
 enum Generator {
@@ -236,6 +384,15 @@ struct Section {
     time: Time,
     index: usize,
     segments: Box<[FSegment]>,
+    /// Start index of the [`FSegment::Loop`] currently being repeated, if
+    /// any -- only meaningful while equal to that loop's own `start`, which
+    /// is how a freshly-entered loop is told apart from one already
+    /// mid-repeat.
+    loop_active_start: Option<usize>,
+    /// Remaining loop-backs for the loop at `loop_active_start`, for a
+    /// finite ([`Some`]) [`FSegment::Loop::count`]; unused for an infinite
+    /// one.
+    loop_remaining: u32,
 }
 
 impl Section {
@@ -327,6 +484,29 @@ impl Section {
                 state.stop(offset);
                 Time::Done
             }
+            Loop { start, count } => {
+                if self.loop_active_start != Some(start) {
+                    self.loop_active_start = Some(start);
+                    self.loop_remaining = count.unwrap_or(0);
+                }
+                let should_loop = match count {
+                    None => true,
+                    Some(_) => {
+                        if self.loop_remaining > 0 {
+                            self.loop_remaining -= 1;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                };
+                if should_loop {
+                    self.index = start;
+                } else {
+                    self.loop_active_start = None;
+                }
+                Time::Done
+            }
         };
     }
 
@@ -357,3 +537,180 @@ impl Function for EnvelopeF {
         }
     }
 }
+
+// =================================================================================================
+// Rate-based four-stage envelope
+// =================================================================================================
+
+/// Number of bits in a [`RateEnvelope`] rate parameter.
+const RATE_BITS: u32 = 5;
+
+/// Largest representable rate; rate [`RATE_MAX`] updates every sample.
+const RATE_MAX: u8 = (1 << RATE_BITS) - 1;
+
+/// Map a `0..=RATE_MAX` rate to a shift: the envelope only re-evaluates
+/// every `2^shift` samples, so a higher rate (smaller shift) updates more
+/// often. Each step in rate halves the interval between updates, giving
+/// roughly exponential timing across the whole range, the same way a real
+/// chip's envelope generator clock divider works.
+pub(crate) fn rate_shift(rate: u8) -> u32 {
+    (RATE_MAX - rate.min(RATE_MAX)) as u32
+}
+
+/// Per-update step for [`RatePhase::Decay1`], [`RatePhase::Decay2`], and
+/// [`RatePhase::Release`], which fall by a fixed amount each time they tick.
+const DECAY_STEP: f32 = 1.0 / 256.0;
+
+/// Per-update coefficient for [`RatePhase::Attack`]'s concave rise: each
+/// tick closes this fraction of the remaining distance to 1.0.
+const ATTACK_COEFF: f32 = 1.0 / 8.0;
+
+/// A rate-based four-stage envelope generator, modeled on the YM2612's
+/// envelope generator: [`Attack`](RatePhase::Attack) rises from 0 toward 1.0
+/// with a concave curve, [`Decay1`](RatePhase::Decay1) falls linearly from
+/// 1.0 toward `sustain_level`, [`Decay2`](RatePhase::Decay2) continues
+/// falling linearly from there, and [`Release`](RatePhase::Release) falls
+/// linearly to 0 once the gate releases. Each stage's rate maps to an update
+/// period via [`rate_shift`]; this produces a plain `0..1` signal, to be
+/// multiplied onto another signal the same way [`Envelope`]'s output is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateEnvelope {
+    pub attack_rate: u8,
+    pub decay1_rate: u8,
+    pub sustain_level: f64,
+    pub decay2_rate: u8,
+    pub release_rate: u8,
+}
+
+impl Node for RateEnvelope {
+    fn inputs(&self) -> &[SignalRef] {
+        &[]
+    }
+    fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(RateEnvelopeF {
+            attack_rate: self.attack_rate,
+            decay1_rate: self.decay1_rate,
+            sustain_level: self.sustain_level as f32,
+            decay2_rate: self.decay2_rate,
+            release_rate: self.release_rate,
+            phase: RatePhase::Attack,
+            level: 0.0,
+            counter: 0,
+        }))
+    }
+    fn encode(&self, _inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::RateEnvelope {
+            attack_rate: self.attack_rate,
+            decay1_rate: self.decay1_rate,
+            sustain_level: push_const(constants, self.sustain_level as f32),
+            decay2_rate: self.decay2_rate,
+            release_rate: self.release_rate,
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::RateEnvelope
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.attack_rate);
+        preset::write_u8(out, self.decay1_rate);
+        preset::write_f64(out, self.sustain_level);
+        preset::write_u8(out, self.decay2_rate);
+        preset::write_u8(out, self.release_rate);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::RateEnvelope(RateEnvelope {
+            attack_rate: self.attack_rate,
+            decay1_rate: self.decay1_rate,
+            sustain_level: self.sustain_level,
+            decay2_rate: self.decay2_rate,
+            release_rate: self.release_rate,
+        })
+    }
+}
+
+/// Which of [`RateEnvelope`]'s four stages is currently running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RatePhase {
+    Attack,
+    Decay1,
+    Decay2,
+    Release,
+}
+
+/// Advance one of [`RateEnvelopeF`]'s stages by one sample, given the shift
+/// its rate maps to, returning the (possibly unchanged) level and phase.
+pub(crate) fn rate_step(
+    phase: RatePhase,
+    level: f32,
+    sustain_level: f32,
+    shift: u32,
+    counter: &mut u32,
+) -> (f32, RatePhase) {
+    *counter += 1;
+    if *counter < (1u32 << shift) {
+        return (level, phase);
+    }
+    *counter = 0;
+    match phase {
+        RatePhase::Attack => {
+            let level = level + (1.0 - level) * ATTACK_COEFF;
+            // In f32 the rise stalls a few ulps below 1.0, so finish once
+            // it is within one decay step of it.
+            if level >= 1.0 - DECAY_STEP {
+                (1.0, RatePhase::Decay1)
+            } else {
+                (level, RatePhase::Attack)
+            }
+        }
+        RatePhase::Decay1 => {
+            let level = level - DECAY_STEP;
+            if level <= sustain_level {
+                (sustain_level, RatePhase::Decay2)
+            } else {
+                (level, RatePhase::Decay1)
+            }
+        }
+        RatePhase::Decay2 => ((level - DECAY_STEP).max(0.0), RatePhase::Decay2),
+        RatePhase::Release => ((level - DECAY_STEP).max(0.0), RatePhase::Release),
+    }
+}
+
+#[derive(Debug)]
+struct RateEnvelopeF {
+    attack_rate: u8,
+    decay1_rate: u8,
+    sustain_level: f32,
+    decay2_rate: u8,
+    release_rate: u8,
+    phase: RatePhase,
+    level: f32,
+    counter: u32,
+}
+
+impl Function for RateEnvelopeF {
+    fn render(&mut self, output: &mut [f32], _inputs: &[&[f32]], state: &mut State) {
+        let release_at = state.gate();
+        for (i, o) in output.iter_mut().enumerate() {
+            if release_at == Some(i) && self.phase != RatePhase::Release {
+                self.phase = RatePhase::Release;
+                self.counter = 0;
+            }
+            let shift = rate_shift(match self.phase {
+                RatePhase::Attack => self.attack_rate,
+                RatePhase::Decay1 => self.decay1_rate,
+                RatePhase::Decay2 => self.decay2_rate,
+                RatePhase::Release => self.release_rate,
+            });
+            let (level, phase) = rate_step(
+                self.phase,
+                self.level,
+                self.sustain_level,
+                shift,
+                &mut self.counter,
+            );
+            self.level = level;
+            self.phase = phase;
+            *o = level;
+        }
+    }
+}
@@ -1,26 +1,44 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::bytecode::{push_const, Instr, Slot};
 use super::graph::{Node, NodeResult, SignalRef};
+use super::json;
+use super::preset::{self, NodeTag};
 use super::program::{Function, Parameters, State};
+use std::f32;
 use std::f64;
 use std::slice::from_ref;
 
 // =================================================================================================
 
 /// The mode for a state-variable filter.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Mode {
     LowPass2,
     HighPass2,
     BandPass2,
     LowPass4,
+    Notch,
+    Peak,
+    Allpass,
+    LowShelf,
+    HighShelf,
 }
 
 /// A state-variable filter with a control input for frequency.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateVariable {
     /// (input, frequency)
     pub inputs: [SignalRef; 2],
     pub mode: Mode,
     pub q: f64,
+    /// Gain for the [`Mode::LowShelf`] and [`Mode::HighShelf`] modes.
+    pub gain: f64,
 }
 
 impl Node for StateVariable {
@@ -42,8 +60,38 @@ impl Node for StateVariable {
             scale: ((2.0 * f64::consts::PI) / parameters.sample_rate) as f32,
             mode: self.mode,
             invq: (1.0 / q) as f32,
+            gain: self.gain as f32,
         }))
     }
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        let q = match self.mode {
+            Mode::LowPass4 => (self.q * 0.5f64.sqrt()).sqrt(),
+            _ => self.q,
+        };
+        Instr::Svf {
+            input: inputs[0],
+            frequency: inputs[1],
+            mode: self.mode,
+            invq: push_const(constants, (1.0 / q) as f32),
+            gain: push_const(constants, self.gain as f32),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::StateVariable
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.mode as u8);
+        preset::write_f64(out, self.q);
+        preset::write_f64(out, self.gain);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::StateVariable(StateVariable {
+            inputs: self.inputs,
+            mode: self.mode,
+            q: self.q,
+            gain: self.gain,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -53,6 +101,7 @@ struct StateVariableF {
     scale: f32,
     mode: Mode,
     invq: f32,
+    gain: f32,
 }
 
 impl Function for StateVariableF {
@@ -72,6 +121,15 @@ impl Function for StateVariableF {
                 self.stage[0].render_lp(output, input, temp, self.invq);
                 self.stage[1].render_lp(output, input, temp, self.invq);
             }
+            Mode::Notch => self.stage[0].render_notch(output, input, temp, self.invq),
+            Mode::Peak => self.stage[0].render_peak(output, input, temp, self.invq),
+            Mode::Allpass => self.stage[0].render_allpass(output, input, temp, self.invq),
+            Mode::LowShelf => {
+                self.stage[0].render_lowshelf(output, input, temp, self.invq, self.gain)
+            }
+            Mode::HighShelf => {
+                self.stage[0].render_highshelf(output, input, temp, self.invq, self.gain)
+            }
         }
     }
 }
@@ -79,7 +137,7 @@ impl Function for StateVariableF {
 // =================================================================================================
 
 /// A two-pole high pass filter with Q=0.707 and fixed frequency.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HighPass {
     pub input: SignalRef,
     pub frequency: f64,
@@ -103,6 +161,24 @@ impl Node for HighPass {
             },
         }))
     }
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::HighPass {
+            input: inputs[0],
+            frequency: push_const(constants, self.frequency as f32),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::HighPass
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_f64(out, self.frequency);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::HighPass(HighPass {
+            input: self.input,
+            frequency: self.frequency,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -120,12 +196,117 @@ impl Function for HighPassF {
 
 // =================================================================================================
 
+/// Output tap for a [`Filter`]'s Chamberlin state-variable core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChamberlinMode {
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+}
+
+/// A state-variable filter with cutoff *and* resonance as per-sample signal
+/// inputs, unlike [`StateVariable`], whose `q` is a fixed constant baked in
+/// at graph-build time. Uses the classic Chamberlin two-integrator-loop
+/// form (cheaper to re-derive every sample than [`StateVariable`]'s
+/// 2x-oversampled core) so resonance can be swept by an envelope
+/// or LFO just like cutoff already can be.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Filter {
+    /// (input, cutoff, resonance)
+    pub inputs: [SignalRef; 3],
+    pub mode: ChamberlinMode,
+}
+
+impl Node for Filter {
+    fn inputs(&self) -> &[SignalRef] {
+        &self.inputs[..]
+    }
+    fn instantiate(&self, parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(FilterF {
+            low: 0.0,
+            band: 0.0,
+            scale: (f64::consts::PI / parameters.sample_rate) as f32,
+            mode: self.mode,
+        }))
+    }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Filter {
+            input: inputs[0],
+            cutoff: inputs[1],
+            resonance: inputs[2],
+            mode: self.mode,
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Filter
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.mode as u8);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Filter(Filter {
+            inputs: self.inputs,
+            mode: self.mode,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FilterF {
+    low: f32,
+    band: f32,
+    /// Angular frequency scale, `pi / sample_rate`.
+    scale: f32,
+    mode: ChamberlinMode,
+}
+
+impl Function for FilterF {
+    fn render(&mut self, output: &mut [f32], inputs: &[&[f32]], _state: &mut State) {
+        let input = inputs[0];
+        let cutoff = inputs[1];
+        let resonance = inputs[2];
+        let mut low = self.low;
+        let mut band = self.band;
+        for (((o, &x), &cutoff), &resonance) in output
+            .iter_mut()
+            .zip(input.iter())
+            .zip(cutoff.iter())
+            .zip(resonance.iter())
+        {
+            // Clamp to a quarter of the sample rate, where this topology
+            // starts to self-oscillate regardless of resonance.
+            let angle = (self.scale * cutoff).min(f32::consts::FRAC_PI_4);
+            let f = 2.0 * angle.sin();
+            let invq = 1.0 / resonance;
+            let high = x - low - invq * band;
+            band += f * high;
+            low += f * band;
+            *o = match self.mode {
+                ChamberlinMode::LowPass => low,
+                ChamberlinMode::HighPass => high,
+                ChamberlinMode::BandPass => band,
+                ChamberlinMode::Notch => low + high,
+            };
+        }
+        self.low = low;
+        self.band = band;
+    }
+}
+
+// =================================================================================================
+
 /// Mode for a state variable filter.
 #[derive(Debug)]
 enum SVFMode {
     LowPass,
     HighPass,
     BandPass,
+    Notch,
+    Peak,
+    Allpass,
+    LowShelf,
+    HighShelf,
 }
 
 /// State for a state variable filter.
@@ -139,6 +320,7 @@ impl SVF {
         input: &[f32],
         frequency: &[f32],
         invq: f32,
+        gain: f32,
         mode: SVFMode,
     ) {
         let mut state = self.0;
@@ -157,6 +339,11 @@ impl SVF {
                 SVFMode::LowPass => b,
                 SVFMode::HighPass => c,
                 SVFMode::BandPass => a,
+                SVFMode::Notch => b + c,
+                SVFMode::Peak => b - c,
+                SVFMode::Allpass => b + c - invq * a,
+                SVFMode::LowShelf => x + (gain - 1.0) * b,
+                SVFMode::HighShelf => x + (gain - 1.0) * c,
             };
             state = [a, b];
         }
@@ -164,14 +351,48 @@ impl SVF {
     }
 
     fn render_lp(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
-        self.render(output, input, frequency, invq, SVFMode::LowPass);
+        self.render(output, input, frequency, invq, 1.0, SVFMode::LowPass);
     }
 
     fn render_hp(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
-        self.render(output, input, frequency, invq, SVFMode::HighPass);
+        self.render(output, input, frequency, invq, 1.0, SVFMode::HighPass);
     }
 
     fn render_bp(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
-        self.render(output, input, frequency, invq, SVFMode::BandPass);
+        self.render(output, input, frequency, invq, 1.0, SVFMode::BandPass);
+    }
+
+    fn render_notch(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
+        self.render(output, input, frequency, invq, 1.0, SVFMode::Notch);
+    }
+
+    fn render_peak(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
+        self.render(output, input, frequency, invq, 1.0, SVFMode::Peak);
+    }
+
+    fn render_allpass(&mut self, output: &mut [f32], input: &[f32], frequency: &[f32], invq: f32) {
+        self.render(output, input, frequency, invq, 1.0, SVFMode::Allpass);
+    }
+
+    fn render_lowshelf(
+        &mut self,
+        output: &mut [f32],
+        input: &[f32],
+        frequency: &[f32],
+        invq: f32,
+        gain: f32,
+    ) {
+        self.render(output, input, frequency, invq, gain, SVFMode::LowShelf);
+    }
+
+    fn render_highshelf(
+        &mut self,
+        output: &mut [f32],
+        input: &[f32],
+        frequency: &[f32],
+        invq: f32,
+        gain: f32,
+    ) {
+        self.render(output, input, frequency, invq, gain, SVFMode::HighShelf);
     }
 }
@@ -0,0 +1,250 @@
+//! Multi-operator FM synthesis modeled on classic four-operator FM chips
+//! like the YM2612: [`FmOperator`] is a phase-accumulating sine operator
+//! with its own frequency ratio and output level, and [`FmAlgorithm`] routes
+//! four of them through one of 8 fixed [`Algorithm`] topologies -- serial
+//! chains, parallel carriers, and the mixed trees in between. Unlike
+//! [`super::ops::PhaseModOscillator`], which is a single building-block
+//! operator meant to be wired up by hand, `FmAlgorithm` owns all four
+//! operators and their routing itself.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::bytecode::{push_const, Instr, Slot};
+use super::graph::{Node, NodeResult, SignalRef};
+use super::json;
+use super::preset::{self, NodeTag};
+use super::program::{Function, Parameters, State};
+use std::convert::TryFrom;
+use std::f32;
+
+/// Number of operators in every [`FmAlgorithm`].
+pub const OPERATOR_COUNT: usize = 4;
+
+/// Number of fixed routing topologies in [`ALGORITHMS`].
+pub const ALGORITHM_COUNT: usize = 8;
+
+/// One FM operator's fixed parameters: a phase accumulator driven by the
+/// algorithm's base frequency times `ratio`, producing `level * sin(phase)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FmOperator {
+    /// Multiplier on the algorithm's base frequency.
+    pub ratio: f64,
+    /// Output level: how much this operator contributes when it's a
+    /// carrier, or how much phase modulation it injects into whatever it's
+    /// routed into.
+    pub level: f64,
+}
+
+/// A fixed operator-routing topology, one of the 8 [`ALGORITHMS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Algorithm {
+    Algorithm0,
+    Algorithm1,
+    Algorithm2,
+    Algorithm3,
+    Algorithm4,
+    Algorithm5,
+    Algorithm6,
+    Algorithm7,
+}
+
+impl TryFrom<u8> for Algorithm {
+    type Error = ();
+    fn try_from(v: u8) -> Result<Self, ()> {
+        use Algorithm::*;
+        Ok(match v {
+            0 => Algorithm0,
+            1 => Algorithm1,
+            2 => Algorithm2,
+            3 => Algorithm3,
+            4 => Algorithm4,
+            5 => Algorithm5,
+            6 => Algorithm6,
+            7 => Algorithm7,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// One topology: `connections` are `(source, dest)` pairs routing operator
+/// `source`'s output into operator `dest`'s phase, and `carriers` are the
+/// operators whose own output is summed into the algorithm's final output.
+/// Every `dest` is greater than its `source`, so rendering operators
+/// `0..OPERATOR_COUNT` in order is enough to evaluate any algorithm -- no
+/// topological sort needed.
+struct Routing {
+    connections: &'static [(usize, usize)],
+    carriers: &'static [usize],
+}
+
+/// The 8 fixed routing tables, indexed by [`Algorithm`].
+const ALGORITHMS: [Routing; ALGORITHM_COUNT] = [
+    // 0: op1 -> op2 -> op3 -> op4, full serial chain.
+    Routing {
+        connections: &[(0, 1), (1, 2), (2, 3)],
+        carriers: &[3],
+    },
+    // 1: (op1 + op2) -> op3 -> op4.
+    Routing {
+        connections: &[(0, 2), (1, 2), (2, 3)],
+        carriers: &[3],
+    },
+    // 2: op1 -> op3 -> op4, op2 -> op4.
+    Routing {
+        connections: &[(0, 2), (1, 3), (2, 3)],
+        carriers: &[3],
+    },
+    // 3: (op1 -> op2) -> op4, op3 -> op4.
+    Routing {
+        connections: &[(0, 1), (1, 3), (2, 3)],
+        carriers: &[3],
+    },
+    // 4: op1 -> op2 carrier, op3 -> op4 carrier -- two parallel 2-op chains.
+    Routing {
+        connections: &[(0, 1), (2, 3)],
+        carriers: &[1, 3],
+    },
+    // 5: op1 modulates three independent carriers op2, op3, op4.
+    Routing {
+        connections: &[(0, 1), (0, 2), (0, 3)],
+        carriers: &[1, 2, 3],
+    },
+    // 6: op1 -> op2 carrier, op3 and op4 carriers on their own.
+    Routing {
+        connections: &[(0, 1)],
+        carriers: &[1, 2, 3],
+    },
+    // 7: all four operators are carriers, summed in parallel.
+    Routing {
+        connections: &[],
+        carriers: &[0, 1, 2, 3],
+    },
+];
+
+/// Four operators routed through one of 8 fixed [`Algorithm`] topologies.
+/// Self-feedback (the first operator's previous output summed into its own
+/// phase, scaled by `feedback`) mirrors
+/// [`PhaseModOscillator`](super::ops::PhaseModOscillator).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FmAlgorithm {
+    /// Base frequency shared by every operator's ratio.
+    pub inputs: [SignalRef; 1],
+    pub algorithm: Algorithm,
+    pub operators: [FmOperator; OPERATOR_COUNT],
+    pub feedback: f64,
+}
+
+impl Node for FmAlgorithm {
+    fn inputs(&self) -> &[SignalRef] {
+        &self.inputs[..]
+    }
+    fn instantiate(&self, parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(FmAlgorithmF {
+            scale: (1.0 / parameters.sample_rate) as f32,
+            algorithm: self.algorithm,
+            operators: self.operators,
+            feedback: self.feedback as f32,
+            phase: [0.0; OPERATOR_COUNT],
+            prev: [0.0, 0.0],
+        }))
+    }
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::Fm {
+            frequency: inputs[0],
+            algorithm: self.algorithm,
+            operators: self.operators,
+            feedback: push_const(constants, self.feedback as f32),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::FmAlgorithm
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.algorithm as u8);
+        for op in &self.operators {
+            preset::write_f64(out, op.ratio);
+            preset::write_f64(out, op.level);
+        }
+        preset::write_f64(out, self.feedback);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::FmAlgorithm(FmAlgorithm {
+            inputs: self.inputs,
+            algorithm: self.algorithm,
+            operators: self.operators,
+            feedback: self.feedback,
+        })
+    }
+}
+
+/// Render one sample of `operators`/`algorithm`/`feedback` at base
+/// `frequency`, given and updating the phase accumulators in `phase` and
+/// the feedback history in `prev`.
+pub(crate) fn render_sample(
+    algorithm: Algorithm,
+    operators: &[FmOperator; OPERATOR_COUNT],
+    feedback: f32,
+    scale: f32,
+    frequency: f32,
+    phase: &mut [f32; OPERATOR_COUNT],
+    prev: &mut [f32; 2],
+) -> f32 {
+    let routing = &ALGORITHMS[algorithm as usize];
+    let mut modulation = [0.0f32; OPERATOR_COUNT];
+    let mut out = 0.0f32;
+    for i in 0..OPERATOR_COUNT {
+        let fb = if i == 0 {
+            feedback * (prev[0] + prev[1]) * 0.5
+        } else {
+            0.0
+        };
+        let y = ((phase[i] + modulation[i] + fb) * (2.0 * f32::consts::PI)).sin();
+        if i == 0 {
+            *prev = [prev[1], y];
+        }
+        let scaled = y * operators[i].level as f32;
+        for &(source, dest) in routing.connections {
+            if source == i {
+                modulation[dest] += scaled;
+            }
+        }
+        if routing.carriers.contains(&i) {
+            out += scaled;
+        }
+        phase[i] += frequency * operators[i].ratio as f32 * scale;
+        if phase[i] > 1.0 {
+            phase[i] -= 1.0;
+        }
+    }
+    out
+}
+
+#[derive(Debug)]
+struct FmAlgorithmF {
+    scale: f32,
+    algorithm: Algorithm,
+    operators: [FmOperator; OPERATOR_COUNT],
+    feedback: f32,
+    phase: [f32; OPERATOR_COUNT],
+    prev: [f32; 2],
+}
+
+impl Function for FmAlgorithmF {
+    fn render(&mut self, output: &mut [f32], inputs: &[&[f32]], _state: &mut State) {
+        let frequency = &inputs[0][0..output.len()];
+        for (o, &f) in output.iter_mut().zip(frequency.iter()) {
+            *o = render_sample(
+                self.algorithm,
+                &self.operators,
+                self.feedback,
+                self.scale,
+                f,
+                &mut self.phase,
+                &mut self.prev,
+            );
+        }
+    }
+}
@@ -1,7 +1,17 @@
-use super::program::{Function, Parameters};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::bytecode::{Instr, Slot};
+use super::preset::NodeTag;
+use super::program::{self, Function, Parameters, Program};
 use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt::Debug;
+#[cfg(not(feature = "no_std"))]
 use std::io;
 
 /// Result of instantiating a node.
@@ -14,6 +24,25 @@ pub trait Node: Debug {
 
     /// Create an instance of the node's audio function.
     fn instantiate(&self, params: &Parameters) -> NodeResult;
+
+    /// Encode this node as a single bytecode [`Instr`], given the already
+    /// resolved [`Slot`] for each of its inputs (in the same order as
+    /// [`inputs`](Self::inputs)) and a constant pool to push any floating
+    /// point literals into.
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr;
+
+    /// This node's stable tag in the binary preset format; see
+    /// [`preset::NodeTag`](super::preset::NodeTag).
+    fn preset_tag(&self) -> NodeTag;
+
+    /// Write this node's extra parameters to `out` -- everything beyond its
+    /// inputs, which [`preset::encode`](super::preset::encode) already
+    /// writes generically from [`inputs`](Self::inputs).
+    fn write_preset_params(&self, out: &mut Vec<u8>);
+
+    /// This node's tagged JSON representation; see
+    /// [`json::NodeData`](super::json::NodeData).
+    fn to_json_data(&self) -> super::json::NodeData;
 }
 
 /// Description of an audio processing graph.
@@ -27,19 +56,38 @@ impl Graph {
         Graph { nodes: Vec::new() }
     }
 
-    /// Add a new node to the graph.
-    pub fn add(&mut self, node: Box<dyn Node>) -> SignalRef {
-        for &SignalRef(idx) in node.inputs().iter() {
+    /// Add a new node to the graph, validating its inputs instead of
+    /// panicking: each input must refer to a node already added (nodes can
+    /// only reference earlier nodes, never themselves or later ones), and
+    /// there can be at most four of them, the same limit [`Program`] bakes
+    /// into its internal node representation.
+    pub fn add(&mut self, node: Box<dyn Node>) -> Result<SignalRef, program::Error> {
+        let this_node = self.nodes.len();
+        let inputs = node.inputs();
+        if inputs.len() > 4 {
+            return Err(program::Error::WrongInputCount {
+                node: this_node,
+                expected: 4,
+                found: inputs.len(),
+            });
+        }
+        for (input, &SignalRef(idx)) in inputs.iter().enumerate() {
             if idx as usize >= self.nodes.len() {
-                panic!("node input out of range");
+                return Err(program::Error::InputOutOfRange {
+                    node: this_node,
+                    input,
+                    index: idx,
+                    size: self.nodes.len(),
+                });
             }
         }
-        let idx = u32::try_from(self.nodes.len()).unwrap();
+        let idx = u32::try_from(this_node).unwrap();
         self.nodes.push(node);
-        SignalRef(idx)
+        Ok(SignalRef(idx))
     }
 
     /// Dump the graph to a stream in text format.
+    #[cfg(not(feature = "no_std"))]
     pub fn dump(&self, f: &mut dyn io::Write) {
         for (n, node) in self.nodes.iter().enumerate() {
             writeln!(f, "{}: {:?}", n, node).unwrap();
@@ -50,8 +98,21 @@ impl Graph {
     pub fn nodes(&self) -> &[Box<dyn Node>] {
         &self.nodes
     }
+
+    /// Compile this graph into a [`Program`]: a flat, topologically-ordered
+    /// buffer of node "instructions" referencing their inputs by slot index,
+    /// so rendering a sample block is a tight loop instead of chasing
+    /// pointers through this graph's boxed nodes. Dependency cycles are
+    /// reported as an error rather than causing this to loop forever.
+    pub fn compile(
+        &self,
+        output: SignalRef,
+        parameters: &Parameters,
+    ) -> Result<Program, Box<dyn Error>> {
+        Program::new(self, output, parameters)
+    }
 }
 
 /// A reference to a signal in the audio processing graph.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignalRef(pub u32);
@@ -0,0 +1,164 @@
+//! JSON patch format for a [`Graph`]: every node's type tag and parameters,
+//! keyed by a `"type"` field, so a patch reads as a flat JSON array of
+//! `{"type": "Oscillator", ...}` objects a tool or editor can inspect and
+//! hand-edit. Unlike the compact [`preset`](super::preset) format, which
+//! hand-writes a byte layout to keep control over backward compatibility,
+//! this leans on `serde`'s internally-tagged enum representation, the same
+//! way [`bytecode`](super::bytecode) already serializes a compiled
+//! [`Instr`](super::bytecode::Instr) stream.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::envelope::{Envelope, RateEnvelope};
+use super::filter::{Filter, HighPass, StateVariable};
+use super::fm::FmAlgorithm;
+use super::graph::{Graph, Node, SignalRef};
+use super::ops::{
+    ApplyFunction, ColorNoise, Constant, Frequency, Mix, Multiply, Noise, Note, Oscillator,
+    PhaseModOscillator, SamplePlayer, ScaleInt, Zero,
+};
+use super::program;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+#[cfg(not(feature = "no_std"))]
+use std::io;
+
+/// One node's tagged JSON representation -- a registry mirroring
+/// [`preset::NodeTag`](super::preset::NodeTag), but carrying each node's
+/// actual field values instead of a numeric tag plus a hand-written byte
+/// layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NodeData {
+    Oscillator(Oscillator),
+    PhaseModOscillator(PhaseModOscillator),
+    ApplyFunction(ApplyFunction),
+    Noise(Noise),
+    ColorNoise(ColorNoise),
+    Multiply(Multiply),
+    Mix(Mix),
+    Frequency(Frequency),
+    Zero(Zero),
+    ScaleInt(ScaleInt),
+    Note(Note),
+    Constant(Constant),
+    StateVariable(StateVariable),
+    HighPass(HighPass),
+    Filter(Filter),
+    Envelope(Envelope),
+    SamplePlayer(SamplePlayer),
+    FmAlgorithm(FmAlgorithm),
+    RateEnvelope(RateEnvelope),
+}
+
+impl NodeData {
+    /// Box this node's data up as a [`Node`] trait object, ready for
+    /// [`Graph::add`].
+    fn into_node(self) -> Box<dyn Node> {
+        match self {
+            NodeData::Oscillator(n) => Box::new(n),
+            NodeData::PhaseModOscillator(n) => Box::new(n),
+            NodeData::ApplyFunction(n) => Box::new(n),
+            NodeData::Noise(n) => Box::new(n),
+            NodeData::ColorNoise(n) => Box::new(n),
+            NodeData::Multiply(n) => Box::new(n),
+            NodeData::Mix(n) => Box::new(n),
+            NodeData::Frequency(n) => Box::new(n),
+            NodeData::Zero(n) => Box::new(n),
+            NodeData::ScaleInt(n) => Box::new(n),
+            NodeData::Note(n) => Box::new(n),
+            NodeData::Constant(n) => Box::new(n),
+            NodeData::StateVariable(n) => Box::new(n),
+            NodeData::HighPass(n) => Box::new(n),
+            NodeData::Filter(n) => Box::new(n),
+            NodeData::Envelope(n) => Box::new(n),
+            NodeData::SamplePlayer(n) => Box::new(n),
+            NodeData::FmAlgorithm(n) => Box::new(n),
+            NodeData::RateEnvelope(n) => Box::new(n),
+        }
+    }
+}
+
+/// The whole patch as it round-trips through JSON: nodes in index order, so
+/// [`SignalRef`] indices stay valid on reload, plus the output node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PatchData {
+    output: SignalRef,
+    nodes: Vec<NodeData>,
+}
+
+/// Error loading a patch from JSON.
+#[derive(Debug)]
+pub enum Error {
+    /// The JSON was malformed, or didn't match [`PatchData`]'s shape.
+    Json(serde_json::Error),
+    /// The JSON parsed fine, but the graph it describes is invalid -- e.g.
+    /// an input referring to a node not yet added, same validation
+    /// [`Graph::add`] already performs.
+    Graph(program::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self {
+            Error::Json(e) => write!(f, "invalid patch JSON: {}", e),
+            Error::Graph(e) => write!(f, "invalid patch graph: {}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Json(e) => Some(e),
+            Error::Graph(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<program::Error> for Error {
+    fn from(e: program::Error) -> Self {
+        Error::Graph(e)
+    }
+}
+
+impl Graph {
+    /// Write this graph and its output node to `w` as JSON (see the
+    /// [module docs](self)).
+    #[cfg(not(feature = "no_std"))]
+    pub fn to_json(&self, w: impl io::Write, output: SignalRef) -> serde_json::Result<()> {
+        let patch = PatchData {
+            output,
+            nodes: self
+                .nodes()
+                .iter()
+                .map(|node| node.to_json_data())
+                .collect(),
+        };
+        serde_json::to_writer(w, &patch)
+    }
+
+    /// Reverse of [`to_json`](Self::to_json): parse a patch from `r` and
+    /// rebuild a fresh [`Graph`], re-running the same input-range validation
+    /// [`Graph::add`] performs on each node as it's added.
+    #[cfg(not(feature = "no_std"))]
+    pub fn from_json(r: impl io::Read) -> Result<(Graph, SignalRef), Error> {
+        let patch: PatchData = serde_json::from_reader(r)?;
+        let mut graph = Graph::new();
+        for node in patch.nodes {
+            graph.add(node.into_node())?;
+        }
+        Ok((graph, patch.output))
+    }
+}
@@ -1,7 +1,17 @@
+use crate::rand::Rand;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::bytecode::{push_const, Instr, Slot};
 use super::graph::{Node, NodeResult, SignalRef};
+use super::json;
+use super::preset::{self, NodeTag};
 use super::program::{Function, Parameters, State};
 use std::error;
 use std::f32;
+use std::f64;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::slice::from_ref;
 
@@ -27,7 +37,7 @@ fn unimplemented(name: &'static str) -> NodeResult {
 // =================================================================================================
 
 /// Generate phase from frequency.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Oscillator {
     pub inputs: [SignalRef; 1],
 }
@@ -36,18 +46,86 @@ impl Node for Oscillator {
     fn inputs(&self) -> &[SignalRef] {
         &self.inputs[..]
     }
-    fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
+    fn instantiate(&self, parameters: &Parameters) -> NodeResult {
+        let oversample = parameters.oversample.max(1);
         Ok(Box::new(OscillatorF {
-            scale: 1.0 / 48000.0,
+            scale: (1.0 / (parameters.sample_rate * oversample as f64)) as f32,
             phase: 0.0,
+            oversample,
+            kernel: if oversample > 1 {
+                decimation_kernel(oversample)
+            } else {
+                Vec::new().into_boxed_slice()
+            },
+            history: vec![0.0; OVERSAMPLE_TAPS].into_boxed_slice(),
+            history_pos: 0,
         }))
     }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Osc {
+            frequency: inputs[0],
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Oscillator
+    }
+    fn write_preset_params(&self, _out: &mut Vec<u8>) {}
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Oscillator(Oscillator {
+            inputs: self.inputs,
+        })
+    }
+}
+
+/// Number of taps in the FIR kernel [`decimation_kernel`] builds, regardless
+/// of the oversampling factor.
+pub(crate) const OVERSAMPLE_TAPS: usize = 16;
+
+/// Build a windowed-sinc low-pass kernel for decimating a signal running `n`
+/// times faster than the target rate back down to the target rate: cutoff at
+/// the target Nyquist (`1 / (2n)` of the oversampled rate), windowed with a
+/// Blackman window to tame the bare sinc's slow rolloff.
+pub(crate) fn decimation_kernel(n: usize) -> Box<[f32]> {
+    let taps = OVERSAMPLE_TAPS;
+    let cutoff = 1.0 / (2.0 * n as f64);
+    let center = (taps - 1) as f64 / 2.0;
+    let mut kernel = Vec::with_capacity(taps);
+    let mut sum = 0.0;
+    for i in 0..taps {
+        let x = i as f64 - center;
+        let sinc = if x == 0.0 {
+            2.0 * cutoff
+        } else {
+            (2.0 * f64::consts::PI * cutoff * x).sin() / (f64::consts::PI * x)
+        };
+        let phase = 2.0 * f64::consts::PI * i as f64 / (taps - 1) as f64;
+        let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+        let value = sinc * window;
+        sum += value;
+        kernel.push(value);
+    }
+    for value in kernel.iter_mut() {
+        *value = (*value / sum) as f32;
+    }
+    Box::from(kernel)
 }
 
 #[derive(Debug)]
 struct OscillatorF {
+    /// Phase step per sample (or, while oversampling, per oversampled
+    /// micro-sample).
     scale: f32,
     phase: f32,
+    /// Oversampling factor; 1 disables oversampling and renders directly at
+    /// the output rate.
+    oversample: usize,
+    /// Decimation FIR kernel, empty unless oversampling.
+    kernel: Box<[f32]>,
+    /// Ring buffer of the most recent raw (oversampled) phase samples, kept
+    /// across `render` calls so the kernel can be convolved continuously
+    /// across buffer boundaries.
+    history: Box<[f32]>,
+    history_pos: usize,
 }
 
 impl Function for OscillatorF {
@@ -55,21 +133,132 @@ impl Function for OscillatorF {
         let frequency = &inputs[0][0..output.len()];
         let scale = self.scale;
         let mut phase = self.phase;
-        for (output, &frequency) in output.iter_mut().zip(frequency.iter()) {
-            *output = phase;
+        if self.oversample <= 1 {
+            for (output, &frequency) in output.iter_mut().zip(frequency.iter()) {
+                *output = phase;
+                phase += frequency * scale;
+                if phase > 1.0 {
+                    phase -= 1.0;
+                }
+            }
+        } else {
+            let taps = self.kernel.len();
+            let mut pos = self.history_pos;
+            for (output, &frequency) in output.iter_mut().zip(frequency.iter()) {
+                for _ in 0..self.oversample {
+                    self.history[pos] = phase;
+                    pos = (pos + 1) % taps;
+                    phase += frequency * scale;
+                    if phase > 1.0 {
+                        phase -= 1.0;
+                    }
+                }
+                let mut acc = 0.0f32;
+                for (k, &tap) in self.kernel.iter().enumerate() {
+                    let idx = (pos + taps - 1 - k) % taps;
+                    acc += tap * self.history[idx];
+                }
+                *output = acc;
+            }
+            self.history_pos = pos;
+        }
+        self.phase = phase;
+    }
+}
+
+// =================================================================================================
+
+/// A phase-modulation ("operator") oscillator, as found in classic FM synth
+/// chips: unlike [`Oscillator`] run through an [`ApplyFunction`], the
+/// modulator is added to the running phase *before* the sine is taken, so
+/// modulation index scales with the modulator's amplitude rather than being
+/// capped by it. Chaining these -- a modulator's output into a carrier's
+/// `modulation` input, summed in parallel with [`Mix`] for multiple
+/// modulators -- builds the classic 2- and 4-operator FM algorithms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseModOscillator {
+    /// (frequency, modulation)
+    pub inputs: [SignalRef; 2],
+    /// Self-feedback amount. The phase is pushed by this much times the
+    /// average of the last two output samples, which damps the runaway
+    /// self-oscillation that feeding an operator straight back into itself
+    /// would otherwise cause.
+    pub feedback: f64,
+}
+
+impl Node for PhaseModOscillator {
+    fn inputs(&self) -> &[SignalRef] {
+        &self.inputs[..]
+    }
+    fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(PhaseModOscillatorF {
+            scale: 1.0 / 48000.0,
+            feedback: self.feedback as f32,
+            phase: 0.0,
+            prev: [0.0, 0.0],
+        }))
+    }
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::PhaseMod {
+            frequency: inputs[0],
+            modulation: inputs[1],
+            feedback: push_const(constants, self.feedback as f32),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::PhaseModOscillator
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_f64(out, self.feedback);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::PhaseModOscillator(PhaseModOscillator {
+            inputs: self.inputs,
+            feedback: self.feedback,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct PhaseModOscillatorF {
+    scale: f32,
+    feedback: f32,
+    phase: f32,
+    /// Last two output samples, averaged for the feedback term.
+    prev: [f32; 2],
+}
+
+impl Function for PhaseModOscillatorF {
+    fn render(&mut self, output: &mut [f32], inputs: &[&[f32]], _state: &mut State) {
+        let frequency = &inputs[0][0..output.len()];
+        let modulation = &inputs[1][0..output.len()];
+        let scale = self.scale;
+        let feedback = self.feedback;
+        let mut phase = self.phase;
+        let mut prev = self.prev;
+        for ((output, &frequency), &modulation) in output
+            .iter_mut()
+            .zip(frequency.iter())
+            .zip(modulation.iter())
+        {
+            let fb = feedback * (prev[0] + prev[1]) * 0.5;
+            let y = ((phase + modulation + fb) * (2.0 * f32::consts::PI)).sin();
+            *output = y;
+            prev = [prev[1], y];
             phase += frequency * scale;
             if phase > 1.0 {
                 phase -= 1.0;
             }
         }
         self.phase = phase;
+        self.prev = prev;
     }
 }
 
 // =================================================================================================
 
 /// Types of waveforms.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PointFunction {
     Sine,
     Sawtooth,
@@ -78,7 +267,7 @@ pub enum PointFunction {
 }
 
 /// Apply a function to the waveform.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplyFunction {
     pub input: SignalRef,
     pub function: PointFunction,
@@ -91,6 +280,24 @@ impl Node for ApplyFunction {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(ApplyFunctionF(self.function)))
     }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::ApplyFunction {
+            input: inputs[0],
+            function: self.function,
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::ApplyFunction
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.function as u8);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::ApplyFunction(ApplyFunction {
+            input: self.input,
+            function: self.function,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -128,7 +335,7 @@ impl Function for ApplyFunctionF {
 // =================================================================================================
 
 /// Generate uniform noise at the full sample rate.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Noise;
 
 impl Node for Noise {
@@ -138,6 +345,16 @@ impl Node for Noise {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(NoiseF))
     }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Noise
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Noise
+    }
+    fn write_preset_params(&self, _out: &mut Vec<u8>) {}
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Noise(Noise)
+    }
 }
 
 #[derive(Debug)]
@@ -154,8 +371,146 @@ impl Function for NoiseF {
 
 // =================================================================================================
 
-/// Multiply two inputs.
+/// The color (frequency spectrum) or amplitude distribution of a noise
+/// generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseColor {
+    /// Flat spectrum, uniform amplitude distribution.
+    White,
+    /// -3 dB/octave spectrum.
+    Pink,
+    /// -6 dB/octave spectrum.
+    Brown,
+    /// Flat spectrum, Gaussian (normal) amplitude distribution.
+    Gaussian,
+}
+
+/// Number of independent generators in [`NoiseColor::Pink`]'s
+/// Voss-McCartney summation; generator `k` only gets re-rolled once every
+/// `2^(k+1)` samples (the last one, which also takes every higher counter
+/// bit, once every `2^k`), so this many octaves covers down to about `sample_rate /
+/// 2^(PINK_GENERATORS - 1)`.
+pub(crate) const PINK_GENERATORS: usize = 16;
+
+/// Feedback coefficient for [`NoiseColor::Brown`]'s leaky integrator.
+const BROWN_COEFF: f32 = 0.99;
+
+/// Rescales [`NoiseColor::Brown`]'s output back down to the variance of its
+/// white input: for
+/// `y[n] = a*y[n-1] + x[n]`, `y`'s variance is `x`'s variance divided by `1 -
+/// a^2`, so multiplying by `sqrt(1 - a^2)` undoes the gain the integrator
+/// otherwise builds up.
+const BROWN_SCALE: f32 = 0.14107;
+
+/// Render one sample of `color`, given and updating `rand`'s state plus
+/// whichever of `pink`/`pink_counter`/`brown`/`gaussian_cache` that color
+/// uses.
+pub(crate) fn render_sample(
+    color: NoiseColor,
+    rand: &mut Rand,
+    pink: &mut [f32; PINK_GENERATORS],
+    pink_counter: &mut u32,
+    brown: &mut f32,
+    gaussian_cache: &mut Option<f32>,
+) -> f32 {
+    match color {
+        NoiseColor::White => rand.next_float() * 2.0 - 1.0,
+        NoiseColor::Pink => {
+            // Voss-McCartney: only one generator is re-rolled per sample --
+            // the one whose bit just flipped from 0 to 1 in the incrementing
+            // counter, which is always the counter's trailing zero count.
+            *pink_counter += 1;
+            let index = (pink_counter.trailing_zeros() as usize).min(PINK_GENERATORS - 1);
+            pink[index] = rand.next_float() * 2.0 - 1.0;
+            pink.iter().sum::<f32>() / PINK_GENERATORS as f32
+        }
+        NoiseColor::Brown => {
+            let white = rand.next_float() * 2.0 - 1.0;
+            *brown = BROWN_COEFF * *brown + white;
+            *brown * BROWN_SCALE
+        }
+        NoiseColor::Gaussian => {
+            if let Some(z) = gaussian_cache.take() {
+                z
+            } else {
+                // Box-Muller: u1 is shifted off of 0 so its log never blows up.
+                let u1 = 1.0 - rand.next_float();
+                let u2 = rand.next_float();
+                let r = (-2.0 * u1.ln()).sqrt();
+                let theta = 2.0 * f32::consts::PI * u2;
+                *gaussian_cache = Some(r * theta.sin());
+                r * theta.cos()
+            }
+        }
+    }
+}
+
+/// Generate colored noise at the full sample rate, with its own random
+/// number generator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorNoise {
+    pub color: NoiseColor,
+}
+
+impl Node for ColorNoise {
+    fn inputs(&self) -> &[SignalRef] {
+        &[]
+    }
+    fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(ColorNoiseF {
+            rand: Rand::with_default_seed(),
+            color: self.color,
+            pink: [0.0; PINK_GENERATORS],
+            pink_counter: 0,
+            brown: 0.0,
+            gaussian_cache: None,
+        }))
+    }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::ColorNoise { color: self.color }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::ColorNoise
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u8(out, self.color as u8);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::ColorNoise(ColorNoise { color: self.color })
+    }
+}
+
 #[derive(Debug)]
+struct ColorNoiseF {
+    rand: Rand,
+    color: NoiseColor,
+    pink: [f32; PINK_GENERATORS],
+    pink_counter: u32,
+    // Brown noise leaky integrator state.
+    brown: f32,
+    // Cached second Box-Muller sample for Gaussian noise.
+    gaussian_cache: Option<f32>,
+}
+
+impl Function for ColorNoiseF {
+    fn render(&mut self, output: &mut [f32], _inputs: &[&[f32]], _state: &mut State) {
+        for output in output.iter_mut() {
+            *output = render_sample(
+                self.color,
+                &mut self.rand,
+                &mut self.pink,
+                &mut self.pink_counter,
+                &mut self.brown,
+                &mut self.gaussian_cache,
+            );
+        }
+    }
+}
+
+// =================================================================================================
+
+/// Multiply two inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Multiply {
     pub inputs: [SignalRef; 2],
 }
@@ -167,6 +522,21 @@ impl Node for Multiply {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(MultiplyF))
     }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Multiply {
+            x: inputs[0],
+            y: inputs[1],
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Multiply
+    }
+    fn write_preset_params(&self, _out: &mut Vec<u8>) {}
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Multiply(Multiply {
+            inputs: self.inputs,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -185,7 +555,7 @@ impl Function for MultiplyF {
 // =================================================================================================
 
 /// Multiply an input by a constant gain and add it to the base signal.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mix {
     /// (base, input) => base + gain * input
     pub inputs: [SignalRef; 2],
@@ -201,6 +571,25 @@ impl Node for Mix {
             gain: self.gain as f32,
         }))
     }
+    fn encode(&self, inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::Mix {
+            base: inputs[0],
+            input: inputs[1],
+            gain: push_const(constants, self.gain as f32),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Mix
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_f64(out, self.gain);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Mix(Mix {
+            inputs: self.inputs,
+            gain: self.gain,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -222,7 +611,7 @@ impl Function for MixF {
 // =================================================================================================
 
 /// Convert numbers from -1..+1 to 20..20000, exponentially.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Frequency {
     pub input: SignalRef,
 }
@@ -234,6 +623,16 @@ impl Node for Frequency {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(FrequencyF))
     }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Frequency { input: inputs[0] }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Frequency
+    }
+    fn write_preset_params(&self, _out: &mut Vec<u8>) {}
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Frequency(Frequency { input: self.input })
+    }
 }
 
 #[derive(Debug)]
@@ -250,7 +649,7 @@ impl Function for FrequencyF {
 // =================================================================================================
 
 /// Create a zero buffer.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Zero;
 
 impl Node for Zero {
@@ -260,6 +659,16 @@ impl Node for Zero {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(ZeroF))
     }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Zero
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Zero
+    }
+    fn write_preset_params(&self, _out: &mut Vec<u8>) {}
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Zero(Zero)
+    }
 }
 
 #[derive(Debug)]
@@ -276,7 +685,7 @@ impl Function for ZeroF {
 // =================================================================================================
 
 /// Scale input by an integer.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScaleInt {
     pub input: SignalRef,
     pub scale: i32,
@@ -291,6 +700,24 @@ impl Node for ScaleInt {
             scale: self.scale as f32,
         }))
     }
+    fn encode(&self, inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::ScaleInt {
+            input: inputs[0],
+            scale: self.scale,
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::ScaleInt
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_i32(out, self.scale);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::ScaleInt(ScaleInt {
+            input: self.input,
+            scale: self.scale,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -315,7 +742,7 @@ op!(Note, 1, 0, 1);
 // =================================================================================================
 
 /// Generate input note frequency.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
     /// Offset to apply to input note, in semitones.
     pub offset: i32,
@@ -330,6 +757,22 @@ impl Node for Note {
             offset: self.offset,
         }))
     }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Note {
+            offset: self.offset,
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Note
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_i32(out, self.offset);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Note(Note {
+            offset: self.offset,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -350,7 +793,7 @@ impl Function for NoteF {
 // =================================================================================================
 
 /// Generate a constant value.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Constant {
     pub value: f32,
 }
@@ -362,6 +805,20 @@ impl Node for Constant {
     fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
         Ok(Box::new(ConstantF { value: self.value }))
     }
+    fn encode(&self, _inputs: &[Slot], constants: &mut Vec<f32>) -> Instr {
+        Instr::Constant {
+            value: push_const(constants, self.value),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::Constant
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_f32(out, self.value);
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::Constant(Constant { value: self.value })
+    }
 }
 
 #[derive(Debug)]
@@ -376,3 +833,60 @@ impl Function for ConstantF {
         }
     }
 }
+
+// =================================================================================================
+
+/// Play back a pre-loaded sample once, top to bottom, at the patch's own
+/// sample rate -- the frames are assumed to already be mono and already at
+/// the render sample rate, since there's no resampling yet. Output is
+/// silence once the sample runs out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplePlayer {
+    pub frames: Box<[f32]>,
+}
+
+impl Node for SamplePlayer {
+    fn inputs(&self) -> &[SignalRef] {
+        &[]
+    }
+    fn instantiate(&self, _parameters: &Parameters) -> NodeResult {
+        Ok(Box::new(SamplePlayerF {
+            frames: self.frames.clone(),
+            pos: 0,
+        }))
+    }
+    fn encode(&self, _inputs: &[Slot], _constants: &mut Vec<f32>) -> Instr {
+        Instr::Sample {
+            frames: self.frames.clone(),
+        }
+    }
+    fn preset_tag(&self) -> NodeTag {
+        NodeTag::SamplePlayer
+    }
+    fn write_preset_params(&self, out: &mut Vec<u8>) {
+        preset::write_u32(out, self.frames.len() as u32);
+        for &frame in self.frames.iter() {
+            preset::write_f32(out, frame);
+        }
+    }
+    fn to_json_data(&self) -> json::NodeData {
+        json::NodeData::SamplePlayer(SamplePlayer {
+            frames: self.frames.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct SamplePlayerF {
+    frames: Box<[f32]>,
+    pos: usize,
+}
+
+impl Function for SamplePlayerF {
+    fn render(&mut self, output: &mut [f32], _inputs: &[&[f32]], _state: &mut State) {
+        for o in output.iter_mut() {
+            *o = self.frames.get(self.pos).copied().unwrap_or(0.0);
+            self.pos += 1;
+        }
+    }
+}
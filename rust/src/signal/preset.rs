@@ -0,0 +1,562 @@
+//! Compact binary preset format for a [`Graph`]: every node's type tag,
+//! inputs, and remaining parameters, flattened to a byte stream that
+//! round-trips back into a fresh graph, plus a URL-safe base64 wrapper so a
+//! whole patch can be shared as a short string (the way classic sfxr-style
+//! tools share presets). Unlike [`bytecode`](super::bytecode), which
+//! serializes a *compiled* [`Instr`](super::bytecode::Instr) program via
+//! `serde`, this serializes the uncompiled node graph itself, by hand, so
+//! the exact byte layout -- and therefore what stays backward compatible as
+//! node types are added -- is explicit rather than whatever a derive
+//! happens to produce.
+
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::string::String;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::envelope::{Envelope, RateEnvelope, Segment};
+use super::filter::{ChamberlinMode, Filter, HighPass, Mode, StateVariable};
+use super::fm::{Algorithm, FmAlgorithm, FmOperator, OPERATOR_COUNT};
+use super::graph::{Graph, Node, SignalRef};
+use super::ops::{
+    ApplyFunction, ColorNoise, Constant, Frequency, Mix, Multiply, Noise, NoiseColor, Note,
+    Oscillator, PhaseModOscillator, PointFunction, SamplePlayer, ScaleInt, Zero,
+};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+/// Format version written as the first two bytes of every encoded preset.
+/// Bump this if a change to [`NodeTag`] or a node's parameter layout would
+/// make old streams decode incorrectly.
+const VERSION: u16 = 1;
+
+/// Stable numeric tag identifying a [`Node`] type in a preset stream -- a
+/// registry mirroring the node types defined in [`ops`](super::ops),
+/// `filter`, `envelope` and `fm`. New node types are appended, never
+/// renumbered, so old presets keep decoding under a newer [`VERSION`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum NodeTag {
+    Oscillator = 0,
+    PhaseModOscillator = 1,
+    ApplyFunction = 2,
+    Noise = 3,
+    ColorNoise = 4,
+    Multiply = 5,
+    Mix = 6,
+    Frequency = 7,
+    Zero = 8,
+    ScaleInt = 9,
+    Note = 10,
+    Constant = 11,
+    StateVariable = 12,
+    HighPass = 13,
+    Filter = 14,
+    Envelope = 15,
+    SamplePlayer = 16,
+    FmAlgorithm = 17,
+    RateEnvelope = 18,
+}
+
+impl TryFrom<u16> for NodeTag {
+    type Error = Error;
+    fn try_from(tag: u16) -> Result<Self, Error> {
+        use NodeTag::*;
+        Ok(match tag {
+            0 => Oscillator,
+            1 => PhaseModOscillator,
+            2 => ApplyFunction,
+            3 => Noise,
+            4 => ColorNoise,
+            5 => Multiply,
+            6 => Mix,
+            7 => Frequency,
+            8 => Zero,
+            9 => ScaleInt,
+            10 => Note,
+            11 => Constant,
+            12 => StateVariable,
+            13 => HighPass,
+            14 => Filter,
+            15 => Envelope,
+            16 => SamplePlayer,
+            17 => FmAlgorithm,
+            18 => RateEnvelope,
+            _ => return Err(Error::UnknownTag(tag)),
+        })
+    }
+}
+
+/// Error decoding a preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The stream ended before a field could be fully read.
+    Truncated,
+    /// The version header didn't match this build's [`VERSION`].
+    UnsupportedVersion(u16),
+    /// A node's tag, or one of its enum-valued parameters, didn't match any
+    /// known variant.
+    UnknownTag(u16),
+    /// An input index referred to a node not yet added.
+    BadInput(u32),
+    /// A node's input count didn't match what its [`NodeTag`] expects.
+    BadArity(u16),
+    /// The base64 wrapper contained invalid characters or padding.
+    BadBase64,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self {
+            Error::Truncated => write!(f, "truncated preset data"),
+            Error::UnsupportedVersion(v) => write!(f, "unsupported preset version: {}", v),
+            Error::UnknownTag(t) => write!(f, "unknown preset tag: {}", t),
+            Error::BadInput(i) => write!(f, "preset input index out of range: {}", i),
+            Error::BadArity(t) => write!(f, "wrong number of inputs for preset tag: {}", t),
+            Error::BadBase64 => write!(f, "invalid base64 preset string"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+pub(crate) fn write_u8(out: &mut Vec<u8>, v: u8) {
+    out.push(v);
+}
+
+pub(crate) fn write_u16(out: &mut Vec<u8>, v: u16) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) fn write_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) fn write_i32(out: &mut Vec<u8>, v: i32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) fn write_f32(out: &mut Vec<u8>, v: f32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) fn write_f64(out: &mut Vec<u8>, v: f64) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+/// Write one [`Segment`] as `[tag: u8][operands...]`, `f64` fields
+/// little-endian.
+pub(crate) fn write_segment(out: &mut Vec<u8>, segment: &Segment) {
+    match *segment {
+        Segment::Set { value } => {
+            write_u8(out, 0);
+            write_f64(out, value);
+        }
+        Segment::Linear { time, value } => {
+            write_u8(out, 1);
+            write_f64(out, time);
+            write_f64(out, value);
+        }
+        Segment::Exponential {
+            time_constant,
+            value,
+        } => {
+            write_u8(out, 2);
+            write_f64(out, time_constant);
+            write_f64(out, value);
+        }
+        Segment::Delay { time } => {
+            write_u8(out, 3);
+            write_f64(out, time);
+        }
+        Segment::Gate => write_u8(out, 4),
+        Segment::Stop => write_u8(out, 5),
+        Segment::Loop { count } => {
+            write_u8(out, 6);
+            write_u32(out, count.unwrap_or(u32::max_value()));
+        }
+    }
+}
+
+/// Cursor over preset bytes, used by [`decode`] to walk the stream one
+/// field at a time.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, pos: 0 }
+    }
+
+    /// Number of bytes left to read.
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let end = self.pos.checked_add(n).ok_or(Error::Truncated)?;
+        if end > self.bytes.len() {
+            return Err(Error::Truncated);
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn i32(&mut self) -> Result<i32, Error> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, Error> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(f64::from_le_bytes(bytes))
+    }
+}
+
+fn read_segment(r: &mut Reader) -> Result<Segment, Error> {
+    Ok(match r.u8()? {
+        0 => Segment::Set { value: r.f64()? },
+        1 => Segment::Linear {
+            time: r.f64()?,
+            value: r.f64()?,
+        },
+        2 => Segment::Exponential {
+            time_constant: r.f64()?,
+            value: r.f64()?,
+        },
+        3 => Segment::Delay { time: r.f64()? },
+        4 => Segment::Gate,
+        5 => Segment::Stop,
+        6 => {
+            let n = r.u32()?;
+            Segment::Loop {
+                count: if n == u32::max_value() { None } else { Some(n) },
+            }
+        }
+        tag => return Err(Error::UnknownTag(tag as u16)),
+    })
+}
+
+fn read_point_function(r: &mut Reader) -> Result<PointFunction, Error> {
+    Ok(match r.u8()? {
+        0 => PointFunction::Sine,
+        1 => PointFunction::Sawtooth,
+        2 => PointFunction::Saturate,
+        3 => PointFunction::Rectify,
+        v => return Err(Error::UnknownTag(v as u16)),
+    })
+}
+
+fn read_noise_color(r: &mut Reader) -> Result<NoiseColor, Error> {
+    Ok(match r.u8()? {
+        0 => NoiseColor::White,
+        1 => NoiseColor::Pink,
+        2 => NoiseColor::Brown,
+        3 => NoiseColor::Gaussian,
+        v => return Err(Error::UnknownTag(v as u16)),
+    })
+}
+
+fn read_mode(r: &mut Reader) -> Result<Mode, Error> {
+    Ok(match r.u8()? {
+        0 => Mode::LowPass2,
+        1 => Mode::HighPass2,
+        2 => Mode::BandPass2,
+        3 => Mode::LowPass4,
+        4 => Mode::Notch,
+        5 => Mode::Peak,
+        6 => Mode::Allpass,
+        7 => Mode::LowShelf,
+        8 => Mode::HighShelf,
+        v => return Err(Error::UnknownTag(v as u16)),
+    })
+}
+
+fn read_chamberlin_mode(r: &mut Reader) -> Result<ChamberlinMode, Error> {
+    Ok(match r.u8()? {
+        0 => ChamberlinMode::LowPass,
+        1 => ChamberlinMode::HighPass,
+        2 => ChamberlinMode::BandPass,
+        3 => ChamberlinMode::Notch,
+        v => return Err(Error::UnknownTag(v as u16)),
+    })
+}
+
+fn read_algorithm(r: &mut Reader) -> Result<Algorithm, Error> {
+    let v = r.u8()?;
+    Algorithm::try_from(v).map_err(|_| Error::UnknownTag(v as u16))
+}
+
+fn read_fm_operator(r: &mut Reader) -> Result<FmOperator, Error> {
+    Ok(FmOperator {
+        ratio: r.f64()?,
+        level: r.f64()?,
+    })
+}
+
+/// Encode `graph` and its `output` node to a preset byte stream: a
+/// [`VERSION`] header, the output [`SignalRef`] index, the node count, then
+/// each node as `[tag: u16-LE][input_count: u8][inputs: u32-LE
+/// each][params...]` in graph order -- already a topological sort, since
+/// [`Graph::add`] requires every input to reference an earlier node.
+pub fn encode(graph: &Graph, output: SignalRef) -> Vec<u8> {
+    let nodes = graph.nodes();
+    let mut out = Vec::new();
+    write_u16(&mut out, VERSION);
+    write_u32(&mut out, output.0);
+    write_u32(&mut out, nodes.len() as u32);
+    for node in nodes.iter() {
+        write_u16(&mut out, node.preset_tag() as u16);
+        let inputs = node.inputs();
+        write_u8(&mut out, inputs.len() as u8);
+        for &SignalRef(idx) in inputs.iter() {
+            write_u32(&mut out, idx);
+        }
+        node.write_preset_params(&mut out);
+    }
+    out
+}
+
+fn check_arity(tag: NodeTag, inputs: &[SignalRef], expected: usize) -> Result<(), Error> {
+    if inputs.len() != expected {
+        return Err(Error::BadArity(tag as u16));
+    }
+    Ok(())
+}
+
+fn decode_node(
+    tag: NodeTag,
+    inputs: &[SignalRef],
+    r: &mut Reader,
+) -> Result<Box<dyn Node>, Error> {
+    Ok(match tag {
+        NodeTag::Oscillator => {
+            check_arity(tag, inputs, 1)?;
+            Box::new(Oscillator { inputs: [inputs[0]] })
+        }
+        NodeTag::PhaseModOscillator => {
+            check_arity(tag, inputs, 2)?;
+            Box::new(PhaseModOscillator {
+                inputs: [inputs[0], inputs[1]],
+                feedback: r.f64()?,
+            })
+        }
+        NodeTag::ApplyFunction => {
+            check_arity(tag, inputs, 1)?;
+            Box::new(ApplyFunction {
+                input: inputs[0],
+                function: read_point_function(r)?,
+            })
+        }
+        NodeTag::Noise => {
+            check_arity(tag, inputs, 0)?;
+            Box::new(Noise)
+        }
+        NodeTag::ColorNoise => {
+            check_arity(tag, inputs, 0)?;
+            Box::new(ColorNoise {
+                color: read_noise_color(r)?,
+            })
+        }
+        NodeTag::Multiply => {
+            check_arity(tag, inputs, 2)?;
+            Box::new(Multiply {
+                inputs: [inputs[0], inputs[1]],
+            })
+        }
+        NodeTag::Mix => {
+            check_arity(tag, inputs, 2)?;
+            Box::new(Mix {
+                inputs: [inputs[0], inputs[1]],
+                gain: r.f64()?,
+            })
+        }
+        NodeTag::Frequency => {
+            check_arity(tag, inputs, 1)?;
+            Box::new(Frequency { input: inputs[0] })
+        }
+        NodeTag::Zero => {
+            check_arity(tag, inputs, 0)?;
+            Box::new(Zero)
+        }
+        NodeTag::ScaleInt => {
+            check_arity(tag, inputs, 1)?;
+            Box::new(ScaleInt {
+                input: inputs[0],
+                scale: r.i32()?,
+            })
+        }
+        NodeTag::Note => {
+            check_arity(tag, inputs, 0)?;
+            Box::new(Note { offset: r.i32()? })
+        }
+        NodeTag::Constant => {
+            check_arity(tag, inputs, 0)?;
+            Box::new(Constant { value: r.f32()? })
+        }
+        NodeTag::StateVariable => {
+            check_arity(tag, inputs, 2)?;
+            let mode = read_mode(r)?;
+            Box::new(StateVariable {
+                inputs: [inputs[0], inputs[1]],
+                mode,
+                q: r.f64()?,
+                gain: r.f64()?,
+            })
+        }
+        NodeTag::HighPass => {
+            check_arity(tag, inputs, 1)?;
+            Box::new(HighPass {
+                input: inputs[0],
+                frequency: r.f64()?,
+            })
+        }
+        NodeTag::Filter => {
+            check_arity(tag, inputs, 3)?;
+            let mode = read_chamberlin_mode(r)?;
+            Box::new(Filter {
+                inputs: [inputs[0], inputs[1], inputs[2]],
+                mode,
+            })
+        }
+        NodeTag::Envelope => {
+            check_arity(tag, inputs, 0)?;
+            let len = r.u32()?;
+            // Every segment takes at least one byte, so `remaining()` is
+            // already an upper bound on how many could possibly be read --
+            // reserving up to it can never over-allocate relative to the
+            // stream actually in memory, unlike reserving `len` itself,
+            // which an attacker/corrupted stream can set arbitrarily high.
+            let mut segments = Vec::with_capacity((len as usize).min(r.remaining()));
+            for _ in 0..len {
+                segments.push(read_segment(r)?);
+            }
+            Box::new(Envelope {
+                segments: segments.into_boxed_slice(),
+            })
+        }
+        NodeTag::SamplePlayer => {
+            check_arity(tag, inputs, 0)?;
+            let len = r.u32()?;
+            // Each frame is a fixed 4-byte f32, so `remaining() / 4` is an
+            // upper bound on how many can possibly be read -- reserving up
+            // to it can never over-allocate relative to the stream already
+            // in memory, unlike reserving `len` itself, which an
+            // attacker/corrupted stream can set arbitrarily high.
+            let mut frames = Vec::with_capacity((len as usize).min(r.remaining() / 4));
+            for _ in 0..len {
+                frames.push(r.f32()?);
+            }
+            Box::new(SamplePlayer {
+                frames: frames.into_boxed_slice(),
+            })
+        }
+        NodeTag::FmAlgorithm => {
+            check_arity(tag, inputs, 1)?;
+            let algorithm = read_algorithm(r)?;
+            let mut operators = [FmOperator {
+                ratio: 0.0,
+                level: 0.0,
+            }; OPERATOR_COUNT];
+            for op in operators.iter_mut() {
+                *op = read_fm_operator(r)?;
+            }
+            Box::new(FmAlgorithm {
+                inputs: [inputs[0]],
+                algorithm,
+                operators,
+                feedback: r.f64()?,
+            })
+        }
+        NodeTag::RateEnvelope => {
+            check_arity(tag, inputs, 0)?;
+            let attack_rate = r.u8()?;
+            let decay1_rate = r.u8()?;
+            let sustain_level = r.f64()?;
+            let decay2_rate = r.u8()?;
+            let release_rate = r.u8()?;
+            Box::new(RateEnvelope {
+                attack_rate,
+                decay1_rate,
+                sustain_level,
+                decay2_rate,
+                release_rate,
+            })
+        }
+    })
+}
+
+/// Decode a preset byte stream produced by [`encode`], rebuilding a fresh
+/// [`Graph`] and returning it with the output [`SignalRef`]. Input indices
+/// are validated to refer only to already-added nodes, same as
+/// [`Graph::add`] enforces directly.
+pub fn decode(bytes: &[u8]) -> Result<(Graph, SignalRef), Error> {
+    let mut r = Reader::new(bytes);
+    let version = r.u16()?;
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    let output = SignalRef(r.u32()?);
+    let node_count = r.u32()?;
+    let mut graph = Graph::new();
+    for _ in 0..node_count {
+        let tag = NodeTag::try_from(r.u16()?)?;
+        let input_count = r.u8()? as usize;
+        let mut inputs = Vec::with_capacity(input_count);
+        for _ in 0..input_count {
+            let idx = r.u32()?;
+            if idx as usize >= graph.nodes().len() {
+                return Err(Error::BadInput(idx));
+            }
+            inputs.push(SignalRef(idx));
+        }
+        let node = decode_node(tag, &inputs, &mut r)?;
+        // Input indices and arity were already validated above/by
+        // `check_arity`, so this can't fail.
+        graph
+            .add(node)
+            .expect("preset decode already validated this node's inputs");
+    }
+    Ok((graph, output))
+}
+
+/// Encode `graph`/`output` as a preset byte stream (see [`encode`]) and
+/// wrap it in URL-safe, unpadded base64 for sharing as a short string.
+pub fn encode_base64(graph: &Graph, output: SignalRef) -> String {
+    base64::encode_config(encode(graph, output), base64::URL_SAFE_NO_PAD)
+}
+
+/// Reverse of [`encode_base64`]: decode the base64 wrapper, then [`decode`]
+/// the resulting byte stream.
+pub fn decode_base64(text: &str) -> Result<(Graph, SignalRef), Error> {
+    let bytes =
+        base64::decode_config(text, base64::URL_SAFE_NO_PAD).map_err(|_| Error::BadBase64)?;
+    decode(&bytes)
+}
@@ -1,15 +1,28 @@
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::debugger::{ChunkSummary, DebugCommand, Debugger};
 use super::graph::{Graph, SignalRef};
 use std::cmp::min;
 use std::error;
 use std::fmt::{Debug, Display, Formatter, Result as FResult};
 
 /// Parameters for instantiating a synthesizer program.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Parameters {
     /// Audio sample rate, samples per second.
     pub sample_rate: f64,
     /// Size of audio buffers.
     pub buffer_size: usize,
+    /// Oversampling factor for nodes that opt into it (e.g. [`Oscillator`]):
+    /// render internally at `sample_rate * oversample` and decimate back
+    /// down with a windowed-sinc filter. 1 (or 0) disables oversampling.
+    ///
+    /// [`Oscillator`]: super::ops::Oscillator
+    pub oversample: usize,
 }
 
 /// Input to a synthesizer program.
@@ -58,14 +71,54 @@ pub trait Function: Debug {
 pub enum Error {
     ContainsLoop,
     BadBuffer,
+    /// `node`'s `input`-th input (counting from 0) refers to `index`, but the
+    /// graph only has `size` nodes added so far.
+    InputOutOfRange {
+        node: usize,
+        input: usize,
+        index: u32,
+        size: usize,
+    },
+    /// `node` has more inputs than [`Node`] (the program-internal one, not
+    /// [`graph::Node`](super::graph::Node)) can hold.
+    WrongInputCount {
+        node: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// `node` does not contribute, directly or transitively, to the
+    /// requested output.
+    UnreachableNode { node: usize },
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FResult {
-        f.write_str(match self {
-            Error::ContainsLoop => "audio graph contains cycle",
-            Error::BadBuffer => "invalid buffer size",
-        })
+        match self {
+            Error::ContainsLoop => f.write_str("audio graph contains cycle"),
+            Error::BadBuffer => f.write_str("invalid buffer size"),
+            Error::InputOutOfRange {
+                node,
+                input,
+                index,
+                size,
+            } => write!(
+                f,
+                "node {}: input {} refers to node {}, but only {} nodes have been added",
+                node, input, index, size
+            ),
+            Error::WrongInputCount {
+                node,
+                expected,
+                found,
+            } => write!(
+                f,
+                "node {}: has {} inputs, but at most {} are supported",
+                node, found, expected
+            ),
+            Error::UnreachableNode { node } => {
+                write!(f, "node {} does not contribute to the output", node)
+            }
+        }
     }
 }
 
@@ -88,10 +141,17 @@ pub struct Program {
     nodes: Box<[Node]>,
     // If true, the program is done and has no more output.
     done: bool,
+    // Attached by `attach_debugger`; consulted only by `render_debug` (and,
+    // for tracing, by every render entry point) so the hot path pays just
+    // one `Option::is_none` check per node when nothing is attached.
+    debugger: Option<Debugger>,
 }
 
 impl Program {
-    /// Create a new program from an audio processing graph.
+    /// Compile an audio processing graph into a program: a topologically
+    /// sorted, flat buffer of nodes, each referencing its inputs by slot
+    /// index into a shared scratch buffer. Prefer [`Graph::compile`], which
+    /// calls this.
     pub fn new(
         graph: &Graph,
         output: SignalRef,
@@ -156,6 +216,11 @@ impl Program {
                 }
             }
         }
+        for (node, state) in states.iter().enumerate() {
+            if let Unvisited = state {
+                return Err(Box::new(Error::UnreachableNode { node }));
+            }
+        }
         let buffer_size = parameters.buffer_size;
         if buffer_size == 0 {
             return Err(Box::new(Error::BadBuffer));
@@ -171,48 +236,160 @@ impl Program {
             buffer,
             nodes,
             done: false,
+            debugger: None,
         })
     }
 
-    /// Render the next output buffer. This will return a series of full
-    /// buffers, then optionally a short buffer, and then None.
-    pub fn render(&mut self, input: &Input) -> Option<&[f32]> {
-        if self.done {
-            return None;
-        }
-        // TODO: Change this function so it doesn't allocate memory.
+    /// Attach a [`Debugger`], consulted from now on by every render entry
+    /// point for tracing, and by [`Self::render_debug`] for breakpoints.
+    pub fn attach_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Detach and return this program's [`Debugger`], if any.
+    pub fn detach_debugger(&mut self) -> Option<Debugger> {
+        self.debugger.take()
+    }
+
+    /// The attached [`Debugger`], if any.
+    pub fn debugger(&self) -> Option<&Debugger> {
+        self.debugger.as_ref()
+    }
+
+    /// Render one buffer's worth of every node into `self.buffer`, reading
+    /// each node's inputs directly out of the chunks rendered earlier in the
+    /// same call. This is the allocation-free core shared by [`Self::render`],
+    /// [`Self::render_into`] and [`Self::render_stream`]: `self.buffer` and
+    /// every node's state are allocated once, in [`Self::new`], and nothing
+    /// here allocates.
+    ///
+    /// `on_break` is only ever consulted when [`Self::debugger`] is attached
+    /// and stops at the current node; pass `None` from the real-time render
+    /// entry points, which never want to block on a callback.
+    fn render_core(
+        &mut self,
+        input: &Input,
+        mut on_break: Option<
+            &mut dyn FnMut(&Debugger, usize, &dyn Function, ChunkSummary, &State) -> DebugCommand,
+        >,
+    ) -> Option<usize> {
         let buffer_size = self.buffer_size;
-        let buffer = &mut self.buffer[..];
-        let nodes = &mut self.nodes[..];
-        let mut outputs = Vec::new();
-        outputs.resize(nodes.len(), Default::default());
+        let Program {
+            buffer,
+            nodes,
+            debugger,
+            ..
+        } = self;
         let mut state = State {
             note: input.note,
             gate: input.gate,
             end: None,
         };
-        for (n, (node, output)) in nodes
-            .iter_mut()
-            .zip(buffer.chunks_mut(buffer_size))
-            .enumerate()
-        {
+        for n in 0..nodes.len() {
+            let node = &mut nodes[n];
             let input_count = node.input_count;
+            let (done, output) = buffer.split_at_mut(n * buffer_size);
+            let output = &mut output[..buffer_size];
             let mut inputs: [&[f32]; 4] = [Default::default(); 4];
             for (i, &index) in node.inputs[0..input_count].iter().enumerate() {
                 debug_assert!(index < n);
-                inputs[i] = outputs[index];
+                inputs[i] = &done[index * buffer_size..(index + 1) * buffer_size];
             }
             node.function
                 .render(output, &inputs[0..input_count], &mut state);
-            outputs[n] = output;
+            if let Some(debugger) = debugger {
+                #[cfg(not(feature = "no_std"))]
+                if debugger.should_trace(n) {
+                    eprintln!("{}: {:?} {:?}", n, node.function, ChunkSummary::of(output));
+                }
+                if debugger.should_stop(n) {
+                    if let Some(callback) = on_break.as_deref_mut() {
+                        loop {
+                            let summary = ChunkSummary::of(output);
+                            let command =
+                                callback(debugger, n, node.function.as_ref(), summary, &state);
+                            debugger.record(command);
+                            if command != DebugCommand::Print {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
         }
-        let output = buffer.chunks_exact(self.buffer_size).next_back().unwrap();
-        Some(match state.end {
+        state.end
+    }
+
+    /// The chunk of `self.buffer` holding the last node's output, i.e. the
+    /// program's result for the buffer just rendered.
+    fn last_chunk(&self) -> &[f32] {
+        let buffer_size = self.buffer_size;
+        let last = self.nodes.len() - 1;
+        &self.buffer[last * buffer_size..(last + 1) * buffer_size]
+    }
+
+    /// Render the next output buffer. This will return a series of full
+    /// buffers, then optionally a short buffer, and then None.
+    pub fn render(&mut self, input: &Input) -> Option<&[f32]> {
+        if self.done {
+            return None;
+        }
+        let end = self.render_core(input, None);
+        Some(match end {
+            Some(len) => {
+                self.done = true;
+                &self.last_chunk()[..len]
+            }
+            None => self.last_chunk(),
+        })
+    }
+
+    /// Like [`Self::render`], but also consults the attached [`Debugger`]
+    /// (if any) for breakpoints: traced or breakpointed nodes get their
+    /// `{:?}` and a [`ChunkSummary`] printed as they run, and `on_break` is
+    /// invoked at each breakpoint until it returns [`DebugCommand::Step`] or
+    /// [`DebugCommand::Continue`].
+    pub fn render_debug(
+        &mut self,
+        input: &Input,
+        on_break: &mut dyn FnMut(&Debugger, usize, &dyn Function, ChunkSummary, &State) -> DebugCommand,
+    ) -> Option<&[f32]> {
+        if self.done {
+            return None;
+        }
+        let end = self.render_core(input, Some(on_break));
+        Some(match end {
             Some(len) => {
                 self.done = true;
-                &output[..len]
+                &self.last_chunk()[..len]
             }
-            None => output,
+            None => self.last_chunk(),
         })
     }
+
+    /// Render into a caller-supplied buffer, which must be exactly
+    /// [`Parameters::buffer_size`] samples long. Returns the number of valid
+    /// samples written: `output.len()` for a full buffer, fewer once the
+    /// program finishes, and 0 once it is done. Does not allocate.
+    pub fn render_into(&mut self, output: &mut [f32], input: &Input) -> usize {
+        if self.done {
+            return 0;
+        }
+        let end = self.render_core(input, None);
+        let n = end.unwrap_or(self.buffer_size);
+        output[..n].copy_from_slice(&self.last_chunk()[..n]);
+        if end.is_some() {
+            self.done = true;
+        }
+        n
+    }
+
+    /// Render full buffers, passing each to `sink`, until the program is
+    /// done. The final call to `sink` may be a short buffer. Does not
+    /// allocate.
+    pub fn render_stream(&mut self, input: &Input, mut sink: impl FnMut(&[f32])) {
+        while let Some(output) = self.render(input) {
+            sink(output);
+        }
+    }
 }
@@ -0,0 +1,177 @@
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(feature = "no_std")]
+use core as std;
+use super::graph::{Graph, SignalRef};
+use super::program::{Input, Parameters, Program};
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+/// Error constructing a [`VoiceBank`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// `max_voices` was 0, so there would be no voice to assign a note to.
+    NoVoices,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self {
+            Error::NoVoices => f.write_str("a voice bank needs at least one voice"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// How [`VoiceBank::render`] keeps the summed voices from clipping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    /// Clamp to `[-1, 1]`, same as a single unmixed voice would be.
+    Hard,
+    /// Compress toward `[-1, 1]` with `tanh`, trading the harsh clipping
+    /// harmonics of [`Limit::Hard`] for a softer knee when many voices
+    /// overlap.
+    Soft,
+}
+
+/// One voice's persistent state: which MIDI note it's currently sounding
+/// (if any), the program rendering it, and when it was last rendered, for
+/// least-recently-used stealing.
+struct Voice {
+    program: Program,
+    note: Option<f32>,
+    age: u64,
+}
+
+/// A bank of `max_voices` independent instances of the same compiled graph,
+/// for polyphonic playback. [`Program`] itself only renders one monophonic
+/// voice; trait objects aren't `Clone`, so rather than literally cloning a
+/// compiled [`Program`], this keeps the source [`Graph`] around and
+/// compiles it again, once per voice slot, whenever a new note needs one --
+/// cheap, since compiling just walks the already-validated graph.
+pub struct VoiceBank {
+    graph: Graph,
+    output: SignalRef,
+    parameters: Parameters,
+    voices: Vec<Voice>,
+    gain: f32,
+    limit: Limit,
+    clock: u64,
+    mix: Box<[f32]>,
+    scratch: Box<[f32]>,
+}
+
+impl VoiceBank {
+    /// Compile `max_voices` independent instances of `graph`/`output`.
+    pub fn new(
+        graph: Graph,
+        output: SignalRef,
+        parameters: Parameters,
+        max_voices: usize,
+    ) -> Result<Self, Box<dyn error::Error>> {
+        if max_voices == 0 {
+            return Err(Box::new(Error::NoVoices));
+        }
+        let buffer_size = parameters.buffer_size;
+        let mut voices = Vec::with_capacity(max_voices);
+        for _ in 0..max_voices {
+            voices.push(Voice {
+                program: graph.compile(output, &parameters)?,
+                note: None,
+                age: 0,
+            });
+        }
+        let mut mix = Vec::new();
+        mix.resize(buffer_size, 0.0);
+        let mut scratch = Vec::new();
+        scratch.resize(buffer_size, 0.0);
+        Ok(VoiceBank {
+            graph,
+            output,
+            parameters,
+            voices,
+            gain: 1.0,
+            limit: Limit::Hard,
+            clock: 0,
+            mix: mix.into_boxed_slice(),
+            scratch: scratch.into_boxed_slice(),
+        })
+    }
+
+    /// Gain applied to every voice's samples before summing. Defaults to
+    /// 1.0; turn it down as more voices are expected to overlap.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    /// How the mixed-down buffer is kept from clipping. Defaults to
+    /// [`Limit::Hard`].
+    pub fn set_limit(&mut self, limit: Limit) {
+        self.limit = limit;
+    }
+
+    /// Find (or steal) the voice for `note`: an already-sounding voice on
+    /// the same note is reused so a sustained note keeps its own running
+    /// state, a free voice is preferred next, and failing that, the least
+    /// recently rendered voice is stolen.
+    fn assign_voice(&mut self, note: f32) -> usize {
+        if let Some(idx) = self.voices.iter().position(|v| v.note == Some(note)) {
+            return idx;
+        }
+        let idx = match self.voices.iter().position(|v| v.note.is_none()) {
+            Some(idx) => idx,
+            None => self
+                .voices
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, v)| v.age)
+                .map(|(idx, _)| idx)
+                .unwrap(),
+        };
+        self.voices[idx].note = Some(note);
+        if let Ok(program) = self.graph.compile(self.output, &self.parameters) {
+            self.voices[idx].program = program;
+        }
+        idx
+    }
+
+    /// Render one buffer for each of the currently sounding `inputs`,
+    /// allocating or stealing a voice per note as needed, and mix them down
+    /// into a single buffer. A voice whose `State::end` is reached during
+    /// this call is reclaimed immediately, so it's available to a new note
+    /// on the very next call.
+    pub fn render(&mut self, inputs: &[Input]) -> &[f32] {
+        self.clock += 1;
+        for sample in self.mix.iter_mut() {
+            *sample = 0.0;
+        }
+        for input in inputs.iter() {
+            let idx = self.assign_voice(input.note);
+            let voice = &mut self.voices[idx];
+            voice.age = self.clock;
+            let n = voice.program.render_into(&mut self.scratch, input);
+            for (m, &s) in self.mix.iter_mut().zip(self.scratch[..n].iter()) {
+                *m += s * self.gain;
+            }
+            if n < self.scratch.len() {
+                voice.note = None;
+            }
+        }
+        match self.limit {
+            Limit::Hard => {
+                for sample in self.mix.iter_mut() {
+                    *sample = sample.max(-1.0).min(1.0);
+                }
+            }
+            Limit::Soft => {
+                for sample in self.mix.iter_mut() {
+                    *sample = sample.tanh();
+                }
+            }
+        }
+        &self.mix[..]
+    }
+}
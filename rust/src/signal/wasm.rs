@@ -0,0 +1,90 @@
+//! FFI surface for the `no_std` + `alloc` DSP core, for a `wasm32-unknown-unknown`
+//! build to call into from JS: load a compiled [`bytecode::Program`], then
+//! pull output blocks from it directly into the wasm module's linear memory.
+//! The s-expression parser stays host-side (see the crate root doc comment);
+//! callers compile a patch to a [`bytecode::Program`] and pass its serialized
+//! bytes in.
+#![cfg(feature = "wasm")]
+
+use super::bytecode::{self, Program, RunState};
+use super::program::{Input, Parameters};
+#[cfg(feature = "no_std")]
+use alloc::boxed::Box;
+
+/// A running patch: its compiled program plus the render state threaded
+/// through successive [`render_block`] calls.
+pub struct Patch {
+    program: Program,
+    state: RunState,
+}
+
+/// Deserialize a [`bytecode::Program`] from `data` and allocate its render
+/// state for the given sample rate and block size. Returns a pointer the
+/// host passes back into [`render_block`]/[`free_patch`]; returns null on a
+/// malformed program.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn init_patch(
+    data: *const u8,
+    len: usize,
+    sample_rate: f64,
+    buffer_size: usize,
+) -> *mut Patch {
+    let bytes = core::slice::from_raw_parts(data, len);
+    let program: Program = match bytecode::deserialize(bytes) {
+        Ok(program) => program,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    let parameters = Parameters {
+        sample_rate,
+        buffer_size,
+        oversample: 1,
+    };
+    let state = program.start(&parameters);
+    Box::into_raw(Box::new(Patch { program, state }))
+}
+
+/// Render one block of audio into the front of `out`, returning the number
+/// of samples written: the `buffer_size` given to [`init_patch`], or fewer
+/// once the patch's envelope has stopped it.
+///
+/// # Safety
+///
+/// `patch` must be a pointer returned by [`init_patch`] and not yet freed.
+/// `out` must point to `out_len` writable `f32`s, and `out_len` must be at
+/// least that `buffer_size`.
+#[no_mangle]
+pub unsafe extern "C" fn render_block(
+    patch: *mut Patch,
+    gate_samples: i64,
+    note: f32,
+    out: *mut f32,
+    out_len: usize,
+) -> usize {
+    let patch = &mut *patch;
+    let input = Input {
+        gate: if gate_samples < 0 {
+            None
+        } else {
+            Some(gate_samples as usize)
+        },
+        note,
+    };
+    let out = core::slice::from_raw_parts_mut(out, out_len);
+    patch.program.render(&input, &mut patch.state, out)
+}
+
+/// Free a patch allocated by [`init_patch`].
+///
+/// # Safety
+///
+/// `patch` must be a pointer returned by [`init_patch`], not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn free_patch(patch: *mut Patch) {
+    if !patch.is_null() {
+        drop(Box::from_raw(patch));
+    }
+}
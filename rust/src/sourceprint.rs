@@ -1,3 +1,5 @@
+use crate::color::Style;
+use crate::error::{Applicability, Suggestion};
 use crate::sourcetext::*;
 use crate::utf8::UTF8Segments;
 use std::cmp::max;
@@ -11,10 +13,14 @@ mod color {
     pub const RESET: Style<'static> = Style(&[Reset]);
     pub const BADCHAR: Style<'static> = Style(&[ReverseVideo]);
     pub const HIGHLIGHT: Style<'static> = Style(&[FgBrightRed]);
+    pub const SECONDARY: Style<'static> = Style(&[FgBrightCyan]);
+    pub const HELP: Style<'static> = Style(&[FgGreen, Bold]);
+    pub const INSERT: Style<'static> = Style(&[FgGreen]);
 }
 
 const SPACES: [u8; 80] = [b' '; 80];
 const CARETS: [u8; 80] = [b'^'; 80];
+const DASHES: [u8; 80] = [b'-'; 80];
 
 fn fill(w: &mut impl Write, count: usize, pattern: &[u8; 80]) -> io::Result<()> {
     let mut rem = count;
@@ -107,12 +113,115 @@ fn digit_length(n: usize) -> usize {
     curs.position() as usize
 }
 
-/// Write a section of source code with the given range highlighted.
+/// A secondary span to annotate alongside the primary one -- e.g. "this
+/// signal is hertz, not volts, because it was defined here" pointing back
+/// at a definition while the primary span underlines the use. Drawn with a
+/// `---` underline instead of the primary span's `^^^`.
+pub struct Label<'a> {
+    pub span: TextSpan,
+    pub text: &'a str,
+}
+
+/// One underlined range on a single displayed source line, in display
+/// columns (not byte offsets).
+struct Mark<'a> {
+    start: usize,
+    end: usize,
+    underline: &'static [u8; 80],
+    style: Style<'static>,
+    label: Option<&'a str>,
+}
+
+/// Re-run [`SourcePrinter`]'s column accounting up to `byte` without
+/// writing anything, to find the display column a byte offset into `line`
+/// falls on (tabs and multi-byte characters don't map 1:1 to columns).
+fn column_at(line: &[u8], byte: usize, tab_width: u32) -> io::Result<usize> {
+    let mut pr = SourcePrinter {
+        column: 0,
+        tab_width,
+        is_highlighted: false,
+    };
+    pr.write(&mut io::sink(), &line[..byte])?;
+    Ok(pr.column)
+}
+
+/// Write a row of underline marks for one source line: a `^^^`/`---` run
+/// for each mark, in column order, with the rightmost labeled mark's text
+/// appended directly afterward if it's the only label on this line.
+fn write_underline_row(
+    w: &mut impl Write,
+    lineno_len: usize,
+    marks: &[Mark<'_>],
+) -> io::Result<()> {
+    fill(w, lineno_len + 1, &SPACES)?;
+    write!(w, "{}|{} ", color::LINENO, color::RESET)?;
+    let mut col = 0;
+    for mark in marks.iter() {
+        let start = mark.start.max(col);
+        fill(w, start - col, &SPACES)?;
+        write!(w, "{}", mark.style)?;
+        fill(w, mark.end.saturating_sub(start), mark.underline)?;
+        write!(w, "{}", color::RESET)?;
+        col = mark.end.max(col);
+    }
+    let labeled: Vec<&Mark> = marks.iter().filter(|m| m.label.is_some()).collect();
+    if let [mark] = &labeled[..] {
+        write!(w, " {}{}{}", mark.style, mark.label.unwrap(), color::RESET)?;
+    }
+    writeln!(w)
+}
+
+/// Write one row of the dangling-label "staircase" below a multi-label
+/// underline row: a `|` connector under every mark in `pending` except
+/// `retiring`, whose label text is written at its own column instead.
+fn write_label_row(
+    w: &mut impl Write,
+    lineno_len: usize,
+    pending: &[&Mark<'_>],
+    retiring: Option<&Mark<'_>>,
+) -> io::Result<()> {
+    fill(w, lineno_len + 1, &SPACES)?;
+    write!(w, "{}|{} ", color::LINENO, color::RESET)?;
+    let mut col = 0;
+    for &mark in pending.iter() {
+        if Some(mark.start) == retiring.map(|m| m.start) {
+            continue;
+        }
+        let start = mark.start.max(col);
+        fill(w, start - col, &SPACES)?;
+        write!(w, "{}|{}", mark.style, color::RESET)?;
+        col = start + 1;
+    }
+    if let Some(mark) = retiring {
+        let start = mark.start.max(col);
+        fill(w, start - col, &SPACES)?;
+        write!(w, "{}{}{}", mark.style, mark.label.unwrap(), color::RESET)?;
+    }
+    writeln!(w)
+}
+
+/// Write a section of source code with the given range highlighted, plus
+/// any secondary `labels` pointing at other spans in the same excerpt.
+///
+/// Every affected source line is printed once, even if several labels fall
+/// on it, with the gutter width shared across all of them. A lone label on
+/// a line is printed right after its underline; several labels on the same
+/// line instead dangle below it, each on its own row connected upward by a
+/// `|`, nearest (rightmost) label first, in the style of codespan/rustc.
 ///
 /// Control characters, non-ASCII characters, and invalid UTF-8 sequences are
 /// appropriately formatted and made visible.
-pub fn write_source(w: &mut impl Write, text: &SourceText<'_>, span: &TextSpan) -> io::Result<()> {
-    let lineno_len = digit_length((max(span.start.line, span.end.line) + 1) as usize);
+pub fn write_source(
+    w: &mut impl Write,
+    text: &SourceText<'_>,
+    span: &TextSpan,
+    labels: &[Label<'_>],
+) -> io::Result<()> {
+    let last_line = labels
+        .iter()
+        .map(|l| l.span.end.line)
+        .fold(max(span.start.line, span.end.line), max);
+    let lineno_len = digit_length((last_line + 1) as usize);
     fill(w, lineno_len, &SPACES)?;
     writeln!(
         w,
@@ -123,44 +232,153 @@ pub fn write_source(w: &mut impl Write, text: &SourceText<'_>, span: &TextSpan)
         span.start.line + 1,
         span.start.byte
     )?;
-    for lineno in span.start.line..=span.end.line {
+    let mut lines: Vec<u32> = (span.start.line..=span.end.line).collect();
+    for label in labels.iter() {
+        lines.extend(label.span.start.line..=label.span.end.line);
+    }
+    lines.sort_unstable();
+    lines.dedup();
+    for lineno in lines.iter().copied() {
         fill(w, lineno_len + 1, &SPACES)?;
         writeln!(w, "{}|{}", color::LINENO, color::RESET)?;
         write!(w, "{}{} |{} ", color::LINENO, lineno + 1, color::RESET)?;
         let line = text.line(lineno);
-        let startbyte = if lineno == span.start.line {
-            span.start.byte as usize
-        } else {
-            0
-        };
-        let endbyte = if lineno == span.end.line {
-            span.end.byte as usize
-        } else {
-            line.len()
-        };
         let mut pr = SourcePrinter {
             column: 0,
             tab_width: 8,
             is_highlighted: false,
         };
-        pr.write(w, &line[..startbyte])?;
-        let startcol = pr.column;
-        pr.write(w, &line[startbyte..endbyte])?;
-        let mut endcol = pr.column;
-        pr.write(w, &line[endbyte..])?;
-        if span.end.line == lineno && startcol == endcol {
-            endcol = startcol + 1;
-        }
+        pr.write(w, line)?;
         pr.finish(w)?;
         writeln!(w)?;
-        if startcol < endcol {
-            fill(w, lineno_len + 1, &SPACES)?;
-            write!(w, "{}|{} ", color::LINENO, color::RESET)?;
-            fill(w, startcol, &SPACES)?;
-            write!(w, "{}", color::HIGHLIGHT)?;
-            fill(w, endcol - startcol, &CARETS)?;
-            write!(w, "{}\n", color::RESET)?;
+
+        let mut marks = Vec::new();
+        if span.start.line <= lineno && lineno <= span.end.line {
+            let startbyte = if lineno == span.start.line {
+                span.start.byte as usize
+            } else {
+                0
+            };
+            let endbyte = if lineno == span.end.line {
+                span.end.byte as usize
+            } else {
+                line.len()
+            };
+            let start = column_at(line, startbyte, 8)?;
+            let mut end = column_at(line, endbyte, 8)?;
+            if lineno == span.end.line && start == end {
+                end = start + 1;
+            }
+            if start < end {
+                marks.push(Mark {
+                    start,
+                    end,
+                    underline: &CARETS,
+                    style: color::HIGHLIGHT,
+                    label: None,
+                });
+            }
+        }
+        for label in labels.iter() {
+            if label.span.start.line <= lineno && lineno <= label.span.end.line {
+                let startbyte = if lineno == label.span.start.line {
+                    label.span.start.byte as usize
+                } else {
+                    0
+                };
+                let endbyte = if lineno == label.span.end.line {
+                    label.span.end.byte as usize
+                } else {
+                    line.len()
+                };
+                let start = column_at(line, startbyte, 8)?;
+                let mut end = column_at(line, endbyte, 8)?;
+                if lineno == label.span.end.line && start == end {
+                    end = start + 1;
+                }
+                if start < end {
+                    marks.push(Mark {
+                        start,
+                        end,
+                        underline: &DASHES,
+                        style: color::SECONDARY,
+                        // Only the line the span ends on carries the label
+                        // text, so a multi-line secondary span doesn't
+                        // repeat its label on every line it touches.
+                        label: if lineno == label.span.end.line {
+                            Some(label.text)
+                        } else {
+                            None
+                        },
+                    });
+                }
+            }
+        }
+        if marks.is_empty() {
+            continue;
+        }
+        marks.sort_unstable_by_key(|m| m.start);
+        write_underline_row(w, lineno_len, &marks)?;
+
+        let mut pending: Vec<&Mark> = marks.iter().filter(|m| m.label.is_some()).collect();
+        if pending.len() > 1 {
+            // The rightmost label was already printed inline by
+            // `write_underline_row` only when it was the sole label; with
+            // more than one, retire them right-to-left, each getting its
+            // own dangling row while the rest keep their `|` connector.
+            while let Some(retiring) = pending.pop() {
+                write_label_row(w, lineno_len, &pending, Some(retiring))?;
+            }
         }
     }
     Ok(())
 }
+
+/// Write a `help:` line for a [`Suggestion`], plus (for a single-line span) a
+/// preview of the affected source line with the replacement substituted in
+/// and highlighted, so the reader can see the fix without applying it.
+pub fn write_suggestion(
+    w: &mut impl Write,
+    text: &SourceText<'_>,
+    suggestion: &Suggestion,
+) -> io::Result<()> {
+    let span = match text.span(suggestion.span) {
+        Some(span) => span,
+        None => return Ok(()),
+    };
+    let note = match suggestion.applicability {
+        Applicability::MachineApplicable => "",
+        Applicability::MaybeIncorrect => " (unverified guess)",
+    };
+    writeln!(
+        w,
+        "{}help{}: replace with `{}`{}",
+        color::HELP,
+        color::RESET,
+        suggestion.replacement,
+        note,
+    )?;
+    if span.start.line != span.end.line {
+        // A preview only makes sense when the replaced text fits on one
+        // line; the message above is the whole suggestion in that case.
+        return Ok(());
+    }
+    let lineno = span.start.line;
+    let line = text.line(lineno);
+    let lineno_len = digit_length((lineno + 1) as usize);
+    fill(w, lineno_len + 1, &SPACES)?;
+    writeln!(w, "{}|{}", color::LINENO, color::RESET)?;
+    write!(w, "{}{} |{} ", color::LINENO, lineno + 1, color::RESET)?;
+    let mut pr = SourcePrinter {
+        column: 0,
+        tab_width: 8,
+        is_highlighted: false,
+    };
+    pr.write(w, &line[..span.start.byte as usize])?;
+    write!(w, "{}", color::INSERT)?;
+    pr.write(w, suggestion.replacement.as_bytes())?;
+    write!(w, "{}", color::RESET)?;
+    pr.write(w, &line[span.end.byte as usize..])?;
+    pr.finish(w)?;
+    writeln!(w)
+}
@@ -9,16 +9,25 @@ pub struct TextPos {
     pub byte: u32,
 }
 
+// A decoded span within a source file, as a pair of decoded positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextSpan {
+    pub start: TextPos,
+    pub end: TextPos,
+}
+
 // A decoder for source positions within a single source file.
 pub struct SourceText<'a> {
+    filename: &'a str,
     text: &'a [u8],
     lines: Vec<u32>, // Start offset of each line.
     span: Span,
 }
 
 impl<'a> SourceText<'a> {
-    // Create a new source location decoder for a file with the given contents.
-    pub fn new(text: &'a [u8]) -> Self {
+    // Create a new source location decoder for a file with the given name and
+    // contents.
+    pub fn new(filename: &'a str, text: &'a [u8]) -> Self {
         let mut prev = b'\0';
         let mut lines = Vec::<u32>::new();
         lines.push(0);
@@ -38,6 +47,7 @@ impl<'a> SourceText<'a> {
             prev = c;
         }
         SourceText {
+            filename,
             text,
             lines,
             span: Span {
@@ -47,6 +57,11 @@ impl<'a> SourceText<'a> {
         }
     }
 
+    // Get the name of the source file.
+    pub fn filename(&self) -> &'a str {
+        self.filename
+    }
+
     // Convert a byte offset to a line number and character offset.
     pub fn lookup(&self, pos: Pos) -> Option<TextPos> {
         if pos < self.span.start || self.span.end < pos {
@@ -85,6 +100,14 @@ impl<'a> SourceText<'a> {
             }
         }
     }
+
+    // Decode the start and end of a span. Returns None if either endpoint
+    // falls outside the source text.
+    pub fn span(&self, span: Span) -> Option<TextSpan> {
+        let start = self.lookup(span.start)?;
+        let end = self.lookup(span.end)?;
+        Some(TextSpan { start, end })
+    }
 }
 
 #[cfg(test)]
@@ -94,7 +117,7 @@ mod test {
 
     fn test_lookup(input: &[u8], outputs: &[(u32, u32)]) {
         assert_eq!(input.len() + 1, outputs.len());
-        let text = SourceText::new(input);
+        let text = SourceText::new("<test>", input);
         let mut success = true;
         for (n, &expect) in (1..).zip(outputs.iter()) {
             let expect = Some(match expect {
@@ -153,7 +176,7 @@ mod test {
 
     #[test]
     fn test_line() {
-        let text = SourceText::new(b"abc\ndef\rghi\r\njkl");
+        let text = SourceText::new("<test>", b"abc\ndef\rghi\r\njkl");
         let lines: &[&'static [u8]] = &[b"abc", b"def", b"ghi", b"jkl"];
         let mut success = true;
         for (n, &line) in lines.iter().enumerate() {
@@ -1,7 +1,12 @@
+use crate::number::ParsedNumber;
 use crate::sourcepos::{HasPos, Pos, Span};
 use crate::utf8::parse_character;
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
+use std::iter::FusedIterator;
+use std::str;
 
 /// Tokenizer error. Not used for syntax errors.
 #[derive(Debug, Clone, Copy)]
@@ -20,6 +25,46 @@ impl fmt::Display for TokenError {
 
 impl Error for TokenError {}
 
+/// Why a [`Type::Error`] token couldn't be lexed as anything else. Following
+/// rustc_lexer's approach of not reporting errors but storing them as flags
+/// on the token, so the tokenizer never has to stop: it always emits a
+/// token, and a downstream parser decides what diagnostic to produce (and
+/// can keep going to find more errors in the same pass).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LexError {
+    /// A byte sequence that doesn't decode as UTF-8 at all.
+    InvalidUtf8,
+    /// A Unicode control character (C0 or C1) where a symbol or number was
+    /// expected.
+    ControlCharacter,
+    /// A lone byte that can never begin a valid UTF-8 sequence: an
+    /// unexpected continuation byte, or 0xc0, 0xc1, or 0xf5-0xff.
+    StrayByte,
+    /// A well-formed character that just isn't the start of anything the
+    /// tokenizer recognizes (e.g. a bare `#` not followed by `|`).
+    UnexpectedCharacter,
+    /// A [`Type::String`] token that ran to the end of input before a
+    /// closing, unescaped `"`.
+    UnterminatedString,
+    /// A [`Type::Comment`] block comment (`#| ... |#`) that ran to the end
+    /// of input before its nesting depth returned to zero.
+    UnterminatedComment,
+    /// A [`Type::Number`] token whose digits don't form a valid number --
+    /// a second `.` (`1.2.3`), or an `e`/`E` exponent marker with no
+    /// digits after it (`1e`).
+    MalformedNumber,
+}
+
+/// Whether a [`Type::Number`] token's value has a fractional or exponent
+/// part. Determined purely from the shape of the digits -- `.` or `e`/`E`
+/// present means [`Float`](Self::Float) -- independent of whether the
+/// number actually parses (see [`LexError::MalformedNumber`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberKind {
+    Integer,
+    Float,
+}
+
 // Token types.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Type {
@@ -28,6 +73,10 @@ pub enum Type {
     Comment,
     Symbol,
     Number,
+    // A quoted string literal, including its delimiting quotes. Escapes are
+    // left undecoded in `text` -- pass it through `unescape_string` for the
+    // value.
+    String,
     ParenOpen,
     ParenClose,
 }
@@ -38,6 +87,21 @@ pub struct Token<'a> {
     pub ty: Type,
     pub pos: Pos,
     pub text: &'a [u8],
+    /// Extra detail about why lexing didn't go cleanly: the reason for a
+    /// [`Type::Error`] token, [`LexError::UnterminatedString`]/
+    /// [`LexError::UnterminatedComment`] for a [`Type::String`]/
+    /// [`Type::Comment`] token that reached end of input unclosed, or
+    /// [`LexError::MalformedNumber`] for a [`Type::Number`] token whose
+    /// digits don't form a valid number.
+    pub error: Option<LexError>,
+    /// For a [`Type::Number`] token, whether its digits read as an integer
+    /// or a float. `None` for every other token type.
+    pub number_kind: Option<NumberKind>,
+    /// For a [`Type::Number`] token, the trailing unit/suffix characters
+    /// swallowed into `text` after its numeric body (e.g. the `abc` in
+    /// `5.0abc`), if any. `None` for every other token type, and for a
+    /// number with no suffix.
+    pub suffix: Option<&'a [u8]>,
 }
 
 impl HasPos for Token<'_> {
@@ -50,10 +114,48 @@ impl HasPos for Token<'_> {
     }
 }
 
+impl<'a> Token<'a> {
+    /// The numeric body of a well-formed [`Type::Number`] token, with any
+    /// unit suffix stripped off. `None` for any other token, or one with
+    /// `error` set.
+    fn number_text(&self) -> Option<&'a str> {
+        if self.ty != Type::Number || self.error.is_some() {
+            return None;
+        }
+        let len = match self.suffix {
+            Some(suffix) => self.text.len() - suffix.len(),
+            None => self.text.len(),
+        };
+        str::from_utf8(&self.text[..len]).ok()
+    }
+
+    /// Parse this token's numeric body as an `f64`. `None` unless this is a
+    /// well-formed [`Type::Number`] token.
+    pub fn number_value(&self) -> Option<f64> {
+        self.number_text()?.parse().ok()
+    }
+
+    /// The `i64` analog of [`Token::number_value`]. Also `None` for a
+    /// [`NumberKind::Float`] body, the same way `"1.5".parse::<i64>()`
+    /// would fail.
+    pub fn number_value_i64(&self) -> Option<i64> {
+        self.number_text()?.parse().ok()
+    }
+}
+
 pub struct Tokenizer<'a> {
     text: &'a [u8],
     pos: u32,
     start_pos: u32,
+    /// Set once the iterator adaptor below has yielded `End`, so that it
+    /// keeps returning `None` afterward instead of yielding `End` again on
+    /// every subsequent call. The inherent [`Tokenizer::next`] is unaffected
+    /// and keeps returning `End` forever, as before.
+    ended: bool,
+    /// Scratch space reused by [`scan_number`] on every [`Type::Number`]
+    /// token, the same way [`crate::parser::Parser`] keeps its own
+    /// [`ParsedNumber`] around instead of allocating a fresh one per call.
+    number: ParsedNumber,
 }
 
 // Return true if the character is ASCII whitespace.
@@ -93,6 +195,76 @@ fn symbol_len(text: &[u8]) -> usize {
     }
 }
 
+/// Scan a [`Type::Number`] token's full captured text for the boundary
+/// between its numeric body and a trailing unit suffix (e.g. the `abc` in
+/// `5.0abc`), classifying the body as [`NumberKind::Integer`] or
+/// [`NumberKind::Float`] along the way.
+///
+/// Returns `(body_len, kind, malformed)`. Delegates the actual boundary
+/// detection to [`ParsedNumber::parse`] -- the same parser
+/// [`crate::parser::Parser`] itself uses -- so radix prefixes (`0x`, `0b`,
+/// `0o`) and `_` digit-group separators are understood identically at
+/// both layers instead of by a second, independently maintained scanner.
+///
+/// [`ParsedNumber::parse`] is deliberately lenient about a dangling
+/// exponent marker with nothing after it (it just leaves `e`/`p` in the
+/// remainder, for [`crate::units::Units::parse`] to make sense of as a
+/// unit suffix), but at the lexer level that shape, and a second `.`, can
+/// only be a botched continuation of the number -- so both are reported
+/// as `malformed` here, with `body_len` covering the whole text instead
+/// of splitting off a suffix.
+///
+/// Assumes `text` starts with a valid number, as already decided by
+/// [`Tokenizer::next`]'s dispatch on the first character.
+fn scan_number(num: &mut ParsedNumber, text: &[u8]) -> (usize, NumberKind, bool) {
+    // `is_symbol` only ever lets ASCII bytes into a number token's text.
+    let text_str = str::from_utf8(text).expect("number token text must be ASCII");
+    let pos = Span {
+        start: Pos(0),
+        end: Pos(text.len() as u32),
+    };
+    let rest = match num.parse(text_str, pos) {
+        Ok(rest) => rest,
+        Err(_) => {
+            let kind = if text.contains(&b'.') {
+                NumberKind::Float
+            } else {
+                NumberKind::Integer
+            };
+            return (text.len(), kind, true);
+        }
+    };
+    if matches!(
+        rest.as_bytes().first(),
+        Some(b'.') | Some(b'e') | Some(b'E') | Some(b'p') | Some(b'P')
+    ) {
+        return (text.len(), NumberKind::Float, true);
+    }
+    let kind = if num.exponent.is_some() || num.bin_exponent.is_some() {
+        NumberKind::Float
+    } else {
+        NumberKind::Integer
+    };
+    (text.len() - rest.len(), kind, false)
+}
+
+/// Classify a byte that didn't start any recognized token, for a
+/// [`Type::Error`] token. `first` is the byte itself and `text` is the
+/// remaining input starting at it.
+fn classify_char_error(first: u8, text: &[u8]) -> (LexError, usize) {
+    use LexError::*;
+    let (c, n) = parse_character(text);
+    let error = match c {
+        Some(c) if c.is_control() => ControlCharacter,
+        Some(_) => UnexpectedCharacter,
+        None => match first {
+            0x80..=0xbf | 0xc0 | 0xc1 | 0xf5..=0xff => StrayByte,
+            _ => InvalidUtf8,
+        },
+    };
+    (error, n)
+}
+
 impl<'a> Tokenizer<'a> {
     // Create a new tokenizer that returns a stream of tokens from the given text.
     pub fn new(text: &'a [u8]) -> Result<Self, TokenError> {
@@ -104,16 +276,20 @@ impl<'a> Tokenizer<'a> {
             text,
             pos: 0,
             start_pos,
+            ended: false,
+            number: ParsedNumber::new(),
         })
     }
 
     /// Rewind tokenizer to start of stream.
     pub fn rewind(&mut self) -> () {
         self.pos = 0;
+        self.ended = false;
     }
 
     // Return the next token from the stream.
     pub fn next(&mut self) -> Token<'a> {
+        use LexError::*;
         use Type::*;
         let pos = match self.text[self.pos as usize..]
             .iter()
@@ -127,10 +303,14 @@ impl<'a> Tokenizer<'a> {
                     ty: End,
                     pos: Pos(pos),
                     text: &[],
+                    error: None,
+                    number_kind: None,
+                    suffix: None,
                 };
             }
         };
         let (&first, rest) = self.text[pos..].split_first().unwrap();
+        let mut error = None;
         let (ty, len) = match first as char {
             // Lower case
             'a' | 'b' | 'c' | 'd' | 'e' | 'f' | 'g' | 'h' | 'i' | 'j' | 'k' | 'l' | 'm'
@@ -173,26 +353,189 @@ impl<'a> Tokenizer<'a> {
                 };
 		(ty, symbol_len(rest))
             }
+            '"' => {
+                let mut i = 0;
+                let mut terminated = false;
+                while i < rest.len() {
+                    match rest[i] {
+                        b'"' => {
+                            i += 1;
+                            terminated = true;
+                            break;
+                        }
+                        b'\\' => i += 2,
+                        _ => i += 1,
+                    }
+                }
+                if !terminated {
+                    error = Some(UnterminatedString);
+                    i = i.min(rest.len());
+                }
+                (String, i)
+            }
+            '#' if rest.first() == Some(&b'|') => {
+                // Nestable block comment: #| outer #| inner |# still-comment |#
+                let mut i = 1; // rest[0] is the '|' that opened it.
+                let mut depth = 1u32;
+                let mut terminated = false;
+                while i < rest.len() {
+                    if rest[i..].starts_with(b"|#") {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            terminated = true;
+                            break;
+                        }
+                    } else if rest[i..].starts_with(b"#|") {
+                        depth += 1;
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                if !terminated {
+                    error = Some(UnterminatedComment);
+                    i = i.min(rest.len());
+                }
+                (Comment, i)
+            }
             '(' => (ParenOpen, 0),
             ')' => (ParenClose, 0),
             _ => {
-		let (_, n) = parse_character(&self.text[pos..]);
+		let (err, n) = classify_char_error(first, &self.text[pos..]);
+		error = Some(err);
 		(Error, n-1)
 	    }
         };
         let end = pos + 1 + len;
         self.pos = end as u32;
+        let text = &self.text[pos..end];
+        let (number_kind, suffix) = if ty == Number {
+            let (body_len, kind, malformed) = scan_number(&mut self.number, text);
+            if malformed {
+                error = Some(MalformedNumber);
+            }
+            let suffix = if body_len < text.len() {
+                Some(&text[body_len..])
+            } else {
+                None
+            };
+            (Some(kind), suffix)
+        } else {
+            (None, None)
+        };
         Token {
             ty,
             pos: Pos(pos as u32 + self.start_pos),
-            text: &self.text[pos..end],
+            text,
+            error,
+            number_kind,
+            suffix,
+        }
+    }
+}
+
+/// Exposes the token stream through the standard iterator protocol, for
+/// callers who just want `for tok in tokenizer { ... }`, `collect`,
+/// `take_while`, etc. instead of hand-rolling a loop around the sentinel
+/// `End` token that the inherent [`Tokenizer::next`] returns forever.
+/// [`Tokenizer::next`] remains available unchanged for callers (like
+/// [`crate::parser::Parser`]) that want to see `End` explicitly.
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if self.ended {
+            return None;
+        }
+        let tok = Tokenizer::next(self);
+        if tok.ty == Type::End {
+            self.ended = true;
+            None
+        } else {
+            Some(tok)
         }
     }
 }
 
+impl<'a> FusedIterator for Tokenizer<'a> {}
+
+/// An invalid escape sequence in a string literal, at the given byte offset
+/// of the backslash within the raw bytes passed to [`unescape_string`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnescapeError {
+    pub offset: usize,
+}
+
+impl fmt::Display for UnescapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid escape sequence at offset {}", self.offset)
+    }
+}
+
+impl Error for UnescapeError {}
+
+/// Decode a string literal's value from `raw`, the bytes between (not
+/// including) its delimiting quotes. This is the separate unescaping pass
+/// rustc_lexer's design calls for: [`Tokenizer`] only records the string's
+/// span and whether it's terminated, leaving escapes undecoded in
+/// [`Token::text`]; this function is what actually interprets them.
+///
+/// Recognizes `\n`, `\t`, `\r`, `\\`, `\"`, `\xNN` (a byte given as two hex
+/// digits), and `\u{...}` (a Unicode scalar value given as hex digits).
+/// Allocates only if `raw` contains an escape; otherwise borrows it
+/// unchanged.
+pub fn unescape_string(raw: &[u8]) -> Result<Cow<[u8]>, UnescapeError> {
+    let first = match raw.iter().position(|&c| c == b'\\') {
+        Some(first) => first,
+        None => return Ok(Cow::Borrowed(raw)),
+    };
+    let mut out = Vec::with_capacity(raw.len());
+    out.extend_from_slice(&raw[..first]);
+    let mut pos = first;
+    while pos < raw.len() {
+        if raw[pos] != b'\\' {
+            out.push(raw[pos]);
+            pos += 1;
+            continue;
+        }
+        let esc_start = pos;
+        let err = || UnescapeError { offset: esc_start };
+        let kind = *raw.get(pos + 1).ok_or_else(err)?;
+        pos += 2;
+        match kind {
+            b'n' => out.push(b'\n'),
+            b't' => out.push(b'\t'),
+            b'r' => out.push(b'\r'),
+            b'\\' => out.push(b'\\'),
+            b'"' => out.push(b'"'),
+            b'x' => {
+                let hex = raw.get(pos..pos + 2).ok_or_else(err)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| err())?;
+                out.push(u8::from_str_radix(hex, 16).map_err(|_| err())?);
+                pos += 2;
+            }
+            b'u' => {
+                if raw.get(pos) != Some(&b'{') {
+                    return Err(err());
+                }
+                let len = raw[pos + 1..].iter().position(|&c| c == b'}').ok_or_else(err)?;
+                let hex = std::str::from_utf8(&raw[pos + 1..pos + 1 + len]).map_err(|_| err())?;
+                let code = u32::from_str_radix(hex, 16).map_err(|_| err())?;
+                let c = char::try_from(code).map_err(|_| err())?;
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                pos += len + 2;
+            }
+            _ => return Err(err()),
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Token, Tokenizer, Type};
+    use super::{LexError, NumberKind, Token, Tokenizer, Type};
     use crate::sourcepos::Pos;
     use crate::test::*;
     use std::fmt;
@@ -202,6 +545,7 @@ mod tests {
             && x.pos == y.pos
             && x.text.as_ptr() == y.text.as_ptr()
             && x.text.len() == y.text.len()
+            && x.error == y.error
     }
 
     struct Tok<'a>(&'a Token<'a>);
@@ -212,8 +556,20 @@ mod tests {
                 ty,
                 pos: Pos(pos),
                 text,
+                error,
+                number_kind,
+                suffix,
             }) = self;
-            write!(f, "pos={}, type={:?}, text={}", pos, ty, Str(text))
+            write!(
+                f,
+                "pos={}, type={:?}, text={}, error={:?}, number_kind={:?}, suffix={:?}",
+                pos,
+                ty,
+                Str(text),
+                error,
+                number_kind,
+                suffix
+            )
         }
     }
 
@@ -251,11 +607,12 @@ mod tests {
             (b"+.9 ", Number),
             (b"(a", ParenOpen),
             (b")a", ParenClose),
-            (b"\x01 ", Error),
-            (b"\x7f ", Error),
-            (b"\x80 ", Error),
-            (b"\xff ", Error),
-            (b"\xc2\x80 ", Error),
+            (b"\"\" ", String),
+            (b"\"hello\" ", String),
+            (b"\"a\\\"b\" ", String),
+            (b"#| hi |# ", Comment),
+            (b"#| outer #| inner |# still-comment |# ", Comment),
+            (b"#|#| |#|# ", Comment),
         ];
         let mut tests = Tests::new();
         for (n, &(input, ty)) in cases.iter().enumerate() {
@@ -264,6 +621,9 @@ mod tests {
                 ty,
                 pos: Pos(1),
                 text: &input[..input.len() - 1],
+                error: None,
+                number_kind: None,
+                suffix: None,
             };
             for input in [baretok, input].iter() {
                 let mut toks = match Tokenizer::new(input) {
@@ -285,4 +645,288 @@ mod tests {
         }
         tests.done()
     }
+
+    #[test]
+    fn test_lex_errors() -> Result<(), TestFailure> {
+        use LexError::*;
+        use Type::*;
+        let cases: &[(&'static [u8], LexError)] = &[
+            (b"\x01 ", ControlCharacter),
+            (b"\x7f ", ControlCharacter),
+            (b"\x80 ", StrayByte),
+            (b"\xff ", StrayByte),
+            (b"\xc2\x80 ", ControlCharacter),
+        ];
+        let mut tests = Tests::new();
+        for (n, &(input, error)) in cases.iter().enumerate() {
+            let baretok = &input[..input.len() - 1];
+            let etok = Token {
+                ty: Error,
+                pos: Pos(1),
+                text: &input[..input.len() - 1],
+                error: Some(error),
+                number_kind: None,
+                suffix: None,
+            };
+            for input in [baretok, input].iter() {
+                let mut toks = match Tokenizer::new(input) {
+                    Ok(toks) => toks,
+                    Err(e) => {
+                        eprintln!("Test {} failed: input={}", n, Str(input));
+                        eprintln!("    Error: {}", e);
+                        tests.add(false);
+                        continue;
+                    }
+                };
+                let tok = toks.next();
+                if !tests.add(tok_eq(&tok, &etok)) {
+                    eprintln!("Test {} failed: input={}", n, Str(input));
+                    eprintln!("    Got:    {}", Tok(&tok));
+                    eprintln!("    Expect: {}", Tok(&etok));
+                }
+            }
+        }
+        tests.done()
+    }
+
+    #[test]
+    fn test_number_kind() -> Result<(), TestFailure> {
+        use NumberKind::*;
+        // (input, kind, suffix, value as f64, value as i64)
+        type Case = (&'static [u8], NumberKind, &'static [u8], f64, Option<i64>);
+        const CASES: &[Case] = &[
+            (b"987", Integer, b"", 987.0, Some(987)),
+            (b"5.0", Float, b"", 5.0, None),
+            (b"1e10", Float, b"", 1e10, None),
+            (b"-.0", Float, b"", -0.0, None),
+            (b"5.0abc", Float, b"abc", 5.0, None),
+        ];
+        let mut tests = Tests::new();
+        for (n, &(input, kind, suffix, value, ivalue)) in CASES.iter().enumerate() {
+            let mut toks = match Tokenizer::new(input) {
+                Ok(toks) => toks,
+                Err(e) => {
+                    eprintln!("Test {} failed: input={}", n, Str(input));
+                    eprintln!("    Error: {}", e);
+                    tests.add(false);
+                    continue;
+                }
+            };
+            let tok = toks.next();
+            let expect_suffix = if suffix.is_empty() { None } else { Some(suffix) };
+            if !tests.add(
+                tok.ty == Type::Number
+                    && tok.error.is_none()
+                    && tok.number_kind == Some(kind)
+                    && tok.suffix == expect_suffix
+                    && tok.number_value() == Some(value)
+                    && tok.number_value_i64() == ivalue,
+            ) {
+                eprintln!("Test {} failed: input={}", n, Str(input));
+                eprintln!("    Got:    {}", Tok(&tok));
+                eprintln!(
+                    "    number_value={:?}, number_value_i64={:?}",
+                    tok.number_value(),
+                    tok.number_value_i64()
+                );
+            }
+        }
+        tests.done()
+    }
+
+    #[test]
+    fn test_malformed_number() -> Result<(), TestFailure> {
+        let cases: &[&'static [u8]] = &[b"1.2.3", b"1e"];
+        let mut tests = Tests::new();
+        for (n, &input) in cases.iter().enumerate() {
+            let mut toks = match Tokenizer::new(input) {
+                Ok(toks) => toks,
+                Err(e) => {
+                    eprintln!("Test {} failed: input={}", n, Str(input));
+                    eprintln!("    Error: {}", e);
+                    tests.add(false);
+                    continue;
+                }
+            };
+            let tok = toks.next();
+            if !tests.add(
+                tok.ty == Type::Number
+                    && tok.error == Some(LexError::MalformedNumber)
+                    && tok.text == input
+                    && tok.number_value().is_none()
+                    && tok.number_value_i64().is_none(),
+            ) {
+                eprintln!("Test {} failed: input={}", n, Str(input));
+                eprintln!("    Got:    {}", Tok(&tok));
+            }
+        }
+        tests.done()
+    }
+
+    #[test]
+    fn test_iterator() -> Result<(), TestFailure> {
+        let mut tests = Tests::new();
+        let toks = Tokenizer::new(b"a b 1").unwrap();
+        let collected: Vec<Type> = toks.map(|tok| tok.ty).collect();
+        tests.add(collected == [Type::Symbol, Type::Symbol, Type::Number]);
+
+        // The fused iterator stops yielding after the first `End`, while the
+        // inherent `next()` would keep returning `End` forever.
+        let mut toks = Tokenizer::new(b"a").unwrap();
+        tests.add(Iterator::next(&mut toks).is_some());
+        tests.add(Iterator::next(&mut toks).is_none());
+        tests.add(Iterator::next(&mut toks).is_none());
+        tests.add(toks.next().ty == Type::End);
+
+        // rewind() resets the fused state too.
+        toks.rewind();
+        tests.add(Iterator::next(&mut toks).is_some());
+        tests.add(Iterator::next(&mut toks).is_none());
+
+        tests.done()
+    }
+
+    #[test]
+    fn test_unterminated_string() -> Result<(), TestFailure> {
+        let cases: &[&'static [u8]] = &[b"\"", b"\"abc", b"\"abc\\"];
+        let mut tests = Tests::new();
+        for (n, &input) in cases.iter().enumerate() {
+            let mut toks = match Tokenizer::new(input) {
+                Ok(toks) => toks,
+                Err(e) => {
+                    eprintln!("Test {} failed: input={}", n, Str(input));
+                    eprintln!("    Error: {}", e);
+                    tests.add(false);
+                    continue;
+                }
+            };
+            let tok = toks.next();
+            let etok = Token {
+                ty: Type::String,
+                pos: Pos(1),
+                text: input,
+                error: Some(LexError::UnterminatedString),
+                number_kind: None,
+                suffix: None,
+            };
+            if !tests.add(tok_eq(&tok, &etok)) {
+                eprintln!("Test {} failed: input={}", n, Str(input));
+                eprintln!("    Got:    {}", Tok(&tok));
+                eprintln!("    Expect: {}", Tok(&etok));
+            }
+        }
+        tests.done()
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() -> Result<(), TestFailure> {
+        let cases: &[&'static [u8]] = &[
+            b"#|",
+            b"#| abc",
+            // Nesting never returns to depth zero.
+            b"#| outer #| inner |# still unterminated",
+        ];
+        let mut tests = Tests::new();
+        for (n, &input) in cases.iter().enumerate() {
+            let mut toks = match Tokenizer::new(input) {
+                Ok(toks) => toks,
+                Err(e) => {
+                    eprintln!("Test {} failed: input={}", n, Str(input));
+                    eprintln!("    Error: {}", e);
+                    tests.add(false);
+                    continue;
+                }
+            };
+            let tok = toks.next();
+            let etok = Token {
+                ty: Type::Comment,
+                pos: Pos(1),
+                text: input,
+                error: Some(LexError::UnterminatedComment),
+                number_kind: None,
+                suffix: None,
+            };
+            if !tests.add(tok_eq(&tok, &etok)) {
+                eprintln!("Test {} failed: input={}", n, Str(input));
+                eprintln!("    Got:    {}", Tok(&tok));
+                eprintln!("    Expect: {}", Tok(&etok));
+            }
+        }
+        tests.done()
+    }
+
+    #[test]
+    fn test_unescape_string() -> Result<(), TestFailure> {
+        use super::unescape_string;
+        let ok_cases: &[(&'static [u8], &'static [u8])] = &[
+            (b"", b""),
+            (b"plain", b"plain"),
+            (b"a\\nb", b"a\nb"),
+            (b"a\\tb", b"a\tb"),
+            (b"a\\rb", b"a\rb"),
+            (b"a\\\\b", b"a\\b"),
+            (b"a\\\"b", b"a\"b"),
+            (b"\\x41\\x42", b"AB"),
+            (b"\\u{41}", b"A"),
+        ];
+        let mut tests = Tests::new();
+        for (n, &(raw, expect)) in ok_cases.iter().enumerate() {
+            match unescape_string(raw) {
+                Ok(got) => {
+                    if !tests.add(&*got == expect) {
+                        eprintln!("Test {} failed: raw={}", n, Str(raw));
+                        eprintln!("    Got:    {}", Str(&got));
+                        eprintln!("    Expect: {}", Str(expect));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Test {} failed: raw={}", n, Str(raw));
+                    eprintln!("    Error: {}", e);
+                    tests.add(false);
+                }
+            }
+        }
+        let emoji = "x\u{1f600}y".to_string();
+        match unescape_string(b"x\\u{1F600}y") {
+            Ok(got) => {
+                if !tests.add(&*got == emoji.as_bytes()) {
+                    eprintln!("Test emoji failed");
+                    eprintln!("    Got:    {}", Str(&got));
+                    eprintln!("    Expect: {}", Str(emoji.as_bytes()));
+                }
+            }
+            Err(e) => {
+                eprintln!("Test emoji failed");
+                eprintln!("    Error: {}", e);
+                tests.add(false);
+            }
+        }
+
+        let err_cases: &[(&'static [u8], usize)] = &[
+            (b"\\q", 0),
+            (b"ab\\", 2),
+            (b"\\x4", 0),
+            (b"\\x4g", 0),
+            (b"\\u41", 0),
+            (b"\\u{41", 0),
+            (b"\\u{d800}", 0),
+        ];
+        for (n, &(raw, offset)) in err_cases.iter().enumerate() {
+            match unescape_string(raw) {
+                Ok(got) => {
+                    eprintln!("Test {} failed: raw={}", n, Str(raw));
+                    eprintln!("    Got:    Ok({})", Str(&got));
+                    tests.add(false);
+                }
+                Err(e) => {
+                    if !tests.add(e.offset == offset) {
+                        eprintln!("Test {} failed: raw={}", n, Str(raw));
+                        eprintln!("    Got:    {:?}", e);
+                        eprintln!("    Expect: offset={}", offset);
+                    }
+                }
+            }
+        }
+        tests.done()
+    }
 }
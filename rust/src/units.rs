@@ -1,5 +1,8 @@
+use crate::editdistance::edit_distance;
 use crate::sourcepos::Span;
+use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
 
 /// An error from an operation on units.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -16,12 +19,18 @@ impl fmt::Display for UnitError {
     }
 }
 
+impl Error for UnitError {}
+
 /// An error from parsing units.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ParseError {
     UnknownPrefix,
     UnknownUnits,
     PrefixNotAllowed,
+    /// A `^` wasn't followed by a valid integer power.
+    InvalidExponent,
+    /// Combining a compound expression's factors overflowed a dimension.
+    Overflow,
 }
 
 impl fmt::Display for ParseError {
@@ -31,10 +40,14 @@ impl fmt::Display for ParseError {
             UnknownPrefix => "unknown prefix",
             UnknownUnits => "unknown units",
             PrefixNotAllowed => "metric prefix not allowed with units",
+            InvalidExponent => "invalid exponent after '^'",
+            Overflow => "units too large, operation overflowed",
         })
     }
 }
 
+impl Error for ParseError {}
+
 /// Parse a metric prefix. Returns the prefix's power of 10. Only powers of 1000
 /// are recognized; so hecto (h), deca (da), deci (d), and centi (c) are
 /// ignored.
@@ -62,6 +75,74 @@ fn parse_prefix(c: char) -> Option<i32> {
     })
 }
 
+/// The unit strings accepted by [`Units::parse_without_prefix`], reused to
+/// suggest the nearest valid spelling when parsing fails.
+const UNIT_NAMES: &[&str] = &["V", "s", "Hz", "rad", "dB"];
+
+/// The single-character metric prefixes accepted by [`parse_prefix`], reused
+/// to suggest the nearest valid spelling when parsing fails.
+const PREFIX_CHARS: &[char] = &[
+    'y', 'z', 'a', 'f', 'p', 'n', 'u', 'm', 'k', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y',
+];
+
+/// Greatest edit distance worth suggesting a fix for: close enough that the
+/// suggestion is probably what was meant, rather than noise.
+fn max_suggestion_distance(text: &str) -> usize {
+    if text.chars().count() > 4 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Suggest the closest of `candidates` to `text` by case-insensitive edit
+/// distance (case is folded away since a mismatched prefix/unit's case is
+/// the single most common typo this is meant to catch). Ties are reported
+/// as no suggestion at all, rather than guessing among equally-plausible
+/// candidates.
+fn suggest<'a>(text: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max_distance = max_suggestion_distance(text);
+    let text = text.to_lowercase();
+    let mut best: Option<(&'a str, usize)> = None;
+    let mut tied = false;
+    for candidate in candidates {
+        let distance = edit_distance(&text, &candidate.to_lowercase());
+        if distance > max_distance {
+            continue;
+        }
+        best = match best {
+            Some((_, best_distance)) if distance > best_distance => best,
+            Some((_, best_distance)) if distance == best_distance => {
+                tied = true;
+                best
+            }
+            _ => {
+                tied = false;
+                Some((candidate, distance))
+            }
+        };
+    }
+    if tied {
+        None
+    } else {
+        best.map(|(candidate, _)| candidate.to_string())
+    }
+}
+
+/// Suggest the nearest known unit string to `text`, for a "did you mean"
+/// diagnostic.
+fn suggest_unit_name(text: &str) -> Option<String> {
+    suggest(text, UNIT_NAMES.iter().copied())
+}
+
+/// Suggest the nearest known metric prefix to `c`, for a "did you mean"
+/// diagnostic.
+fn suggest_prefix(c: char) -> Option<String> {
+    let text = c.to_string();
+    let prefixes: Vec<String> = PREFIX_CHARS.iter().map(|c| c.to_string()).collect();
+    suggest(&text, prefixes.iter().map(String::as_str))
+}
+
 /// Units associated with a quantity.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub struct Units {
@@ -109,8 +190,8 @@ impl Units {
     pub fn multiply(&self, other: &Units) -> Result<Self, UnitError> {
         let (volt, o1) = self.volt.overflowing_add(other.volt);
         let (second, o2) = self.second.overflowing_add(other.second);
-        let (radian, o3) = self.volt.overflowing_add(other.radian);
-        let (decibel, o4) = self.volt.overflowing_add(other.decibel);
+        let (radian, o3) = self.radian.overflowing_add(other.radian);
+        let (decibel, o4) = self.decibel.overflowing_add(other.decibel);
         if o1 || o2 || o3 || o4 {
             Err(UnitError::Overflow)
         } else {
@@ -123,11 +204,67 @@ impl Units {
         }
     }
 
-    /// Parse units with metric prefix.
+    /// Divides two units -- `x.divide(y)` is `x.multiply(&y.inverse()?)`,
+    /// e.g. for the units of a `/` expression.
+    pub fn divide(&self, other: &Units) -> Result<Self, UnitError> {
+        self.multiply(&other.inverse()?)
+    }
+
+    /// Inverts units, negating every dimension -- `x.inverse()` is `x^-1`,
+    /// e.g. for the right-hand side of a `/`.
+    pub fn inverse(&self) -> Result<Self, UnitError> {
+        let volt = self.volt.checked_neg().ok_or(UnitError::Overflow)?;
+        let second = self.second.checked_neg().ok_or(UnitError::Overflow)?;
+        let radian = self.radian.checked_neg().ok_or(UnitError::Overflow)?;
+        let decibel = self.decibel.checked_neg().ok_or(UnitError::Overflow)?;
+        Ok(Units {
+            volt,
+            second,
+            radian,
+            decibel,
+        })
+    }
+
+    /// Raises units to an integer power by exponentiation by squaring --
+    /// `x.powi(2)` is `x.multiply(&x)`, `x.powi(-1)` is `x.inverse()`, and
+    /// `x.powi(0)` is [`Units::scalar`].
+    ///
+    /// This takes O(log `power`) multiplications rather than one per unit
+    /// of `power`: `base`'s dimensions overflow the `i8` fields `multiply`
+    /// checks long before `power` itself gets very large, *except* when
+    /// `base` is dimensionless (e.g. `5^2000000000` in source text, a
+    /// plain number with a huge integer exponent and no unit), where that
+    /// overflow check never fires and a naive repeated-multiply loop would
+    /// spin for up to `power` iterations.
+    pub fn powi(&self, power: i32) -> Result<Self, UnitError> {
+        let (mut base, mut count) = if power < 0 {
+            let count = power.checked_neg().ok_or(UnitError::Overflow)?;
+            (self.inverse()?, count as u32)
+        } else {
+            (*self, power as u32)
+        };
+        let mut result = Units::scalar();
+        while count > 0 {
+            if count & 1 != 0 {
+                result = result.multiply(&base)?;
+            }
+            count >>= 1;
+            if count > 0 {
+                base = base.multiply(&base)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Parse a single unit with an optional metric prefix -- one factor of a
+    /// compound expression parsed by [`Units::parse`].
     ///
     /// Returns the units and the exponent for the metric prefix used. For
     /// example, "ms" will parse as (second, -3), "kV" will parse as (volt, +3).
-    pub fn parse(text: &str, pos: Span) -> Result<(Span, Self, i32), (ParseError, Span)> {
+    fn parse_factor(
+        text: &str,
+        pos: Span,
+    ) -> Result<(Span, Self, i32), (ParseError, Span, Option<String>)> {
         use ParseError::*;
         let mut chars = text.chars();
         let c = match chars.next() {
@@ -140,20 +277,99 @@ impl Units {
         if let Some((allow_prefix, units)) = Units::parse_without_prefix(rest) {
             let exponent = match exponent {
                 Some(x) => x,
-                None => return Err((UnknownPrefix, pos.sub_span(..split_idx))),
+                None => {
+                    return Err((
+                        UnknownPrefix,
+                        pos.sub_span(..split_idx),
+                        suggest_prefix(c),
+                    ))
+                }
             };
             if !allow_prefix {
-                return Err((PrefixNotAllowed, pos));
+                return Err((PrefixNotAllowed, pos, None));
             }
             return Ok((pos.sub_span(split_idx..), units, exponent));
         }
         if exponent.is_some() && !rest.is_empty() {
-            return Err((UnknownUnits, pos.sub_span(split_idx..)));
+            return Err((
+                UnknownUnits,
+                pos.sub_span(split_idx..),
+                suggest_unit_name(rest),
+            ));
         }
         match Units::parse_without_prefix(text) {
             Some((_, units)) => Ok((pos, units, 0)),
-            None => Err((UnknownUnits, pos)),
+            None => Err((UnknownUnits, pos, suggest_unit_name(text))),
+        }
+    }
+
+    /// Parse a compound unit expression: a chain of single prefixed-unit
+    /// factors joined by `*` or `/`, each optionally raised to an integer
+    /// power with `^<int>` -- the same form [`Units`]'s `Display` impl
+    /// prints compound units in, e.g. `V^2*s*rad^-1`. `Display`'s `scalar`
+    /// for dimensionless units is not accepted here.
+    ///
+    /// Returns the combined units and the accumulated exponent for the
+    /// metric prefixes used, each scaled by its own factor's `^` power. For
+    /// example, "V/s" parses as (volt * hertz, 0), and "mV*s^-1" parses as
+    /// (volt * hertz, -3).
+    pub fn parse(
+        text: &str,
+        pos: Span,
+    ) -> Result<(Span, Self, i32), (ParseError, Span, Option<String>)> {
+        use ParseError::*;
+        let mut units = Units::scalar();
+        let mut prefix_exponent = 0i32;
+        let mut offset = 0usize;
+        let mut divide = false;
+        let mut factor_count = 0u32;
+        let mut factor_span = pos.sub_span(..0);
+        loop {
+            let rest = &text[offset..];
+            let op_idx = rest.find(|c| c == '*' || c == '/');
+            let factor_text = match op_idx {
+                Some(i) => &rest[..i],
+                None => rest,
+            };
+            let (unit_text, power) = match factor_text.find('^') {
+                Some(caret) => {
+                    let power_span =
+                        pos.sub_span(offset + caret + 1..offset + factor_text.len());
+                    let power: i32 = match factor_text[caret + 1..].parse() {
+                        Ok(power) => power,
+                        Err(_) => return Err((InvalidExponent, power_span, None)),
+                    };
+                    (&factor_text[..caret], power)
+                }
+                None => (factor_text, 1),
+            };
+            let unit_span = pos.sub_span(offset..offset + unit_text.len());
+            let (unit_span, factor_units, factor_prefix) =
+                Units::parse_factor(unit_text, unit_span)?;
+            let factor_units = factor_units
+                .powi(power)
+                .map_err(|_| (Overflow, unit_span, None))?;
+            let factor_prefix = factor_prefix * power;
+            let combined = if divide {
+                prefix_exponent -= factor_prefix;
+                units.divide(&factor_units)
+            } else {
+                prefix_exponent += factor_prefix;
+                units.multiply(&factor_units)
+            };
+            units = combined.map_err(|_| (Overflow, unit_span, None))?;
+            factor_span = unit_span;
+            factor_count += 1;
+            match op_idx {
+                None => break,
+                Some(i) => {
+                    divide = rest.as_bytes()[i] == b'/';
+                    offset += i + 1;
+                }
+            }
         }
+        let result_span = if factor_count == 1 { factor_span } else { pos };
+        Ok((result_span, units, prefix_exponent))
     }
 
     /// Parse units without metric prefix.
@@ -171,6 +387,42 @@ impl Units {
     }
 }
 
+/// A compound unit expression together with the power-of-ten scale carried
+/// by any metric prefixes used, e.g. "kHz" is `Units::hertz(1)` scaled by
+/// `exponent` `3`. [`Units`] alone can't represent that scale, since it only
+/// tracks the dimensions, not the prefix.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct PrefixedUnits {
+    pub units: Units,
+    pub exponent: i32,
+}
+
+impl FromStr for PrefixedUnits {
+    type Err = ParseError;
+
+    /// Parse a compound unit expression, as [`Units::parse`] does, but
+    /// without a source [`Span`] to report errors against -- for callers
+    /// outside the parser that just want `"kHz".parse::<PrefixedUnits>()`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let pos = Span {
+            start: crate::sourcepos::Pos(0),
+            end: crate::sourcepos::Pos(text.len() as u32),
+        };
+        let (_, units, exponent) = Units::parse(text, pos).map_err(|(e, _, _)| e)?;
+        Ok(PrefixedUnits { units, exponent })
+    }
+}
+
+impl FromStr for Units {
+    type Err = ParseError;
+
+    /// Parse a compound unit expression, discarding any metric prefix scale
+    /// -- use [`PrefixedUnits`] if that scale matters.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.parse::<PrefixedUnits>().map(|p| p.units)
+    }
+}
+
 impl fmt::Display for Units {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fn write_unit(
@@ -218,6 +470,47 @@ mod test {
     use super::*;
     use crate::sourcepos::{Pos, Span};
 
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            Units::volt(1).multiply(&Units::second(-1)),
+            Ok(Units {
+                volt: 1,
+                second: -1,
+                radian: 0,
+                decibel: 0,
+            }),
+        );
+        assert_eq!(
+            Units::volt(1).divide(&Units::second(1)),
+            Ok(Units {
+                volt: 1,
+                second: -1,
+                radian: 0,
+                decibel: 0,
+            }),
+        );
+        assert_eq!(Units::second(1).inverse(), Ok(Units::hertz(1)));
+        assert_eq!(Units::radian(1).powi(2), Ok(Units::radian(2)));
+        assert_eq!(Units::radian(1).powi(-1), Ok(Units::radian(-1)));
+        assert_eq!(Units::radian(1).powi(0), Ok(Units::scalar()));
+        assert_eq!(Units::radian(100).powi(2), Err(UnitError::Overflow));
+        // A dimensionless base never trips `multiply`'s i8-overflow check,
+        // so this only finishes quickly if `powi` is O(log power) rather
+        // than looping `power` times.
+        assert_eq!(Units::scalar().powi(2_000_000_000), Ok(Units::scalar()));
+        assert_eq!(
+            Units {
+                volt: i8::MIN,
+                second: 0,
+                radian: 0,
+                decibel: 0,
+            }
+            .inverse(),
+            Err(UnitError::Overflow),
+        );
+    }
+
     #[test]
     fn display() {
         assert_eq!(Units::default().to_string(), "scalar");
@@ -238,6 +531,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn from_str() {
+        assert_eq!("kHz".parse::<Units>(), Ok(Units::hertz(1)));
+        assert_eq!(
+            "kHz".parse::<PrefixedUnits>(),
+            Ok(PrefixedUnits {
+                units: Units::hertz(1),
+                exponent: 3,
+            }),
+        );
+        assert_eq!("qV".parse::<Units>(), Err(ParseError::UnknownPrefix));
+    }
+
     #[test]
     fn parse() {
         let mut success = true;
@@ -251,6 +557,31 @@ mod test {
             ("mV", Units::volt(1), -3, 1, 2),
             ("kHz", Units::hertz(1), 3, 1, 3),
             ("\u{03BC}s", Units::second(1), -6, 2, 3),
+            (
+                "V/s",
+                Units {
+                    volt: 1,
+                    second: -1,
+                    radian: 0,
+                    decibel: 0,
+                },
+                0,
+                0,
+                3,
+            ),
+            ("rad^2", Units::radian(2), 0, 0, 3),
+            (
+                "mV*s^-1",
+                Units {
+                    volt: 1,
+                    second: -1,
+                    radian: 0,
+                    decibel: 0,
+                },
+                -3,
+                0,
+                7,
+            ),
         ];
         for (n, &(input, units, exponent, start, end)) in cases.iter().enumerate() {
             let offset: u32 = (1 + n as u32) * 100;
@@ -282,15 +613,17 @@ mod test {
     fn parse_fail() {
         let mut success = true;
         use ParseError::*;
-        const CASES: &'static [(&'static str, ParseError, u32, u32)] = &[
-            ("v", UnknownUnits, 0, 1),       // Wrong case, should be V.
-            ("mdB", PrefixNotAllowed, 0, 3), // Prefix not permitted, dB already has prefix.
-            ("kv", UnknownUnits, 1, 2),      // Wrong case, should be kV.
-            ("k", UnknownUnits, 0, 1),       // No units.
-            ("qV", UnknownPrefix, 0, 1),     // Invalid prefix.
-            ("mS", UnknownUnits, 1, 2),      // Unknown units.
+        const CASES: &'static [(&'static str, ParseError, u32, u32, Option<&'static str>)] = &[
+            ("v", UnknownUnits, 0, 1, Some("V")),      // Wrong case, should be V.
+            ("mdB", PrefixNotAllowed, 0, 3, None),     // Prefix not permitted, dB already has prefix.
+            ("kv", UnknownUnits, 1, 2, Some("V")),     // Wrong case, should be kV.
+            ("k", UnknownUnits, 0, 1, None),           // No units, and no unambiguous guess.
+            ("qV", UnknownPrefix, 0, 1, None),         // Invalid prefix, and no unambiguous guess.
+            ("mS", UnknownUnits, 1, 2, Some("s")),     // Unknown units, should be ms.
+            ("V^x", InvalidExponent, 2, 3, None),      // '^' not followed by an integer.
+            ("s^200", Overflow, 0, 1, None),           // Dimension overflows i8 after repeated multiply.
         ];
-        for (n, &(input, err, start, end)) in CASES.iter().enumerate() {
+        for (n, &(input, err, start, end, suggestion)) in CASES.iter().enumerate() {
             let offset: u32 = (1 + n as u32) * 100;
             let in_pos = Span {
                 start: Pos(offset),
@@ -301,7 +634,7 @@ mod test {
                 start: Pos(offset + start),
                 end: Pos(offset + end),
             };
-            let expect = Err((err, expect_pos));
+            let expect = Err((err, expect_pos, suggestion.map(str::to_string)));
             if out != expect {
                 success = false;
                 eprintln!("Test {} failed:", n);
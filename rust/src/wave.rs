@@ -1,12 +1,81 @@
 use crate::rand::Rand;
+use crate::resample::Resampler;
 use std::cmp::min;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
 use std::io::{Result as IOResult, Seek, SeekFrom, Write};
 
-/// Parameters for a WAVE file.
+/// Sample encoding written to the file -- PCM at 16 or 24 bits, or 32-bit
+/// IEEE float (WAVE format tag 3). Dithering only makes sense when
+/// quantizing to a fixed bit depth, so it's skipped entirely for
+/// [`Float32`](Self::Float32).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    Pcm16,
+    Pcm24,
+    Float32,
+}
+
+impl SampleFormat {
+    fn bytes_per_sample(self) -> u32 {
+        match self {
+            SampleFormat::Pcm16 => 2,
+            SampleFormat::Pcm24 => 3,
+            SampleFormat::Float32 => 4,
+        }
+    }
+
+    /// The WAVE `fmt ` chunk's format tag: 1 for PCM, 3 for IEEE float.
+    fn wave_format_tag(self) -> u16 {
+        match self {
+            SampleFormat::Pcm16 | SampleFormat::Pcm24 => 1,
+            SampleFormat::Float32 => 3,
+        }
+    }
+}
+
+/// Output container: WAVE (little-endian) or AIFF (big-endian).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Wave,
+    Aiff,
+}
+
+impl Container {
+    /// Size in bytes of the header reserved at the start of the file before
+    /// sample data, so [`Writer::from_stream`] knows how much space to skip.
+    fn header_size(self) -> usize {
+        match self {
+            Container::Wave => 44,
+            Container::Aiff => 54,
+        }
+    }
+}
+
+/// Dithering applied before quantizing to a fixed bit depth. Ignored for
+/// [`SampleFormat::Float32`], which has no quantization step to dither.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DitherMode {
+    /// A single uniform `0..1` random offset per sample.
+    Rectangular,
+    /// `r1 - r2`, the difference of two independent uniform `0..1` draws --
+    /// a triangular distribution that decorrelates quantization error from
+    /// signal level better than [`Rectangular`](Self::Rectangular).
+    Triangular,
+}
+
+/// Parameters for an output audio file.
 #[derive(Debug, Clone, Copy)]
 pub struct Parameters {
     pub channel_count: u32,
     pub sample_rate: u32,
+    pub sample_format: SampleFormat,
+    pub container: Container,
+    pub dither_mode: DitherMode,
+    /// Feed a fraction of each channel's previous quantization error back
+    /// into the next sample before quantizing, pushing quantization noise
+    /// toward higher frequencies where it's less audible.
+    pub noise_shaping: bool,
 }
 
 trait WriteBytes {
@@ -46,33 +115,120 @@ macro_rules! data {
     });
 }
 
+/// Big-endian counterpart to [`WriteBytes`], for AIFF's big-endian chunks.
+trait WriteBytesBe {
+    fn write_bytes_be(&self, buf: &mut [u8]) -> usize;
+}
+
+impl WriteBytesBe for [u8; 4] {
+    fn write_bytes_be(&self, buf: &mut [u8]) -> usize {
+        buf[..4].copy_from_slice(&self[..]);
+        4
+    }
+}
+
+impl WriteBytesBe for [u8; 10] {
+    fn write_bytes_be(&self, buf: &mut [u8]) -> usize {
+        buf[..10].copy_from_slice(&self[..]);
+        10
+    }
+}
+
+impl WriteBytesBe for u32 {
+    fn write_bytes_be(&self, buf: &mut [u8]) -> usize {
+        buf[..4].copy_from_slice(&self.to_be_bytes()[..]);
+        4
+    }
+}
+
+impl WriteBytesBe for u16 {
+    fn write_bytes_be(&self, buf: &mut [u8]) -> usize {
+        buf[..2].copy_from_slice(&self.to_be_bytes()[..]);
+        2
+    }
+}
+
+/// Big-endian counterpart to [`data!`].
+macro_rules! data_be {
+    ($len:literal, $($type:ty: $value:expr),*,) => ({
+        let mut _arr: [u8; $len] = [0; $len];
+        let mut _pos: usize = 0;
+        $(
+            _pos += <$type>::write_bytes_be(&$value, &mut _arr[_pos..]);
+        )*
+        debug_assert_eq!(_pos, $len);
+        _arr
+    });
+}
+
+/// Encode `sample_rate` as an 80-bit IEEE 754 extended-precision float --
+/// the format AIFF's `COMM` chunk insists on for its sample rate field,
+/// inherited from the old Apple/SANE numeric format. Normalizes the integer
+/// rate so its top bit lands in the explicit-integer-bit position of a
+/// 64-bit mantissa, adjusting the biased exponent (bias 16383, plus 63 for
+/// the mantissa's own scale) to match.
+fn ieee_extended(sample_rate: u32) -> [u8; 10] {
+    let mut mantissa = sample_rate as u64;
+    let mut exponent: u16 = 16383 + 63;
+    if mantissa == 0 {
+        exponent = 0;
+    } else {
+        while mantissa & (1 << 63) == 0 {
+            mantissa <<= 1;
+            exponent -= 1;
+        }
+    }
+    let mut out = [0u8; 10];
+    out[0..2].copy_from_slice(&exponent.to_be_bytes());
+    out[2..10].copy_from_slice(&mantissa.to_be_bytes());
+    out
+}
+
 struct Header {
     frame_count: u32,
     parameters: Parameters,
 }
 
 impl Header {
-    fn to_bytes(&self) -> [u8; 44] {
+    fn to_bytes(&self) -> Vec<u8> {
         let bits_per_byte: u32 = 8;
-        let sample_size_bytes: u32 = 2;
+        let sample_size_bytes: u32 = self.parameters.sample_format.bytes_per_sample();
         let frame_size_bytes: u32 = self.parameters.channel_count * sample_size_bytes;
         let data_length_bytes: u32 = self.frame_count * frame_size_bytes;
-        data![
-            44,
-            [u8;4]: *b"RIFF", // Chunk ID
-            u32: data_length_bytes + 36, // ChunkSize
-            [u8;4]: *b"WAVE", // Format
-            [u8;4]: *b"fmt ", // Subchunk ID
-            u32: 16, // Subchunk size
-            u16: 1, // Format: 1 => PCM
-            u16: self.parameters.channel_count as u16,
-            u32: self.parameters.sample_rate,
-            u32: self.parameters.sample_rate * frame_size_bytes, // Byte rate
-            u16: frame_size_bytes as u16, // Bytes per frame
-            u16: (sample_size_bytes * bits_per_byte) as u16, // Bits per sample
-            [u8;4]: *b"data", // Subchunk ID
-            u32: data_length_bytes, // Subchunk size
-        ]
+        match self.parameters.container {
+            Container::Wave => Vec::from(data![
+                44,
+                [u8;4]: *b"RIFF", // Chunk ID
+                u32: data_length_bytes + 36, // ChunkSize
+                [u8;4]: *b"WAVE", // Format
+                [u8;4]: *b"fmt ", // Subchunk ID
+                u32: 16, // Subchunk size
+                u16: self.parameters.sample_format.wave_format_tag(),
+                u16: self.parameters.channel_count as u16,
+                u32: self.parameters.sample_rate,
+                u32: self.parameters.sample_rate * frame_size_bytes, // Byte rate
+                u16: frame_size_bytes as u16, // Bytes per frame
+                u16: (sample_size_bytes * bits_per_byte) as u16, // Bits per sample
+                [u8;4]: *b"data", // Subchunk ID
+                u32: data_length_bytes, // Subchunk size
+            ]),
+            Container::Aiff => Vec::from(data_be![
+                54,
+                [u8;4]: *b"FORM", // Chunk ID
+                u32: data_length_bytes + 46, // ChunkSize
+                [u8;4]: *b"AIFF", // Format
+                [u8;4]: *b"COMM", // Subchunk ID
+                u32: 18, // Subchunk size
+                u16: self.parameters.channel_count as u16,
+                u32: self.frame_count,
+                u16: (sample_size_bytes * bits_per_byte) as u16, // Bits per sample
+                [u8;10]: ieee_extended(self.parameters.sample_rate),
+                [u8;4]: *b"SSND", // Subchunk ID
+                u32: data_length_bytes + 8, // Subchunk size
+                u32: 0, // Offset
+                u32: 0, // Block size
+            ]),
+        }
     }
 }
 
@@ -86,7 +242,57 @@ where
 {
 }
 
-/// WAVE file writer.
+/// Error-feedback weight for noise shaping: how much of each channel's
+/// previous quantization error is fed back into its next sample. 0.5-1.0 is
+/// the usual range for a simple first-order shaper; much higher and the
+/// feedback starts to ring.
+const SHAPING_WEIGHT: f32 = 0.75;
+
+/// Quantize one sample of `channel` to `scale`'s fixed-point range within
+/// `range`, dithering per `parameters.dither_mode` before truncating. If
+/// `parameters.noise_shaping` is set, folds a fraction of `channel`'s
+/// previous quantization error back in first, and updates that error
+/// from this sample's result.
+///
+/// Takes `rand` and `shaping_error` directly rather than `&mut Writer` so
+/// that [`Writer::encode_samples`] can call this while it already holds a
+/// live borrow of `self.buf` -- a method taking `&mut self` would alias
+/// that borrow, since the borrow checker can't see into the method body
+/// to know it only ever touches these two fields.
+fn dither_quantize(
+    rand: &mut Rand,
+    shaping_error: &mut [f32],
+    parameters: &Parameters,
+    x: f32,
+    channel: usize,
+    scale: f32,
+    range: (i32, i32),
+) -> i32 {
+    let (min, max) = range;
+    let shaped = if parameters.noise_shaping {
+        x + shaping_error[channel]
+    } else {
+        x
+    };
+    let dither = match parameters.dither_mode {
+        DitherMode::Rectangular => rand.next_float(),
+        DitherMode::Triangular => rand.next_float() - rand.next_float(),
+    };
+    let quantized = (shaped * scale + dither).floor();
+    let quantized = if quantized > max as f32 {
+        max
+    } else if quantized < min as f32 {
+        min
+    } else {
+        quantized as i32
+    };
+    if parameters.noise_shaping {
+        shaping_error[channel] = SHAPING_WEIGHT * (shaped - quantized as f32 / scale);
+    }
+    quantized
+}
+
+/// WAVE or AIFF file writer, depending on [`Parameters::container`].
 pub struct Writer<'a> {
     stream: &'a mut dyn SeekWrite,
     buf: Box<[u8]>,
@@ -94,49 +300,116 @@ pub struct Writer<'a> {
     sample_count: usize,
     rand: Rand,
     parameters: Parameters,
+    resampler: Resampler,
+    /// Per-channel running quantization error, for noise shaping.
+    shaping_error: Box<[f32]>,
 }
 
 impl<'a> Writer<'a> {
-    /// Create a WAVE writer from the given stream.
-    pub fn from_stream(stream: &'a mut dyn SeekWrite, parameters: &Parameters) -> Self {
+    /// Create a WAVE writer from the given stream. `in_rate` is the rate
+    /// samples will arrive at through [`write`](Self::write); if it differs
+    /// from `parameters.sample_rate`, samples are converted through a
+    /// [`Resampler`] before being written, so a synth graph can render at
+    /// its own internal rate and still land in a file at any rate.
+    pub fn from_stream(stream: &'a mut dyn SeekWrite, in_rate: u32, parameters: &Parameters) -> Self {
         const BUFFER_SIZE: usize = 32 * 1024;
         let mut buf = Vec::<u8>::new();
         buf.resize(BUFFER_SIZE, 0);
         Writer {
             stream,
             buf: Box::from(buf),
-            buf_pos: 44,
+            buf_pos: parameters.container.header_size(),
             sample_count: 0,
             rand: Rand::with_default_seed(),
             parameters: *parameters,
+            resampler: Resampler::new(
+                parameters.channel_count as usize,
+                in_rate,
+                parameters.sample_rate,
+            ),
+            shaping_error: vec![0.0; parameters.channel_count as usize].into_boxed_slice(),
         }
     }
 
-    /// Write floating-point samples to the file. These samples will be
-    /// converted to 16-bit.
+    /// Write floating-point samples to the file, resampling first if
+    /// `in_rate` (given to [`from_stream`](Self::from_stream)) differs from
+    /// this writer's output sample rate. These samples are converted to
+    /// `parameters.sample_format`.
     pub fn write(&mut self, data: &[f32]) -> IOResult<()> {
+        if self.resampler.is_identity() {
+            return self.encode_samples(data);
+        }
+        let mut resampled = Vec::new();
+        self.resampler
+            .feed(data, |frame| resampled.extend_from_slice(frame));
+        self.encode_samples(&resampled)
+    }
+
+    /// Convert already-output-rate samples to `parameters.sample_format` and
+    /// buffer them up for the stream, flushing whenever the buffer fills.
+    /// Byte order follows `parameters.container`: little-endian for WAVE,
+    /// big-endian for AIFF. Float samples are written as-is, with no
+    /// dithering, since there's no quantization step to dither.
+    fn encode_samples(&mut self, data: &[f32]) -> IOResult<()> {
         let mut data = data;
+        let sample_format = self.parameters.sample_format;
+        let big_endian = self.parameters.container == Container::Aiff;
+        let sample_size = sample_format.bytes_per_sample() as usize;
+        let channel_count = self.parameters.channel_count as usize;
+        let parameters = self.parameters;
         let buf = &mut self.buf[..];
         while !data.is_empty() {
             {
                 let buf = &mut buf[self.buf_pos..];
-                let n = min(data.len(), buf.len() / 2);
+                let n = min(data.len(), buf.len() / sample_size);
                 let (first, rest) = data.split_at(n);
-                for (&x, y) in first.iter().zip(buf.chunks_mut(2)) {
-                    // Random variable with rectangular distribution for dithering.
-                    let r = (self.rand.next() as f32) * (1.0 / 4294967296.0);
-                    let x = (x * 32768.0 + r).floor();
-                    let x = if x > i16::max_value() as f32 {
-                        i16::max_value()
-                    } else if x < i16::min_value() as f32 {
-                        i16::min_value()
-                    } else {
-                        x as i16
-                    };
-                    y.copy_from_slice(&x.to_le_bytes()[..]);
+                let first_channel = self.sample_count % channel_count;
+                for (i, (&x, y)) in first.iter().zip(buf.chunks_mut(sample_size)).enumerate() {
+                    let channel = (first_channel + i) % channel_count;
+                    match sample_format {
+                        SampleFormat::Pcm16 => {
+                            let x = dither_quantize(
+                                &mut self.rand,
+                                &mut self.shaping_error,
+                                &parameters,
+                                x,
+                                channel,
+                                32768.0,
+                                (i16::min_value() as i32, i16::max_value() as i32),
+                            ) as i16;
+                            if big_endian {
+                                y.copy_from_slice(&x.to_be_bytes()[..]);
+                            } else {
+                                y.copy_from_slice(&x.to_le_bytes()[..]);
+                            }
+                        }
+                        SampleFormat::Pcm24 => {
+                            let x = dither_quantize(
+                                &mut self.rand,
+                                &mut self.shaping_error,
+                                &parameters,
+                                x,
+                                channel,
+                                8388608.0,
+                                (-8388608, 8388607),
+                            );
+                            if big_endian {
+                                y.copy_from_slice(&x.to_be_bytes()[1..4]);
+                            } else {
+                                y.copy_from_slice(&x.to_le_bytes()[..3]);
+                            }
+                        }
+                        SampleFormat::Float32 => {
+                            if big_endian {
+                                y.copy_from_slice(&x.to_be_bytes()[..]);
+                            } else {
+                                y.copy_from_slice(&x.to_le_bytes()[..]);
+                            }
+                        }
+                    }
                 }
                 data = rest;
-                self.buf_pos += n * 2;
+                self.buf_pos += n * sample_size;
                 self.sample_count += n;
             }
             if self.buf_pos == buf.len() {
@@ -147,8 +420,14 @@ impl<'a> Writer<'a> {
         Ok(())
     }
 
-    /// Finish writing the file.
-    pub fn finish(self) -> IOResult<()> {
+    /// Finish writing the file, flushing the resampler's remaining taps
+    /// first if resampling is in use.
+    pub fn finish(mut self) -> IOResult<()> {
+        if !self.resampler.is_identity() {
+            let mut resampled = Vec::new();
+            self.resampler.flush(|frame| resampled.extend_from_slice(frame));
+            self.encode_samples(&resampled)?;
+        }
         if self.buf_pos > 0 {
             self.stream.write_all(&self.buf[..self.buf_pos])?;
         }
@@ -161,3 +440,210 @@ impl<'a> Writer<'a> {
         self.stream.write_all(&header[..])
     }
 }
+
+/// Error parsing a RIFF/WAVE byte buffer with [`Reader::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    /// The buffer ended before a field or chunk body could be fully read.
+    Truncated,
+    /// The file didn't start with a `RIFF` chunk ID.
+    BadRiffTag,
+    /// The `RIFF` chunk's format wasn't `WAVE`.
+    BadWaveTag,
+    /// The `data` chunk came before any `fmt ` chunk, so its layout is
+    /// unknown.
+    MissingFmtChunk,
+    /// The file had no `data` chunk.
+    MissingDataChunk,
+    /// `fmt ` chunk's format tag wasn't 1 (PCM).
+    UnsupportedFormat(u16),
+    /// `fmt ` chunk's bits-per-sample wasn't 8, 16, or 24.
+    UnsupportedBitsPerSample(u16),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self {
+            ReadError::Truncated => write!(f, "truncated WAVE file"),
+            ReadError::BadRiffTag => write!(f, "missing RIFF chunk ID"),
+            ReadError::BadWaveTag => write!(f, "RIFF chunk is not WAVE"),
+            ReadError::MissingFmtChunk => write!(f, "data chunk appeared before fmt chunk"),
+            ReadError::MissingDataChunk => write!(f, "missing data chunk"),
+            ReadError::UnsupportedFormat(fmt) => {
+                write!(f, "unsupported WAVE format tag: {} (only PCM is supported)", fmt)
+            }
+            ReadError::UnsupportedBitsPerSample(bits) => {
+                write!(f, "unsupported bits per sample: {}", bits)
+            }
+        }
+    }
+}
+
+impl error::Error for ReadError {}
+
+/// Bounds-checked cursor over WAVE file bytes, returning
+/// [`ReadError::Truncated`] instead of panicking when a read runs past the
+/// end of the buffer -- truncated files are a fact of life for audio assets,
+/// so this makes them an ordinary error instead of an index panic.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Cursor { bytes, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ReadError> {
+        let end = self.pos.checked_add(n).ok_or(ReadError::Truncated)?;
+        if end > self.bytes.len() {
+            return Err(ReadError::Truncated);
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn tag(&mut self) -> Result<[u8; 4], ReadError> {
+        let mut tag = [0u8; 4];
+        tag.copy_from_slice(self.take(4)?);
+        Ok(tag)
+    }
+
+    fn u16(&mut self) -> Result<u16, ReadError> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReadError> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+}
+
+/// Convert a `data` chunk's raw PCM bytes to normalized `f32` samples in
+/// `-1.0..=1.0`, interleaved by channel same as the source bytes.
+fn decode_pcm(data: &[u8], bits_per_sample: u16) -> Result<Box<[f32]>, ReadError> {
+    match bits_per_sample {
+        8 => {
+            // 8-bit PCM is the one unsigned case, centered on 128.
+            Ok(data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect())
+        }
+        16 => {
+            if data.len() % 2 != 0 {
+                return Err(ReadError::Truncated);
+            }
+            Ok(data
+                .chunks_exact(2)
+                .map(|c| i16::from_le_bytes([c[0], c[1]]) as f32 / 32768.0)
+                .collect())
+        }
+        24 => {
+            if data.len() % 3 != 0 {
+                return Err(ReadError::Truncated);
+            }
+            Ok(data
+                .chunks_exact(3)
+                .map(|c| {
+                    let bits = (c[0] as i32) | ((c[1] as i32) << 8) | ((c[2] as i32) << 16);
+                    // Sign-extend the 24-bit value through a 32-bit shift.
+                    let bits = (bits << 8) >> 8;
+                    bits as f32 / 8388608.0
+                })
+                .collect())
+        }
+        bits => Err(ReadError::UnsupportedBitsPerSample(bits)),
+    }
+}
+
+/// WAVE file reader: parses a whole RIFF/WAVE byte buffer up front into its
+/// [`Parameters`] and decoded sample frames.
+pub struct Reader {
+    parameters: Parameters,
+    frames: Box<[f32]>,
+}
+
+impl Reader {
+    /// Parse `bytes` as a RIFF/WAVE file, walking its subchunks and
+    /// converting the `data` chunk's 8/16/24-bit PCM to normalized `f32`s.
+    /// Subchunks other than `fmt ` and `data` (e.g. `LIST`, `fact`) are
+    /// skipped; `fmt ` must come before `data`, since that's the only way to
+    /// know the `data` chunk's sample layout.
+    pub fn parse(bytes: &[u8]) -> Result<Reader, ReadError> {
+        let mut r = Cursor::new(bytes);
+        if r.tag()? != *b"RIFF" {
+            return Err(ReadError::BadRiffTag);
+        }
+        r.u32()?; // RIFF chunk size; the subchunk sizes below are authoritative.
+        if r.tag()? != *b"WAVE" {
+            return Err(ReadError::BadWaveTag);
+        }
+        let mut channel_count: Option<u16> = None;
+        let mut sample_rate: Option<u32> = None;
+        let mut bits_per_sample: Option<u16> = None;
+        let mut frames: Option<Box<[f32]>> = None;
+        while r.remaining() > 0 {
+            let id = r.tag()?;
+            let size = r.u32()? as usize;
+            let body = r.take(size)?;
+            if size % 2 == 1 {
+                r.take(1)?; // Chunks are padded to an even size.
+            }
+            match &id {
+                b"fmt " => {
+                    let mut f = Cursor::new(body);
+                    let format = f.u16()?;
+                    if format != 1 {
+                        return Err(ReadError::UnsupportedFormat(format));
+                    }
+                    channel_count = Some(f.u16()?);
+                    sample_rate = Some(f.u32()?);
+                    f.u32()?; // Byte rate, derivable from the fields above.
+                    f.u16()?; // Block align, likewise derivable.
+                    bits_per_sample = Some(f.u16()?);
+                }
+                b"data" => {
+                    let bits_per_sample = bits_per_sample.ok_or(ReadError::MissingFmtChunk)?;
+                    frames = Some(decode_pcm(body, bits_per_sample)?);
+                }
+                _ => {}
+            }
+        }
+        let channel_count = channel_count.ok_or(ReadError::MissingFmtChunk)? as u32;
+        let sample_rate = sample_rate.ok_or(ReadError::MissingFmtChunk)?;
+        let frames = frames.ok_or(ReadError::MissingDataChunk)?;
+        let sample_format = match bits_per_sample {
+            Some(24) => SampleFormat::Pcm24,
+            _ => SampleFormat::Pcm16,
+        };
+        Ok(Reader {
+            parameters: Parameters {
+                channel_count,
+                sample_rate,
+                sample_format,
+                container: Container::Wave,
+                dither_mode: DitherMode::Rectangular,
+                noise_shaping: false,
+            },
+            frames,
+        })
+    }
+
+    /// The file's channel count and sample rate.
+    pub fn parameters(&self) -> Parameters {
+        self.parameters
+    }
+
+    /// The decoded frames, interleaved by channel the same way the file
+    /// stored them.
+    pub fn frames(&self) -> &[f32] {
+        &self.frames
+    }
+}